@@ -0,0 +1,27 @@
+-- Page-level provenance for documents (PDF text layer pages and OCR markdown pages)
+
+CREATE TABLE document_pages (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    page_number INTEGER, -- NULL when the source had no page markers
+    start_offset INTEGER NOT NULL, -- character offset into documents.extracted_text
+    end_offset INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    needs_ocr INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Where an imported document came from (mistral_ocr_processor output metadata)
+CREATE TABLE document_sources (
+    document_id TEXT PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+    source_file TEXT NOT NULL,
+    markdown_file TEXT NOT NULL UNIQUE,
+    processor TEXT,
+    fallback_method TEXT,
+    processed_date TEXT,
+    metadata TEXT NOT NULL DEFAULT '{}', -- JSON string
+    imported_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_document_pages_document_id ON document_pages(document_id);
+CREATE INDEX idx_document_pages_page_number ON document_pages(document_id, page_number);
@@ -0,0 +1,22 @@
+-- Per-instalment rows extracted from repayment schedules and bank statements
+
+CREATE TABLE payment_schedule_rows (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    document_id TEXT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    page_number INTEGER,
+    row_index INTEGER NOT NULL,
+    due_date TEXT NOT NULL, -- YYYY-MM-DD
+    currency TEXT,
+    instalment_amount REAL,
+    huf_debit REAL,
+    exchange_rate REAL,
+    principal REAL,
+    interest REAL,
+    outstanding_balance REAL,
+    source_offset INTEGER NOT NULL,
+    source_text TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_payment_schedule_rows_document_id ON payment_schedule_rows(document_id);
+CREATE INDEX idx_payment_schedule_rows_due_date ON payment_schedule_rows(due_date);
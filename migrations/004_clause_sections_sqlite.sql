@@ -0,0 +1,3 @@
+-- Location of each extracted clause in the contract's section tree
+
+ALTER TABLE extracted_clauses ADD COLUMN section_path TEXT; -- e.g. "Section 7.3 of Annex 2"
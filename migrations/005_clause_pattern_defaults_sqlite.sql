@@ -0,0 +1,21 @@
+-- The original catch-all multilingual patterns matched every mention of "risk",
+-- "fee" or "CHF". They are superseded by the per-language default patterns
+-- seeded at startup, so deactivate them instead of letting them flood results.
+
+UPDATE clause_patterns
+SET is_active = 0
+WHERE name IN (
+    'Foreign Currency Risk Clause',
+    'Information Disclosure Requirements',
+    'Interest Rate Variation Clause',
+    'Penalty and Fee Clauses'
+);
+
+-- Pattern names become unique. Databases may already hold duplicates, so keep
+-- the row with the lowest id for each name and drop the rest first.
+DELETE FROM clause_patterns
+WHERE id NOT IN (
+    SELECT MIN(id) FROM clause_patterns GROUP BY name
+);
+
+CREATE UNIQUE INDEX idx_clause_patterns_name ON clause_patterns(name);
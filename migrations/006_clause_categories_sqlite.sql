@@ -0,0 +1,7 @@
+-- Overlapping pattern matches are stored as one clause carrying every matched
+-- category and trigger (JSON arrays of strings)
+
+ALTER TABLE extracted_clauses ADD COLUMN categories TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE extracted_clauses ADD COLUMN matched_triggers TEXT NOT NULL DEFAULT '[]';
+
+UPDATE extracted_clauses SET categories = json_array(clause_type) WHERE categories = '[]';
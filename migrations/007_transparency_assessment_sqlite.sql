@@ -0,0 +1,4 @@
+-- How a transparency clause informs the consumer:
+-- adequate_warning, boilerplate_acknowledgement, absent or negated
+
+ALTER TABLE extracted_clauses ADD COLUMN transparency_assessment TEXT;
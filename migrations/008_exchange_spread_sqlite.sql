@@ -0,0 +1,4 @@
+-- Rates and spread extracted from exchange_spread clauses (JSON object:
+-- disbursement_rate, repayment_rate, spread_percent, rate_sources)
+
+ALTER TABLE extracted_clauses ADD COLUMN exchange_spread TEXT;
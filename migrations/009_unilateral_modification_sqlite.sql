@@ -0,0 +1,4 @@
+-- Unilateral modification clauses tested against the principles of
+-- Act XXXVIII of 2014 (JSON object with one check per principle)
+
+ALTER TABLE extracted_clauses ADD COLUMN unilateral_modification TEXT;
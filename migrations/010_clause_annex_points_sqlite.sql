@@ -0,0 +1,4 @@
+-- Directive 93/13/EEC Annex points each clause falls under (JSON array of
+-- {point, title, evidence}); the rules themselves live in data/annex_rules.json
+
+ALTER TABLE extracted_clauses ADD COLUMN annex_points TEXT NOT NULL DEFAULT '[]';
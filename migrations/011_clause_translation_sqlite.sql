@@ -0,0 +1,4 @@
+-- Provenance of english_translation: translation method, date produced and
+-- glossary coverage (JSON object)
+
+ALTER TABLE extracted_clauses ADD COLUMN translation_record TEXT;
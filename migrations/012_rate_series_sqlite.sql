@@ -0,0 +1,22 @@
+-- Dated rate series imported from MNB exports and bank rate sheets: official
+-- and bank buy/sell exchange rates (HUF per 1 unit of currency) and the MNB
+-- base rate (percent per annum, one row per change)
+
+CREATE TABLE rate_series (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    source TEXT NOT NULL, -- MNB or the quoting bank
+    kind TEXT NOT NULL, -- official, bank_buy, bank_sell, base_rate
+    currency TEXT, -- CHF, EUR, JPY, ...; NULL for the base rate
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE UNIQUE INDEX idx_rate_series_key ON rate_series(source, kind, IFNULL(currency, ''));
+
+CREATE TABLE rate_observations (
+    series_id TEXT NOT NULL REFERENCES rate_series(id) ON DELETE CASCADE,
+    rate_date TEXT NOT NULL, -- YYYY-MM-DD
+    value REAL NOT NULL,
+    source_file TEXT,
+    imported_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (series_id, rate_date)
+);
@@ -0,0 +1,11 @@
+-- Names of the default clause patterns seeded so far. The startup seed skips
+-- them, so a default deleted or renamed through the API is not brought back.
+-- Patterns already in the table were seeded under their current name.
+
+CREATE TABLE seeded_clause_patterns (
+    name TEXT PRIMARY KEY,
+    seeded_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO seeded_clause_patterns (name)
+SELECT name FROM clause_patterns;
@@ -0,0 +1,10 @@
+-- The Hungarian disclosure pattern missed the borrower's acknowledgement
+-- ("tudomásul veszi") and the bank's informing ("tájékoztatta", "felhívja a
+-- figyelmét"). The startup seed never rewrites a seeded pattern, so update it
+-- here unless it was edited through the API.
+
+UPDATE clause_patterns
+SET pattern_text = '(?i)(?:tudomásul\s+vesz\w*|tájékoztat\w*.{0,60}kockázat\w*|kockázat\w*.{0,60}tájékoztat\w*|felhívj?\w*\s+.{0,20}figyelm\w*|figyelmeztet\w*|kockázat.*ismertetés|információ.*nyújtás|kockázat.*felvilágosítás)',
+    updated_at = datetime('now')
+WHERE name = 'Risk disclosure (hu)'
+  AND pattern_text = '(?i)(?:tájékoztatás.*kockázat|figyelmeztetés|kockázat.*ismertetés|információ.*nyújtás|kockázat.*felvilágosítás)';
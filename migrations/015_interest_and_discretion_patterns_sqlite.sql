@@ -0,0 +1,21 @@
+-- Widened default patterns that the gold corpus showed missing whole classes:
+-- "oprocentowanie ... jest zmienne", "według decyzji Zarządu Banku" and
+-- "a Bank hirdetményben módosíthatja". Patterns edited through the API are kept.
+
+UPDATE clause_patterns
+SET pattern_text = '(?i)(?:zmienn\w*\s+oprocentowani\w*|oprocentowani\w*.{0,30}\s+zmienn\w*|zmian\w*\s+oprocentowania|stop\w*\s+procentow\w*)',
+    updated_at = datetime('now')
+WHERE name = 'Interest rate variation (pl)'
+  AND pattern_text = '(?i)(?:zmienn\w*\s+oprocentowani\w*|zmian\w*\s+oprocentowania|stop\w*\s+procentow\w*)';
+
+UPDATE clause_patterns
+SET pattern_text = '(?i)(?:(?:bank|hitelező).*jogosult.*egyoldalú|bank.*right.*unilateral|szerződés.*módosítás.*bank|bank\w*\s+.{0,40}módosíthat\w*|contract.*modification.*bank|kizárólag.*bank.*dönt|solely.*bank.*discretion)',
+    updated_at = datetime('now')
+WHERE name = 'Unilateral bank discretion'
+  AND pattern_text = '(?i)(?:(?:bank|hitelező).*jogosult.*egyoldalú|bank.*right.*unilateral|szerződés.*módosítás.*bank|contract.*modification.*bank|kizárólag.*bank.*dönt|solely.*bank.*discretion)';
+
+UPDATE clause_patterns
+SET pattern_text = '(?i)(?:bank\s+(?:jest\s+)?uprawniony\s+do\s+jednostronn\w*|jednostronn\w*\s+zmian\w*|według\s+(?:decyzji|uznania)\s+(?:zarządu\s+)?bank\w*)',
+    updated_at = datetime('now')
+WHERE name = 'Unilateral bank discretion (pl)'
+  AND pattern_text = '(?i)(?:bank\s+(?:jest\s+)?uprawniony\s+do\s+jednostronn\w*|jednostronn\w*\s+zmian\w*)';
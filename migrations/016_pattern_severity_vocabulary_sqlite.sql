@@ -0,0 +1,7 @@
+-- Pattern severities are limited to informational, medium, warning and
+-- critical. Patterns stored with the older "high" severity were treated as
+-- warnings by the extractor, so record them as such.
+
+UPDATE clause_patterns
+SET severity = 'warning', updated_at = datetime('now')
+WHERE severity = 'high';
@@ -193,15 +193,17 @@ class MistralOCRClient:
         try:
             # The Mistral SDK returns an OCRResponse object with pages
             if hasattr(response, 'pages') and response.pages:
-                # Combine all pages' text content
+                # Combine all pages' text content, keeping page boundaries
+                # as <!-- page: N --> markers for the Rust importer
                 combined_text = ""
-                for page in response.pages:
+                for position, page in enumerate(response.pages):
+                    page_number = getattr(page, 'index', position) + 1
                     if hasattr(page, 'markdown'):
-                        combined_text += page.markdown + "\n\n"
+                        combined_text += f"<!-- page: {page_number} -->\n\n" + page.markdown + "\n\n"
                     elif hasattr(page, 'text'):
-                        combined_text += page.text + "\n\n"
+                        combined_text += f"<!-- page: {page_number} -->\n\n" + page.text + "\n\n"
                     elif hasattr(page, 'content'):
-                        combined_text += page.content + "\n\n"
+                        combined_text += f"<!-- page: {page_number} -->\n\n" + page.content + "\n\n"
                 return combined_text.strip() if combined_text else None
             
             # Check for document_annotation attribute
@@ -221,13 +223,14 @@ class MistralOCRClient:
                     response_dict = response.model_dump()
                     if 'pages' in response_dict and response_dict['pages']:
                         combined_text = ""
-                        for page in response_dict['pages']:
+                        for position, page in enumerate(response_dict['pages']):
+                            page_number = page.get('index', position) + 1
                             if 'markdown' in page:
-                                combined_text += page['markdown'] + "\n\n"
+                                combined_text += f"<!-- page: {page_number} -->\n\n" + page['markdown'] + "\n\n"
                             elif 'text' in page:
-                                combined_text += page['text'] + "\n\n"
+                                combined_text += f"<!-- page: {page_number} -->\n\n" + page['text'] + "\n\n"
                             elif 'content' in page:
-                                combined_text += page['content'] + "\n\n"
+                                combined_text += f"<!-- page: {page_number} -->\n\n" + page['content'] + "\n\n"
                         return combined_text.strip() if combined_text else None
                     
                     if 'document_annotation' in response_dict:
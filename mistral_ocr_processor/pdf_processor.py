@@ -261,6 +261,7 @@ class PDFProcessor:
                 metadata={
                     'file_type': file_path.suffix.lower(),
                     'file_size_mb': round(file_path.stat().st_size / (1024 * 1024), 2),
+                    'file_size_bytes': file_path.stat().st_size,
                     'include_images': self.config.include_images,
                     'processor_used': processor_used
                 }
@@ -1,9 +1,12 @@
-use super::mistral_client::{MistralClient, DocumentAnalysis, DamageCalculation, CaseData};
+use super::mistral_client::{MistralClient, DocumentAnalysis, CaseData};
+use crate::damages::{ActualPayment, AmortisationSchedule, ContractRows, DamageAssessment, DamageCalculator, DamageParameters, InterestRatePeriod, LoanTerms, RecordedPayment, RemedyComparison, ScheduleReconciliation};
+use crate::extractors::{OcrMarkdownDocument, OcrMarkdownParser, PaymentTableExtractor};
+use crate::models::PaymentScheduleRow;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
-use std::fs;
 use anyhow::{Result, anyhow};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MultiCaseAnalysis {
@@ -22,6 +25,12 @@ pub struct CaseAnalysis {
     pub payment_history: Vec<PaymentRecord>,
     pub correspondence: Vec<CorrespondenceRecord>,
     pub total_damages: f64,
+    #[serde(default)]
+    pub damage_assessment: Option<DamageAssessment>, // how total_damages was computed
+    #[serde(default)]
+    pub schedules: Vec<ContractSchedule>, // rebuilt on request from the stored rates
+    #[serde(default)]
+    pub statement_payments: Vec<ActualPayment>, // HUF paid per instalment, from the statement tables
     pub case_strength: String,
     pub recommended_actions: Vec<String>,
     pub legal_strategy: String,
@@ -37,11 +46,56 @@ pub struct LoanContract {
     pub start_date: String,
     pub end_date: Option<String>,
     pub fx_risk_disclosure: String, // "excellent", "good", "poor", "none"
-    pub interest_rate: Option<f64>,
+    pub interest_rate: Option<f64>, // percent per annum at signing
+    #[serde(default)]
+    pub interest_rate_history: Vec<InterestRatePeriod>, // later changes, from the bank's notices
+    #[serde(default)]
+    pub term_months: Option<u32>,
+    #[serde(default)]
+    pub disbursement_rate: Option<f64>, // HUF per unit of loan currency
+    #[serde(default)]
+    pub interest_margin: Option<f64>, // percentage points over the reference rate
     pub key_unfair_clauses: Vec<String>,
     pub document_source: String, // filename where this was extracted
 }
 
+impl LoanContract {
+    /// The schedule inputs, when the contract states enough of them. The term
+    /// falls back to the months between the start and end dates.
+    pub fn loan_terms(&self) -> Result<LoanTerms> {
+        let start_date = parse_period_date(&self.start_date)
+            .ok_or_else(|| anyhow!("Start date is not an ISO date: {}", self.start_date))?;
+        let term_months = self.term_months
+            .or_else(|| {
+                let end_date = parse_period_date(self.end_date.as_deref()?)?;
+                let months = (end_date.year() - start_date.year()) * 12 + end_date.month() as i32 - start_date.month() as i32;
+                u32::try_from(months).ok()
+            })
+            .ok_or_else(|| anyhow!("Loan term unknown for {}", self.contract_id))?;
+
+        let mut rate_history = self.interest_rate_history.clone();
+        if let Some(rate) = self.interest_rate {
+            if !rate_history.iter().any(|p| p.effective_from <= start_date) {
+                rate_history.push(InterestRatePeriod { effective_from: start_date, annual_rate: rate });
+            }
+        }
+        if rate_history.is_empty() {
+            return Err(anyhow!("No interest rate known for {}", self.contract_id));
+        }
+        rate_history.sort_by_key(|p| p.effective_from);
+
+        Ok(LoanTerms {
+            principal: self.original_amount,
+            currency: self.currency.to_uppercase(),
+            start_date,
+            term_months,
+            rate_history,
+            disbursement_rate: self.disbursement_rate,
+            margin: self.interest_margin,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PaymentRecord {
     pub bank_name: String,
@@ -54,6 +108,30 @@ pub struct PaymentRecord {
     pub document_source: String,
 }
 
+impl PaymentRecord {
+    /// The record as a dated total, when its period has ISO dates.
+    pub fn recorded_payment(&self) -> Option<RecordedPayment> {
+        Some(RecordedPayment {
+            period_start: parse_period_date(&self.payment_period_start)?,
+            period_end: parse_period_date(&self.payment_period_end)?,
+            currency: self.currency.to_uppercase(),
+            amount: self.total_paid,
+            source: self.document_source.clone(),
+        })
+    }
+}
+
+/// A contract's reconstructed schedule and how the payments on record compare.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContractSchedule {
+    pub contract_id: String,
+    pub rate_series: Option<String>, // the series instalments were converted with
+    pub schedule: AmortisationSchedule,
+    pub reconciliation: ScheduleReconciliation,
+    #[serde(default)]
+    pub remedies: Option<RemedyComparison>, // valued on request for the strategy section
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CorrespondenceRecord {
     pub date: String,
@@ -103,7 +181,8 @@ impl CaseAnalyzer {
         println!("📄 Found {} documents to analyze", documents.len());
 
         // 2. Use AI to analyze documents and extract case information
-        let ai_analysis = self.mistral_client.analyze_documents(&documents).await?;
+        let prompt_texts: Vec<String> = documents.iter().map(|doc| doc.to_prompt_text()).collect();
+        let ai_analysis = self.mistral_client.analyze_documents(&prompt_texts).await?;
         println!("🤖 AI analysis completed");
 
         // 3. Convert AI analysis to structured case data
@@ -114,9 +193,33 @@ impl CaseAnalyzer {
             self.apply_user_overrides(&mut multi_case, overrides)?;
         }
 
-        // 5. Calculate damages for each case
-        for case in &mut multi_case.cases {
-            self.calculate_case_damages(case).await?;
+        // 5. Calculate damages for each case from the payment rows of the documents
+        //    naming its bank; a document naming several of the banks counts for none
+        let banks: Vec<String> = multi_case.cases.iter().map(|c| c.bank_name.trim().to_lowercase()).collect();
+        let mut case_rows: Vec<Vec<PaymentScheduleRow>> = vec![Vec::new(); banks.len()];
+        let mut case_notes: Vec<Vec<String>> = vec![Vec::new(); banks.len()];
+        for doc in &documents {
+            let rows = PaymentTableExtractor::new().extract(Uuid::new_v4(), &doc.text);
+            if rows.is_empty() {
+                continue;
+            }
+            let text = doc.text.to_lowercase();
+            let named: Vec<usize> = (0..banks.len())
+                .filter(|&i| banks.len() == 1 || (!banks[i].is_empty() && text.contains(&banks[i])))
+                .collect();
+            match named.as_slice() {
+                [case] => case_rows[*case].extend(rows),
+                _ => for &case in &named {
+                    case_notes[case].push(format!(
+                        "{} payment row(s) in {} were not counted: the document names more than one of the banks",
+                        rows.len(),
+                        doc.markdown_file
+                    ));
+                },
+            }
+        }
+        for ((case, rows), notes) in multi_case.cases.iter_mut().zip(case_rows).zip(case_notes) {
+            self.calculate_case_damages(case, &rows, notes).await?;
         }
 
         // 6. Calculate total recovery across all cases
@@ -133,38 +236,18 @@ impl CaseAnalyzer {
         Ok(multi_case)
     }
 
-    async fn read_all_documents(&self) -> Result<Vec<String>> {
-        let mut documents = Vec::new();
+    async fn read_all_documents(&self) -> Result<Vec<OcrMarkdownDocument>> {
         let ocr_path = Path::new(&self.ocr_dir);
 
         if !ocr_path.exists() {
             return Err(anyhow!("OCR output directory not found: {}", self.ocr_dir));
         }
 
-        for entry in fs::read_dir(ocr_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if matches!(extension.to_str(), Some("md") | Some("txt") | Some("rtf")) {
-                        if let Ok(content) = fs::read_to_string(&path) {
-                            let filename = path.file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or("unknown");
-                            
-                            documents.push(format!(
-                                "DOCUMENT: {}\n\n{}\n\n",
-                                filename,
-                                content
-                            ));
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(documents)
+        // Prompts keep source file and page markers so extracted figures can be
+        // traced back to "file X, page N"
+        OcrMarkdownParser::new()
+            .parse_directory(ocr_path)
+            .map_err(|e| anyhow!("Failed to read OCR output: {}", e))
     }
 
     async fn structure_case_data(&self, ai_analysis: DocumentAnalysis) -> Result<MultiCaseAnalysis> {
@@ -197,6 +280,10 @@ impl CaseAnalyzer {
                     end_date: None,
                     fx_risk_disclosure: c.fx_risk_disclosure,
                     interest_rate: None,
+                    interest_rate_history: vec![],
+                    term_months: None,
+                    disbursement_rate: None,
+                    interest_margin: None,
                     key_unfair_clauses: c.key_clauses,
                     document_source: "ai_extracted".to_string(),
                 })
@@ -211,7 +298,7 @@ impl CaseAnalyzer {
                     total_paid: p.total_payments,
                     payment_period_start: p.payment_period.split(" to ").next().unwrap_or("").to_string(),
                     payment_period_end: p.payment_period.split(" to ").nth(1).unwrap_or("").to_string(),
-                    overpayment_amount: p.total_payments * 0.3, // Estimate 30% overpayment
+                    overpayment_amount: 0.0, // set from the payment rows by calculate_case_damages
                     exchange_rate_losses: p.exchange_rate_losses,
                     document_source: "ai_extracted".to_string(),
                 })
@@ -237,6 +324,9 @@ impl CaseAnalyzer {
                 payment_history,
                 correspondence,
                 total_damages: 0.0, // Will be calculated later
+                damage_assessment: None,
+                schedules: vec![],
+                statement_payments: vec![],
                 case_strength: ai_analysis.case_strength.clone(),
                 recommended_actions: vec![],
                 legal_strategy: "".to_string(),
@@ -323,6 +413,29 @@ impl CaseAnalyzer {
             "fx_risk_disclosure" => {
                 contract.fx_risk_disclosure = value.to_string();
             },
+            "end_date" => {
+                contract.end_date = Some(value.to_string());
+            },
+            "interest_rate" => {
+                contract.interest_rate = Some(value.parse::<f64>()
+                    .map_err(|_| anyhow!("Invalid number format for interest_rate: {}", value))?);
+            },
+            "term_months" => {
+                contract.term_months = Some(value.parse::<u32>()
+                    .map_err(|_| anyhow!("Invalid number format for term_months: {}", value))?);
+            },
+            "disbursement_rate" => {
+                let rate = value.parse::<f64>()
+                    .map_err(|_| anyhow!("Invalid number format for disbursement_rate: {}", value))?;
+                if !(rate.is_finite() && rate > 0.0) {
+                    return Err(anyhow!("disbursement_rate must be a positive HUF amount per unit: {}", value));
+                }
+                contract.disbursement_rate = Some(rate);
+            },
+            "interest_margin" => {
+                contract.interest_margin = Some(value.parse::<f64>()
+                    .map_err(|_| anyhow!("Invalid number format for interest_margin: {}", value))?);
+            },
             _ => return Err(anyhow!("Unknown contract field: {}", field)),
         }
         Ok(())
@@ -347,67 +460,80 @@ impl CaseAnalyzer {
         Ok(())
     }
 
-    async fn calculate_case_damages(&self, case: &mut CaseAnalysis) -> Result<()> {
-        // Aggregate all loans for this bank
-        let total_loan_amount: f64 = case.loan_contracts.iter()
-            .map(|c| c.original_amount)
-            .sum();
-        
-        let total_payments: f64 = case.payment_history.iter()
-            .map(|p| p.total_paid)
-            .sum();
-
-        // Get primary currency (most common in contracts)
-        let primary_currency = case.loan_contracts.iter()
-            .map(|c| c.currency.as_str())
-            .collect::<std::collections::HashMap<_, usize>>()
-            .into_iter()
-            .max_by_key(|(_, count)| *count)
-            .map(|(currency, _)| currency)
-            .unwrap_or("HUF");
-
-        // Calculate average loan start date
-        let start_date = case.loan_contracts.iter()
-            .map(|c| c.start_date.as_str())
-            .min()
-            .unwrap_or("2006-01-01");
-
-        // Assess FX disclosure quality (worst case across all contracts)
-        let fx_disclosure = case.loan_contracts.iter()
-            .map(|c| match c.fx_risk_disclosure.as_str() {
-                "none" => 0,
-                "poor" => 1,
-                "good" => 2,
-                "excellent" => 3,
-                _ => 1,
-            })
-            .min()
-            .map(|score| match score {
-                0 => "none",
-                1 => "poor",
-                2 => "good",
-                3 => "excellent",
-                _ => "poor",
-            })
-            .unwrap_or("poor");
+    /// Damages come from `DamageCalculator` over the case's payment rows, each
+    /// converted at its contract's disbursement rate; the LLM only writes the
+    /// narrative around the computed figures.
+    async fn calculate_case_damages(&self, case: &mut CaseAnalysis, rows: &[PaymentScheduleRow], notes: Vec<String>) -> Result<()> {
+        let today = Utc::now().date_naive();
+        let mut parameters = DamageParameters::from_env(today);
+        let mut assessment = match case.loan_contracts.as_slice() {
+            [] => DamageCalculator::new(parameters).calculate(rows),
+            contracts => {
+                let contract_rows: Vec<ContractRows> = contracts.iter()
+                    .map(|contract| ContractRows {
+                        contract_id: contract.contract_id.clone(),
+                        disbursement_rate: contract.disbursement_rate,
+                        rows: contract_rows(contracts, contract, rows),
+                    })
+                    .collect();
+                let mut assessment = match contract_rows.as_slice() {
+                    [only] => {
+                        parameters.disbursement_rate = only.disbursement_rate;
+                        DamageCalculator::new(parameters).calculate(&only.rows)
+                    },
+                    _ => DamageCalculator::new(parameters).calculate_contracts(&contract_rows),
+                };
+                let unattributed = rows.len() - contract_rows.iter().map(|c| c.rows.len()).sum::<usize>();
+                if unattributed > 0 {
+                    assessment.notes.push(match contracts {
+                        [contract] => format!(
+                            "{} payment row(s) are not in the contract's currency {} and are not counted",
+                            unattributed,
+                            contract.currency
+                        ),
+                        _ => format!(
+                            "{} payment row(s) could not be attributed to one of the case's {} contracts and are not counted",
+                            unattributed,
+                            contracts.len()
+                        ),
+                    });
+                }
+                assessment
+            },
+        };
+        assessment.notes.extend(notes);
+        case.total_damages = assessment.total_recovery;
+        // Kept apart from the assessment, which a counterfactual recalculation replaces
+        case.statement_payments = assessment.rows.iter()
+            .map(|r| ActualPayment { due_date: r.due_date, huf_paid: r.huf_paid })
+            .collect();
+
+        for payment in &mut case.payment_history {
+            let period = (parse_period_date(&payment.payment_period_start), parse_period_date(&payment.payment_period_end));
+            if let (Some(from), Some(to)) = period {
+                payment.overpayment_amount = assessment.overpayment_between(from, to);
+            }
+        }
 
         let case_data = CaseData {
             bank_name: case.bank_name.clone(),
-            loan_amount: total_loan_amount,
-            currency: primary_currency.to_string(),
-            total_payments,
-            start_date: start_date.to_string(),
-            current_date: Utc::now().format("%Y-%m-%d").to_string(),
-            fx_disclosure_quality: fx_disclosure.to_string(),
+            loan_amount: case.loan_contracts.iter().map(|c| c.original_amount).sum(),
+            currency: case.loan_contracts.first().map(|c| c.currency.clone()).unwrap_or("HUF".to_string()),
+            total_payments: case.payment_history.iter().map(|p| p.total_paid).sum(),
+            start_date: case.loan_contracts.iter().map(|c| c.start_date.as_str()).min().unwrap_or("2006-01-01").to_string(),
+            current_date: today.format("%Y-%m-%d").to_string(),
+            fx_disclosure_quality: case.loan_contracts.first().map(|c| c.fx_risk_disclosure.clone()).unwrap_or("poor".to_string()),
             case_strength: case.case_strength.clone(),
-            estimated_total_damages: 0.0,
+            estimated_total_damages: case.total_damages,
         };
+        match self.mistral_client.describe_damages(&case_data, &assessment).await {
+            Ok(narrative) => assessment.narrative = Some(narrative),
+            Err(e) => println!("⚠️ Damage narrative for {} not generated: {}", case.bank_name, e),
+        }
 
-        let damage_calc = self.mistral_client.calculate_damages(&case_data).await?;
-        case.total_damages = damage_calc.total_recovery;
+        println!("💰 Calculated damages for {} from {} payment rows: {:.0} HUF", case.bank_name, assessment.rows.len(), case.total_damages);
+        case.damage_assessment = Some(assessment);
 
-        println!("💰 Calculated damages for {}: {:.2} HUF", case.bank_name, case.total_damages);
-        
         Ok(())
     }
 
@@ -464,6 +590,25 @@ impl CaseAnalyzer {
     }
 }
 
+/// Payment periods come from the LLM as free text; only ISO dates are used.
+/// The rows for `contract`: those in its currency when no other contract of
+/// the case is in that currency, otherwise none. Rows in another currency, or
+/// with none stated, are never priced at the contract's rate.
+fn contract_rows(contracts: &[LoanContract], contract: &LoanContract, rows: &[PaymentScheduleRow]) -> Vec<PaymentScheduleRow> {
+    let currency = contract.currency.trim();
+    if contracts.iter().filter(|c| c.currency.trim().eq_ignore_ascii_case(currency)).count() > 1 {
+        return Vec::new();
+    }
+    rows.iter()
+        .filter(|r| r.currency.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(currency)))
+        .cloned()
+        .collect()
+}
+
+fn parse_period_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GeneratedDocument {
     pub document_type: String,
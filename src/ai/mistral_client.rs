@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use reqwest::{Client, header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE}};
 use std::env;
 use anyhow::{Result, anyhow};
+use crate::damages::DamageAssessment;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MistralRequest {
@@ -77,10 +78,12 @@ impl MistralClient {
         self.parse_document_analysis(&response)
     }
 
-    pub async fn calculate_damages(&self, case_data: &CaseData) -> Result<DamageCalculation> {
-        let prompt = self.create_damage_calculation_prompt(case_data);
-        let response = self.call_api(&prompt, true).await?;
-        self.parse_damage_calculation(&response)
+    /// Prose explanation of a damage assessment computed by `crate::damages`;
+    /// the figures are passed in and must not be recalculated.
+    pub async fn describe_damages(&self, case_data: &CaseData, assessment: &DamageAssessment) -> Result<String> {
+        let prompt = self.create_damage_narrative_prompt(case_data, assessment)?;
+        let response = self.call_api(&prompt, false).await?;
+        Ok(response.trim().to_string())
     }
 
     pub async fn generate_legal_document(&self, document_type: &str, case_data: &CaseData) -> Result<String> {
@@ -95,6 +98,18 @@ impl MistralClient {
         Ok(response)
     }
 
+    /// English rendering of a contract clause; `glossary` terms are passed as
+    /// fixed renderings so the LLM output uses the firm's terminology.
+    pub async fn translate_clause(&self, text: &str, source_language: &str, glossary: &[(String, String)]) -> Result<String> {
+        let prompt = self.create_translation_prompt(text, source_language, glossary);
+        let response = self.call_api(&prompt, false).await?;
+        Ok(response.trim().to_string())
+    }
+
+    pub fn small_model(&self) -> &str {
+        &self.model_small
+    }
+
     async fn call_api(&self, prompt: &str, use_large_model: bool) -> Result<String> {
         let model = if use_large_model { &self.model_large } else { &self.model_small };
         
@@ -190,59 +205,37 @@ Focus on:
         )
     }
 
-    fn create_damage_calculation_prompt(&self, case_data: &CaseData) -> String {
-        format!(
-            r#"Calculate comprehensive damages for this Hungarian FX mortgage case. Base all calculations on the provided case data and Hungarian legal precedents.
+    fn create_damage_narrative_prompt(&self, case_data: &CaseData, assessment: &DamageAssessment) -> Result<String> {
+        Ok(format!(
+            r#"Write the damages section of a claim in this Hungarian FX mortgage case.
 
 Case Data:
 - Bank: {}
-- Loan Type: {} {}
-- Original Amount: {} {}
+- Loan: {} {} (started: {})
 - Total Payments Made: {} HUF
-- Loan Period: {} to {}
-- FX Risk Disclosure Quality: {}
-
-Calculate the following damage categories in HUF:
-
-1. **Primary Restitution**: Total overpayments due to unfair FX terms
-2. **Lost Interest**: Compound interest on overpayments over the loan period
-3. **Inflation Adjustment**: Purchasing power lost over time
-4. **Opportunity Cost**: What overpayments could have earned if invested
-5. **Credit Rating Damages**: If loan affected other financial opportunities
-6. **Psychological Damages**: Stress and anxiety from unfair treatment
-7. **Broker Liability**: Fees paid for inadequate financial advice
-8. **Legal Costs**: Professional fees for pursuing the claim
-9. **Administrative Costs**: Document preparation, translation, travel
-
-Return calculations in JSON format:
-{{
-  "total_recovery": number,
-  "damage_breakdown": {{
-    "primary_restitution": number,
-    "lost_interest": number,
-    "inflation_adjustment": number,
-    "opportunity_cost": number,
-    "credit_rating_damages": number,
-    "psychological_damages": number,
-    "broker_liability": number,
-    "legal_costs": number,
-    "administrative_costs": number
-  }},
-  "calculation_notes": ["explanation1", "explanation2"],
-  "confidence_level": "high|medium|low"
-}}
 
-Use realistic rates:
-- Interest rates: 5-8% annually for lost interest calculations
-- Inflation: 3-4% annually for purchasing power adjustment
-- Opportunity cost: 6-9% annually for alternative investments"#,
+The damages below were calculated from the payment rows with fixed formulas.
+Explain each category, the formula and the parameters used, and the notes.
+Quote the figures exactly as given. Do not recalculate, round, estimate or add any figure.
+
+Breakdown (HUF):
+{}
+
+Total recovery: {} HUF
+
+Parameters:
+{}
+
+Notes:
+{}"#,
             case_data.bank_name,
-            case_data.loan_amount, case_data.currency,
-            case_data.loan_amount, case_data.currency,
+            case_data.loan_amount, case_data.currency, case_data.start_date,
             case_data.total_payments,
-            case_data.start_date, case_data.current_date,
-            case_data.fx_disclosure_quality
-        )
+            serde_json::to_string_pretty(&assessment.breakdown)?,
+            assessment.total_recovery,
+            serde_json::to_string_pretty(&assessment.parameters)?,
+            assessment.notes.join("\n")
+        ))
     }
 
     fn create_legal_document_prompt(&self, document_type: &str, case_data: &CaseData) -> String {
@@ -331,6 +324,27 @@ Tone should be professional but determined, showing case preparation."#,
         )
     }
 
+    fn create_translation_prompt(&self, text: &str, source_language: &str, glossary: &[(String, String)]) -> String {
+        let terms: Vec<String> = glossary.iter().map(|(source, english)| format!("- {} = {}", source, english)).collect();
+        format!(
+            r#"Translate the following loan contract clause from the language with ISO code "{}" into English.
+
+Use these fixed renderings for legal terms:
+{}
+
+Rules:
+1. Translate faithfully; do not summarize, explain or add anything
+2. Keep clause numbering, amounts, dates and party names as they are
+3. Return only the English text
+
+Clause:
+{}"#,
+            source_language,
+            terms.join("\n"),
+            text
+        )
+    }
+
     fn create_action_steps_prompt(&self, case_data: &CaseData) -> String {
         format!(
             r#"Generate a personalized action plan for this Hungarian FX mortgage case.
@@ -383,12 +397,6 @@ Format as actionable checklist with realistic timelines and specific next steps
         serde_json::from_str(response)
             .map_err(|e| anyhow!("Failed to parse document analysis: {}", e))
     }
-
-    fn parse_damage_calculation(&self, response: &str) -> Result<DamageCalculation> {
-        // Parse JSON response into DamageCalculation struct
-        serde_json::from_str(response)
-            .map_err(|e| anyhow!("Failed to parse damage calculation: {}", e))
-    }
 }
 
 // Data structures for AI analysis
@@ -450,24 +458,3 @@ pub struct DamageRange {
     pub max: f64,
     pub currency: String,
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DamageCalculation {
-    pub total_recovery: f64,
-    pub damage_breakdown: DamageBreakdown,
-    pub calculation_notes: Vec<String>,
-    pub confidence_level: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DamageBreakdown {
-    pub primary_restitution: f64,
-    pub lost_interest: f64,
-    pub inflation_adjustment: f64,
-    pub opportunity_cost: f64,
-    pub credit_rating_damages: f64,
-    pub psychological_damages: f64,
-    pub broker_liability: f64,
-    pub legal_costs: f64,
-    pub administrative_costs: f64,
-}
\ No newline at end of file
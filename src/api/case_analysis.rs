@@ -3,7 +3,12 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use crate::ai::{CaseAnalyzer, MultiCaseAnalysis, UserOverride, GeneratedDocument};
+use crate::ai::{CaseAnalysis, CaseAnalyzer, ContractSchedule, LoanContract, MultiCaseAnalysis, UserOverride, GeneratedDocument};
+use crate::damages::{ActualPayment, AmortisationSchedule, CounterfactualBasis, CounterfactualComparison, DamageCalculator, DamageParameters, RecordedPayment, RemedyComparison, DEFAULT_RECONCILIATION_TOLERANCE};
+use crate::models::RateKind;
+use crate::rates::RateTable;
+use crate::db;
+use sqlx::SqlitePool;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnalysisRequest {
@@ -49,6 +54,16 @@ pub struct OverrideRequest {
     pub reason: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CounterfactualRequest {
+    pub basis: Option<CounterfactualBasis>, // default: contractual_rate
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemedyRequest {
+    pub case_id: Option<String>, // If None, every case
+}
+
 // Shared state for analysis results
 pub type SharedAnalysis = Arc<Mutex<Option<MultiCaseAnalysis>>>;
 
@@ -264,4 +279,292 @@ pub async fn get_current_analysis(
             "message": "No analysis available"
         })))
     }
-}
\ No newline at end of file
+}
+
+/// Rebuilds the instalment schedule of every contract in the current analysis,
+/// converts it to HUF with the stored rates and reconciles it with the payment
+/// records. The results are kept on the analysis.
+pub async fn reconstruct_schedules(
+    pool: web::Data<SqlitePool>,
+    shared_analysis: web::Data<SharedAnalysis>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let mut analysis_guard = shared_analysis.lock().await;
+
+    let Some(analysis) = analysis_guard.as_mut() else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "error": "No case analysis available. Please analyze case first."
+        })));
+    };
+
+    let today = chrono::Utc::now().date_naive();
+    let mut skipped = Vec::new();
+
+    for case in &mut analysis.cases {
+        case.schedules = contract_schedules(pool, case, today, &mut skipped).await;
+    }
+
+    let schedules: Vec<_> = analysis.cases.iter().flat_map(|c| c.schedules.iter()).collect();
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "schedules": schedules,
+        "skipped": skipped
+    })))
+}
+
+/// Recomputes each case's damages against a HUF loan of the disbursed forint
+/// amount, at the contract's own rates or at BUBOR plus the contractual
+/// margin. The instalment-by-instalment table is attached to the assessment.
+pub async fn counterfactual_damages(
+    data: web::Json<CounterfactualRequest>,
+    pool: web::Data<SqlitePool>,
+    shared_analysis: web::Data<SharedAnalysis>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let mut analysis_guard = shared_analysis.lock().await;
+
+    let Some(analysis) = analysis_guard.as_mut() else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "error": "No case analysis available. Please analyze case first."
+        })));
+    };
+
+    let basis = data.basis.unwrap_or_default();
+    let bubor = match bubor_rates(pool).await {
+        Ok(bubor) => bubor,
+        Err(e) => {
+            log::error!("Failed to load BUBOR rates: {}", e);
+            None
+        }
+    };
+    let today = chrono::Utc::now().date_naive();
+    let mut skipped = Vec::new();
+
+    for case in &mut analysis.cases {
+        if case.schedules.is_empty() {
+            case.schedules = contract_schedules(pool, case, today, &mut skipped).await;
+        }
+
+        let parameters = case.damage_assessment.as_ref()
+            .map(|a| a.parameters.clone())
+            .unwrap_or_else(|| DamageParameters::from_env(today));
+
+        let mut notes = Vec::new();
+        let actual = statement_payments(case, &mut notes);
+
+        let comparisons: Vec<CounterfactualComparison> = case.schedules.iter()
+            .filter_map(|s| CounterfactualComparison::build(&s.schedule, &actual, bubor.as_ref(), parameters.valuation_date, basis))
+            .collect();
+        if comparisons.is_empty() {
+            skipped.push(serde_json::json!({ "case_id": case.id, "reason": "No contract schedule with a disbursement rate" }));
+            continue;
+        }
+
+        let mut assessment = DamageCalculator::new(parameters).calculate_against(comparisons);
+        assessment.notes.extend(notes);
+        case.total_damages = assessment.total_recovery;
+        case.damage_assessment = Some(assessment);
+    }
+
+    analysis.total_recovery = analysis.cases.iter().map(|c| c.total_damages).sum();
+
+    let cases: Vec<_> = analysis.cases.iter()
+        .map(|c| serde_json::json!({
+            "case_id": c.id,
+            "total_damages": c.total_damages,
+            "damage_assessment": c.damage_assessment
+        }))
+        .collect();
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "basis": basis,
+        "cases": cases,
+        "total_recovery": analysis.total_recovery,
+        "skipped": skipped
+    })))
+}
+
+/// Values every contract under full invalidity, the 2014-2015 statutory
+/// settlement and conversion, and substitution of the unfair term, side by
+/// side with the assumptions of each. Kept on the contract schedules.
+pub async fn compare_remedies(
+    data: web::Json<RemedyRequest>,
+    pool: web::Data<SqlitePool>,
+    shared_analysis: web::Data<SharedAnalysis>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let mut analysis_guard = shared_analysis.lock().await;
+
+    let Some(analysis) = analysis_guard.as_mut() else {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "success": false,
+            "error": "No case analysis available. Please analyze case first."
+        })));
+    };
+
+    let bubor = match bubor_rates(pool).await {
+        Ok(bubor) => bubor,
+        Err(e) => {
+            log::error!("Failed to load BUBOR rates: {}", e);
+            None
+        }
+    };
+    let today = chrono::Utc::now().date_naive();
+    let mut skipped = Vec::new();
+    let mut results = Vec::new();
+
+    for case in analysis.cases.iter_mut().filter(|c| data.case_id.as_ref().is_none_or(|id| *id == c.id)) {
+        if case.schedules.is_empty() {
+            case.schedules = contract_schedules(pool, case, today, &mut skipped).await;
+        }
+        let valuation_date = case.damage_assessment.as_ref()
+            .map(|a| a.parameters.valuation_date)
+            .unwrap_or(today);
+        let mut notes = Vec::new();
+        let actual = statement_payments(case, &mut notes);
+
+        for contract in &mut case.schedules {
+            let official = match official_rates(pool, &contract.schedule.terms.currency).await {
+                Ok(official) => official,
+                Err(e) => {
+                    log::error!("Failed to load official rates for {}: {}", contract.contract_id, e);
+                    None
+                }
+            };
+            let mut remedies = RemedyComparison::build(&contract.schedule, &actual, official.as_ref(), bubor.as_ref(), valuation_date);
+            remedies.notes.extend(notes.iter().cloned());
+            contract.remedies = Some(remedies);
+        }
+
+        results.push(serde_json::json!({
+            "case_id": case.id,
+            "bank_name": case.bank_name,
+            "contracts": case.schedules.iter()
+                .map(|s| serde_json::json!({ "contract_id": s.contract_id, "remedies": s.remedies }))
+                .collect::<Vec<_>>()
+        }));
+    }
+
+    if results.is_empty() {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "success": false,
+            "error": "Case not found"
+        })));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "cases": results,
+        "skipped": skipped
+    })))
+}
+
+/// Statement rows belong to the case, not to one contract, so they are only
+/// used when the case has a single contract; otherwise a note says so.
+fn statement_payments(case: &CaseAnalysis, notes: &mut Vec<String>) -> Vec<ActualPayment> {
+    if case.statement_payments.is_empty() || case.schedules.len() == 1 {
+        return case.statement_payments.clone();
+    }
+    notes.push(format!(
+        "{} statement payment(s) were not used: the case has {} contracts and the statements are not attributed to one; the schedules at the bank's rates stand in",
+        case.statement_payments.len(),
+        case.schedules.len()
+    ));
+    Vec::new()
+}
+
+/// The payments on record for `contract`: all of them when the case has one
+/// contract, otherwise those with the contract's bank when it is the only
+/// contract with that bank. `None` when they cannot be attributed.
+fn contract_payments(case: &CaseAnalysis, contract: &LoanContract) -> Option<Vec<RecordedPayment>> {
+    let same_bank = |bank: &str| bank.trim().eq_ignore_ascii_case(contract.bank_name.trim());
+    if case.loan_contracts.len() > 1 && case.loan_contracts.iter().filter(|c| same_bank(&c.bank_name)).count() > 1 {
+        return None;
+    }
+    Some(case.payment_history.iter()
+        .filter(|p| case.loan_contracts.len() == 1 || same_bank(&p.bank_name))
+        .filter_map(|p| p.recorded_payment())
+        .collect())
+}
+
+async fn contract_schedules(
+    pool: &SqlitePool,
+    case: &CaseAnalysis,
+    as_of: chrono::NaiveDate,
+    skipped: &mut Vec<serde_json::Value>,
+) -> Vec<ContractSchedule> {
+    let mut schedules = Vec::new();
+
+    for contract in &case.loan_contracts {
+        let terms = match contract.loan_terms() {
+            Ok(terms) => terms,
+            Err(e) => {
+                skipped.push(serde_json::json!({ "contract_id": contract.contract_id, "reason": e.to_string() }));
+                continue;
+            }
+        };
+
+        let (rate_series, rates) = match repayment_rates(pool, contract).await {
+            Ok(Some((label, table))) => (Some(label), Some(table)),
+            Ok(None) => (None, None),
+            Err(e) => {
+                log::error!("Failed to load rates for {}: {}", contract.contract_id, e);
+                (None, None)
+            }
+        };
+
+        let schedule = AmortisationSchedule::reconstruct(terms, rates.as_ref());
+        let reconciliation = match contract_payments(case, contract) {
+            Some(payments) => schedule.reconcile(&payments, as_of, DEFAULT_RECONCILIATION_TOLERANCE),
+            None => schedule.unreconciled(as_of, DEFAULT_RECONCILIATION_TOLERANCE, format!(
+                "The case has more than one contract with {}; the payments on record cannot be attributed to one and were not reconciled",
+                contract.bank_name
+            )),
+        };
+        schedules.push(ContractSchedule {
+            contract_id: contract.contract_id.clone(),
+            rate_series,
+            schedule,
+            reconciliation,
+            remedies: None,
+        });
+    }
+
+    schedules
+}
+
+/// Instalments were debited at the bank's selling rate; the MNB official rate
+/// stands in when the bank's own rate sheet has not been imported.
+async fn repayment_rates(pool: &SqlitePool, contract: &LoanContract) -> Result<Option<(String, RateTable)>, sqlx::Error> {
+    let currency = contract.currency.to_uppercase();
+    let candidates = [
+        (contract.bank_name.as_str(), RateKind::BankSell),
+        ("MNB", RateKind::Official),
+    ];
+
+    for (source, kind) in candidates {
+        let Some(series) = db::rates::find_series(pool, source, kind.as_str(), Some(&currency)).await? else { continue };
+        if let Some(table) = db::rates::load_rate_table(pool, &series).await? {
+            if !table.is_empty() {
+                return Ok(Some((format!("{} {} {}", source, kind.as_str(), currency), table)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+async fn official_rates(pool: &SqlitePool, currency: &str) -> Result<Option<RateTable>, sqlx::Error> {
+    match db::rates::find_series(pool, "MNB", RateKind::Official.as_str(), Some(currency)).await? {
+        Some(series) => db::rates::load_rate_table(pool, &series).await,
+        None => Ok(None),
+    }
+}
+
+async fn bubor_rates(pool: &SqlitePool) -> Result<Option<RateTable>, sqlx::Error> {
+    match db::rates::find_series(pool, "MNB", RateKind::Bubor.as_str(), None).await? {
+        Some(series) => db::rates::load_rate_table(pool, &series).await,
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,254 @@
+use actix_web::{web, HttpResponse, Result};
+use sqlx::SqlitePool;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use crate::models::{ClausePattern, ClausePatternRequest, PATTERN_SEVERITIES};
+use crate::extractors::{ClauseExtractor, AnnexClassifier, evaluate, load_baseline, load_gold_corpus, F1_TOLERANCE};
+use crate::db;
+use serde_json::json;
+
+// Extractor shared by the upload and research handlers, rebuilt whenever patterns change
+pub type SharedClauseExtractor = Arc<RwLock<ClauseExtractor>>;
+
+const PATTERN_TYPES: [&str; 2] = ["regex", "keyword"];
+
+/// Builds the extractor from the active patterns in the database, falling back
+/// to the built-in defaults if they cannot be read. Annex rules are read from
+/// `ANNEX_RULES_PATH` when set, otherwise the bundled rules are used.
+pub async fn load_clause_extractor(pool: &SqlitePool) -> ClauseExtractor {
+    let extractor = match db::clauses::get_active_patterns(pool).await {
+        Ok(patterns) => ClauseExtractor::from_patterns(patterns),
+        Err(e) => {
+            log::error!("Failed to load clause patterns, using defaults: {}", e);
+            ClauseExtractor::new()
+        }
+    };
+
+    let Ok(rules_path) = std::env::var("ANNEX_RULES_PATH") else { return extractor };
+    match AnnexClassifier::load(Path::new(&rules_path)) {
+        Ok(annex) => {
+            log::info!("Loaded {} Annex rules from {}", annex.rule_count(), rules_path);
+            extractor.with_annex_classifier(annex)
+        },
+        Err(e) => {
+            log::error!("Failed to load Annex rules from {}, using bundled rules: {}", rules_path, e);
+            extractor
+        }
+    }
+}
+
+async fn reload(pool: &SqlitePool, extractor: &SharedClauseExtractor) -> usize {
+    let reloaded = load_clause_extractor(pool).await;
+    let count = reloaded.pattern_count();
+    *extractor.write().await = reloaded;
+    log::info!("Clause extractor reloaded with {} active patterns", count);
+    count
+}
+
+fn validate(request: &ClausePatternRequest) -> Result<(), String> {
+    if request.name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if !PATTERN_TYPES.contains(&request.pattern_type.as_str()) {
+        return Err(format!("pattern_type must be one of: {}", PATTERN_TYPES.join(", ")));
+    }
+    if let Some(severity) = &request.severity {
+        if !PATTERN_SEVERITIES.contains(&severity.as_str()) {
+            return Err(format!("severity must be one of: {}", PATTERN_SEVERITIES.join(", ")));
+        }
+    }
+
+    let candidate = ClausePattern::new(request.clone());
+    ClauseExtractor::compile_pattern(&candidate)
+        .map(|_| ())
+        .map_err(|e| format!("pattern_text does not compile: {}", e))
+}
+
+pub async fn get_clause_patterns(
+    pool: web::Data<SqlitePool>,
+) -> Result<HttpResponse> {
+    match db::clauses::get_all_patterns(pool.get_ref()).await {
+        Ok(patterns) => Ok(HttpResponse::Ok().json(json!({
+            "patterns": patterns,
+            "total": patterns.len()
+        }))),
+        Err(e) => {
+            log::error!("Failed to fetch clause patterns: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch clause patterns",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn get_clause_pattern(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let pattern_id = path.into_inner();
+
+    match db::clauses::get_pattern_by_id(pool.get_ref(), pattern_id).await {
+        Ok(Some(pattern)) => Ok(HttpResponse::Ok().json(pattern)),
+        Ok(None) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Clause pattern not found"
+        }))),
+        Err(e) => {
+            log::error!("Failed to fetch clause pattern {}: {}", pattern_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch clause pattern",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn create_clause_pattern(
+    pool: web::Data<SqlitePool>,
+    extractor: web::Data<SharedClauseExtractor>,
+    request: web::Json<ClausePatternRequest>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let request = request.into_inner();
+
+    if let Err(message) = validate(&request) {
+        return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+    }
+
+    match db::clauses::insert_clause_pattern(pool, ClausePattern::new(request)).await {
+        Ok(pattern) => {
+            let active_patterns = reload(pool, extractor.get_ref()).await;
+            Ok(HttpResponse::Created().json(json!({
+                "pattern": pattern,
+                "active_patterns": active_patterns
+            })))
+        },
+        Err(e) => {
+            log::error!("Failed to create clause pattern: {}", e);
+            Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Failed to create clause pattern (names must be unique)",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn update_clause_pattern(
+    pool: web::Data<SqlitePool>,
+    extractor: web::Data<SharedClauseExtractor>,
+    path: web::Path<Uuid>,
+    request: web::Json<ClausePatternRequest>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let pattern_id = path.into_inner();
+    let request = request.into_inner();
+
+    if let Err(message) = validate(&request) {
+        return Ok(HttpResponse::BadRequest().json(json!({ "error": message })));
+    }
+
+    let mut pattern = match db::clauses::get_pattern_by_id(pool, pattern_id).await {
+        Ok(Some(pattern)) => pattern,
+        Ok(None) => return Ok(HttpResponse::NotFound().json(json!({
+            "error": "Clause pattern not found"
+        }))),
+        Err(e) => {
+            log::error!("Failed to fetch clause pattern {}: {}", pattern_id, e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch clause pattern",
+                "details": e.to_string()
+            })));
+        }
+    };
+    pattern.update(request);
+
+    match db::clauses::update_clause_pattern(pool, pattern).await {
+        Ok(pattern) => {
+            let active_patterns = reload(pool, extractor.get_ref()).await;
+            Ok(HttpResponse::Ok().json(json!({
+                "pattern": pattern,
+                "active_patterns": active_patterns
+            })))
+        },
+        Err(e) => {
+            log::error!("Failed to update clause pattern {}: {}", pattern_id, e);
+            Ok(HttpResponse::BadRequest().json(json!({
+                "error": "Failed to update clause pattern",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn delete_clause_pattern(
+    pool: web::Data<SqlitePool>,
+    extractor: web::Data<SharedClauseExtractor>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let pattern_id = path.into_inner();
+
+    match db::clauses::delete_clause_pattern(pool, pattern_id).await {
+        Ok(true) => {
+            let active_patterns = reload(pool, extractor.get_ref()).await;
+            Ok(HttpResponse::Ok().json(json!({
+                "deleted": pattern_id,
+                "active_patterns": active_patterns
+            })))
+        },
+        Ok(false) => Ok(HttpResponse::NotFound().json(json!({
+            "error": "Clause pattern not found"
+        }))),
+        Err(e) => {
+            log::error!("Failed to delete clause pattern {}: {}", pattern_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to delete clause pattern",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+/// Rebuilds the extractor after patterns were changed directly in the database.
+pub async fn reload_clause_patterns(
+    pool: web::Data<SqlitePool>,
+    extractor: web::Data<SharedClauseExtractor>,
+) -> Result<HttpResponse> {
+    let active_patterns = reload(pool.get_ref(), extractor.get_ref()).await;
+    Ok(HttpResponse::Ok().json(json!({
+        "active_patterns": active_patterns
+    })))
+}
+
+/// Scores the active patterns against the gold corpus (`GOLD_CORPUS_PATH`,
+/// default `./data/gold`) and lists classes that fell below its baseline.
+pub async fn evaluate_clause_patterns(
+    extractor: web::Data<SharedClauseExtractor>,
+) -> Result<HttpResponse> {
+    let dir = std::env::var("GOLD_CORPUS_PATH").unwrap_or_else(|_| "./data/gold".to_string());
+    let corpus = match load_gold_corpus(Path::new(&dir)) {
+        Ok(corpus) => corpus,
+        Err(e) => {
+            log::error!("Failed to load gold corpus from {}: {}", dir, e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to load gold corpus",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let report = evaluate(&*extractor.read().await, &corpus).await;
+    let regressions = load_baseline(&Path::new(&dir).join("baseline.json"))
+        .map(|baseline| report.regressions(&baseline, F1_TOLERANCE))
+        .unwrap_or_default();
+    let overall = report.overall();
+
+    Ok(HttpResponse::Ok().json(json!({
+        "documents": report.documents,
+        "classes": report.scores(),
+        "overall": { "precision": overall.precision(), "recall": overall.recall(), "f1": overall.f1() },
+        "regressions": regressions
+    })))
+}
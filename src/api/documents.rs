@@ -1,43 +1,84 @@
 use actix_web::{web, HttpResponse, Result};
 use sqlx::SqlitePool;
 use uuid::Uuid;
-use crate::models::{Document, DocumentUploadRequest, DocumentProcessingResult};
-use crate::extractors::{TextProcessor, LanguageDetector, ClauseExtractor};
+use crate::models::{Document, DocumentPage, DocumentSource, DocumentUploadRequest, DocumentProcessingResult, PaymentScheduleRow};
+use crate::extractors::{ClauseExtractor, TextProcessor, LanguageDetector, OcrMarkdownDocument, OcrMarkdownParser, PaymentTableExtractor, PAGE_SEPARATOR};
 use crate::db;
+use super::clause_patterns::SharedClauseExtractor;
 use serde_json::json;
 
 pub async fn upload_document(
     pool: web::Data<SqlitePool>,
+    extractor: web::Data<SharedClauseExtractor>,
     request: web::Json<DocumentUploadRequest>,
 ) -> Result<HttpResponse> {
     let pool = pool.get_ref();
     let processor = TextProcessor::new();
     let language_detector = LanguageDetector::new();
-    let extractor = ClauseExtractor::new();
+    let extractor = extractor.read().await;
 
-    // Create document record
-    let mut document = Document::new(request.into_inner());
+    // Create document record (keep the payload, the record itself does not store it)
+    let request = request.into_inner();
+    let file_data = request.file_data.clone();
+    let mut document = Document::new(request);
     
     // Process the uploaded file
-    match processor.process_document(&document.filename, &document.content_type).await {
+    match processor.process_document(&file_data, &document.content_type).await {
         Ok(processing_result) => {
             document.extracted_text = Some(processing_result.extracted_text.clone());
             document.processing_status = "processing".to_string();
+
+            // Report scanned pages explicitly instead of storing silent gaps
+            let ocr_pages = processing_result.pages_needing_ocr();
+            if !ocr_pages.is_empty() {
+                log::warn!("{}: {} page(s) without text layer", document.filename, ocr_pages.len());
+                document.error_message = Some(format!(
+                    "Pages without text layer (needs OCR): {}",
+                    ocr_pages.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+                ));
+                if ocr_pages.len() == processing_result.pages.len() {
+                    document.processing_status = "needs_ocr".to_string();
+                }
+            }
             
             // Detect language if not provided
             if document.language == "unknown" {
-                let detection = language_detector.detect_language(&processing_result.extracted_text);
-                document.language = detection.language;
+                document.language = match &processing_result.language_detected {
+                    Some(language) => language.clone(),
+                    None => language_detector.detect_language(&processing_result.extracted_text).language,
+                };
             }
             
             // Save document to database
             match db::documents::insert_document(pool, document.clone()).await {
                 Ok(saved_doc) => {
-                    // Extract clauses asynchronously
+                    // Store page boundaries (character offsets into extracted_text)
+                    let mut stored_pages = Vec::new();
+                    let mut offset = 0i32;
+                    for page in &processing_result.pages {
+                        let length = page.text.chars().count() as i32;
+                        let record = DocumentPage::new(
+                            saved_doc.id,
+                            Some(page.page_number as i32),
+                            offset,
+                            offset + length,
+                            page.text.clone(),
+                            page.needs_ocr,
+                        );
+                        match db::documents::insert_document_page(pool, record).await {
+                            Ok(stored) => stored_pages.push(stored),
+                            Err(e) => log::error!("Failed to save page {}: {}", page.page_number, e),
+                        }
+                        offset += length + PAGE_SEPARATOR.len_utf8() as i32;
+                    }
+
+                    // Extract clauses asynchronously, each language span with its own patterns
                     if let Some(text) = &saved_doc.extracted_text {
-                        let clause_result = extractor.extract_clauses(
-                            saved_doc.id, 
-                            text, 
+                        let spans = language_detector.detect_mixed_language(text);
+                        let clause_result = extractor.extract_clauses_by_span(
+                            saved_doc.id,
+                            text,
+                            &spans,
                             &saved_doc.language
                         ).await;
                         
@@ -48,11 +89,22 @@ pub async fn upload_document(
                             }
                         }
                         
-                        // Update document status
+                        store_payment_schedule(pool, saved_doc.id, text, |offset| {
+                            stored_pages.iter()
+                                .find(|p| p.contains_offset(offset))
+                                .and_then(|p| p.page_number)
+                        }).await;
+
+                        // Update document status (scanned documents stay flagged for OCR)
+                        let final_status = if saved_doc.processing_status == "needs_ocr" {
+                            "needs_ocr"
+                        } else {
+                            "completed"
+                        };
                         let _ = db::documents::update_document_status(
                             pool, 
                             saved_doc.id, 
-                            "completed"
+                            final_status
                         ).await;
                         
                         let response = DocumentProcessingResult {
@@ -160,4 +212,208 @@ pub struct DocumentQuery {
     pub limit: Option<i32>,
     pub offset: Option<i32>,
     pub status: Option<String>,
-}
\ No newline at end of file
+}
+
+/// Imports the markdown written by mistral_ocr_processor from OCR_OUTPUT_DIR
+/// into the documents table, one page record per OCR page, and runs the same
+/// clause and payment schedule extraction as uploads. Files that were
+/// imported before are skipped.
+pub async fn import_ocr_output(
+    pool: web::Data<SqlitePool>,
+    extractor: web::Data<SharedClauseExtractor>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let ocr_dir = std::env::var("OCR_OUTPUT_DIR")
+        .unwrap_or_else(|_| "./ocr_output".to_string());
+    let language_detector = LanguageDetector::new();
+    let extractor = extractor.read().await;
+
+    let parsed = match OcrMarkdownParser::new().parse_directory(std::path::Path::new(&ocr_dir)) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            log::error!("Failed to read OCR output directory {}: {}", ocr_dir, e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to read OCR output directory",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    let mut failed = Vec::new();
+
+    for ocr_doc in parsed {
+        match import_ocr_document(pool, &extractor, &language_detector, &ocr_doc).await {
+            Ok(Some(summary)) => imported.push(summary),
+            Ok(None) => skipped.push(ocr_doc.markdown_file.clone()),
+            Err(e) => {
+                log::error!("Failed to import {}: {}", ocr_doc.markdown_file, e);
+                failed.push(json!({ "markdown_file": ocr_doc.markdown_file, "error": e.to_string() }));
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "imported": imported,
+        "skipped": skipped,
+        "failed": failed,
+        "ocr_output_dir": ocr_dir
+    })))
+}
+
+/// Stores one OCR document with its pages, clauses, payment rows and source
+/// record in a single transaction, so a failed import leaves nothing behind
+/// and is retried on the next run. Returns None if the file was imported before.
+async fn import_ocr_document(
+    pool: &SqlitePool,
+    extractor: &ClauseExtractor,
+    language_detector: &LanguageDetector,
+    ocr_doc: &OcrMarkdownDocument,
+) -> Result<Option<serde_json::Value>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    if db::documents::get_document_source_by_markdown_file(&mut *tx, &ocr_doc.markdown_file).await?.is_some() {
+        return Ok(None);
+    }
+
+    let mut document = Document::new(DocumentUploadRequest {
+        filename: ocr_doc.source_name().to_string(),
+        content_type: ocr_doc.metadata.content_type().to_string(),
+        file_data: String::new(),
+        document_type: "unclassified".to_string(),
+        language: None,
+        client_id: None,
+        case_reference: None,
+    });
+    document.language = language_detector.detect_language(&ocr_doc.text).language;
+    document.extracted_text = Some(ocr_doc.text.clone());
+    document.file_size = ocr_doc.metadata.file_size().unwrap_or(0);
+    document.processing_status = "completed".to_string();
+
+    let saved_doc = db::documents::insert_document(&mut *tx, document).await?;
+
+    for segment in &ocr_doc.pages {
+        let page = DocumentPage::new(
+            saved_doc.id,
+            segment.page_number.map(|n| n as i32),
+            segment.start as i32,
+            segment.end as i32,
+            ocr_doc.page_text(segment),
+            false,
+        );
+        db::documents::insert_document_page(&mut *tx, page).await?;
+    }
+
+    // Same clause extraction as uploads, each language span with its own patterns
+    let spans = language_detector.detect_mixed_language(&ocr_doc.text);
+    let clause_result = extractor.extract_clauses_by_span(
+        saved_doc.id,
+        &ocr_doc.text,
+        &spans,
+        &saved_doc.language
+    ).await;
+    let clause_count = clause_result.clauses.len();
+    for clause in clause_result.clauses {
+        db::clauses::insert_extracted_clause(&mut *tx, clause).await?;
+    }
+
+    let schedule_rows = payment_schedule_rows(saved_doc.id, &ocr_doc.text, |offset| {
+        ocr_doc.page_at(offset as usize).map(|n| n as i32)
+    });
+    for row in &schedule_rows {
+        db::payments::insert_payment_schedule_row(&mut *tx, row.clone()).await?;
+    }
+
+    let source = DocumentSource {
+        document_id: saved_doc.id,
+        source_file: ocr_doc.source_name().to_string(),
+        markdown_file: ocr_doc.markdown_file.clone(),
+        processor: ocr_doc.metadata.processor_used.clone().or(ocr_doc.metadata.processor.clone()),
+        fallback_method: ocr_doc.metadata.fallback_method().map(|m| m.to_string()),
+        processed_date: ocr_doc.metadata.processed_date.clone(),
+        metadata: serde_json::to_value(&ocr_doc.metadata).unwrap_or_default(),
+        imported_at: chrono::Utc::now(),
+    };
+    db::documents::insert_document_source(&mut *tx, source).await?;
+
+    tx.commit().await?;
+
+    Ok(Some(json!({
+        "document_id": saved_doc.id,
+        "markdown_file": ocr_doc.markdown_file,
+        "source_file": ocr_doc.source_name(),
+        "pages": ocr_doc.pages.len(),
+        "clauses": clause_count,
+        "payment_rows": schedule_rows.len()
+    })))
+}
+
+/// Repayment schedule rows in the document text, each with the page its
+/// character offset falls on.
+fn payment_schedule_rows<F>(document_id: Uuid, text: &str, page_of: F) -> Vec<PaymentScheduleRow>
+where
+    F: Fn(i32) -> Option<i32>,
+{
+    PaymentTableExtractor::new().extract(document_id, text)
+        .into_iter()
+        .map(|mut row| {
+            row.page_number = page_of(row.source_offset);
+            row
+        })
+        .collect()
+}
+
+/// Extracts repayment schedule rows from the document text and stores them.
+/// Returns the number of rows stored.
+async fn store_payment_schedule<F>(pool: &SqlitePool, document_id: Uuid, text: &str, page_of: F) -> usize
+where
+    F: Fn(i32) -> Option<i32>,
+{
+    let mut stored = 0;
+    for row in payment_schedule_rows(document_id, text, page_of) {
+        match db::payments::insert_payment_schedule_row(pool, row).await {
+            Ok(_) => stored += 1,
+            Err(e) => log::error!("Failed to save payment row for document {}: {}", document_id, e),
+        }
+    }
+    stored
+}
+
+pub async fn get_document_payments(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let document_id = path.into_inner();
+
+    match db::payments::get_payment_schedule_by_document(pool, document_id).await {
+        Ok(rows) => Ok(HttpResponse::Ok().json(rows)),
+        Err(e) => {
+            log::error!("Failed to fetch payment schedule for document {}: {}", document_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch payment schedule",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+pub async fn get_document_pages(
+    pool: web::Data<SqlitePool>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let document_id = path.into_inner();
+
+    match db::documents::get_pages_by_document(pool, document_id).await {
+        Ok(pages) => Ok(HttpResponse::Ok().json(pages)),
+        Err(e) => {
+            log::error!("Failed to fetch pages for document {}: {}", document_id, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch document pages",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
@@ -3,9 +3,13 @@ pub mod cases;
 pub mod research;
 pub mod health;
 pub mod case_analysis;
+pub mod clause_patterns;
+pub mod rates;
 
 pub use documents::*;
 pub use cases::*;
 pub use research::*;
 pub use health::*;
-pub use case_analysis::*;
\ No newline at end of file
+pub use case_analysis::*;
+pub use clause_patterns::*;
+pub use rates::*;
\ No newline at end of file
@@ -0,0 +1,223 @@
+use actix_web::{web, HttpResponse, Result};
+use chrono::{NaiveDate, Utc};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use crate::models::{RateKind, RateLookup, RateObservation, RateSeries};
+use crate::rates::MnbRateImporter;
+use crate::db;
+use serde_json::json;
+
+pub async fn get_rate_series(
+    pool: web::Data<SqlitePool>,
+) -> Result<HttpResponse> {
+    match db::rates::get_all_series(pool.get_ref()).await {
+        Ok(series) => Ok(HttpResponse::Ok().json(json!({
+            "series": series,
+            "total": series.len()
+        }))),
+        Err(e) => {
+            log::error!("Failed to fetch rate series: {}", e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch rate series",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+/// The rate of a series on a given day. Weekends and holidays get the last
+/// published rate, flagged as a fallback.
+pub async fn lookup_rate(
+    pool: web::Data<SqlitePool>,
+    query: web::Query<RateLookupQuery>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+
+    let Some(kind) = RateKind::parse(&query.kind) else {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": format!("Unknown rate kind: {}", query.kind)
+        })));
+    };
+    let source = query.source.as_deref().unwrap_or("MNB");
+    let currency = query.currency.as_deref().map(str::to_uppercase);
+
+    let series = match db::rates::find_series(pool, source, kind.as_str(), currency.as_deref()).await {
+        Ok(Some(series)) => series,
+        Ok(None) => return Ok(HttpResponse::NotFound().json(json!({
+            "error": "Rate series not found"
+        }))),
+        Err(e) => {
+            log::error!("Failed to fetch rate series: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch rate series",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    match db::rates::get_observation_on_or_before(pool, series.id, query.date).await {
+        Ok(observation) => {
+            let latest = observation.map(|o| (o.rate_date, o.value));
+            match RateLookup::resolve(kind, query.date, latest) {
+                Some(lookup) => Ok(HttpResponse::Ok().json(json!({
+                    "series": series,
+                    "requested_date": lookup.requested_date,
+                    "rate_date": lookup.rate_date,
+                    "value": lookup.value,
+                    "fallback": lookup.is_fallback()
+                }))),
+                None => Ok(HttpResponse::NotFound().json(json!({
+                    "error": format!("No rate published on or shortly before {}", query.date)
+                }))),
+            }
+        },
+        Err(e) => {
+            log::error!("Failed to look up rate for {}: {}", query.date, e);
+            Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to look up rate",
+                "details": e.to_string()
+            })))
+        }
+    }
+}
+
+/// Imports every CSV and XLSX file in `RATES_DIR` (default ./data/rates), or
+/// the one named in the request. Re-importing a file replaces its values.
+///
+/// Each file is stored under its own source: the part of the file name before
+/// the first "_" ("MNB_2010.csv", "OTP Bank_CHF.xlsx"), which for a bank sheet
+/// has to match the contract's bank name. A single named file may be given an
+/// explicit source instead.
+pub async fn import_rates(
+    pool: web::Data<SqlitePool>,
+    request: web::Json<RateImportRequest>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let rates_dir = PathBuf::from(std::env::var("RATES_DIR").unwrap_or_else(|_| "./data/rates".to_string()));
+
+    let files = match &request.file {
+        Some(name) => {
+            let path = Path::new(name);
+            if path.components().count() != 1 || path.file_name().is_none() {
+                return Ok(HttpResponse::BadRequest().json(json!({
+                    "error": "file must be the name of a file in the rates directory"
+                })));
+            }
+            vec![rates_dir.join(path)]
+        }
+        None if request.source.is_some() => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "source can only be given together with file"
+            })));
+        }
+        None => match rate_files(&rates_dir) {
+            Ok(files) => files,
+            Err(e) => {
+                log::error!("Failed to read rates directory {}: {}", rates_dir.display(), e);
+                return Ok(HttpResponse::InternalServerError().json(json!({
+                    "error": "Failed to read rates directory",
+                    "details": e.to_string()
+                })));
+            }
+        },
+    };
+
+    let importer = MnbRateImporter::new();
+    let mut imported = Vec::new();
+    let mut failed = Vec::new();
+
+    for file in files {
+        let file_name = file.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let source = request.source.clone().unwrap_or_else(|| file_source(&file));
+        let series = match importer.import_file(&file) {
+            Ok(series) => series,
+            Err(e) => {
+                log::error!("Failed to import rates from {}: {}", file.display(), e);
+                failed.push(json!({ "file": file_name, "error": e.to_string() }));
+                continue;
+            }
+        };
+
+        match store_series(pool, &source, &file_name, series).await {
+            Ok(counts) => imported.push(json!({ "file": file_name, "source": source, "series": counts })),
+            Err(e) => {
+                log::error!("Failed to store rates from {}: {}", file.display(), e);
+                failed.push(json!({ "file": file_name, "error": e.to_string() }));
+            }
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "imported": imported,
+        "failed": failed
+    })))
+}
+
+fn rate_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+            extension == "csv" || extension == "xlsx"
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// "OTP Bank_CHF.xlsx" is stored as source "OTP Bank"; a name without "_" is
+/// taken whole.
+fn file_source(file: &Path) -> String {
+    let stem = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    stem.split('_').next().unwrap_or_default().trim().to_string()
+}
+
+/// A file is stored whole or not at all.
+async fn store_series(
+    pool: &SqlitePool,
+    source: &str,
+    file_name: &str,
+    series: Vec<crate::rates::ImportedSeries>,
+) -> Result<Vec<serde_json::Value>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut counts = Vec::new();
+    for imported in series {
+        let stored = db::rates::get_or_insert_series(
+            &mut tx,
+            RateSeries::new(source, imported.kind, imported.currency.as_deref()),
+        ).await?;
+
+        let imported_at = Utc::now();
+        for (rate_date, value) in &imported.values {
+            db::rates::upsert_observation(&mut *tx, &RateObservation {
+                series_id: stored.id,
+                rate_date: *rate_date,
+                value: *value,
+                source_file: Some(file_name.to_string()),
+                imported_at,
+            }).await?;
+        }
+
+        counts.push(json!({
+            "kind": stored.kind,
+            "currency": stored.currency,
+            "observations": imported.values.len()
+        }));
+    }
+    tx.commit().await?;
+    Ok(counts)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RateLookupQuery {
+    pub source: Option<String>,
+    pub kind: String,
+    pub currency: Option<String>,
+    pub date: NaiveDate,
+}
+
+#[derive(serde::Deserialize)]
+pub struct RateImportRequest {
+    pub file: Option<String>,
+    pub source: Option<String>,
+}
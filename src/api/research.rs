@@ -1,10 +1,12 @@
 use actix_web::{web, HttpResponse, Result};
 use sqlx::SqlitePool;
 use uuid::Uuid;
-use crate::models::{ClauseExtractionRequest, DraftPleadingRequest, ResearchRequest, ResearchSheet, LegalFindings, GeneratedPleading};
-use crate::extractors::{ClauseExtractor, LanguageDetector};
+use crate::models::{ClauseExtractionRequest, DraftPleadingRequest, ResearchRequest, ResearchSheet, LegalFindings, GeneratedPleading, ExtractedClause, TransparencyChecklist};
+use crate::extractors::{ChecklistSource, LanguageDetector, TransparencyChecklistEngine};
 use crate::matching::PrecedentMatcher;
+use crate::translation::{GlossaryTranslator, LlmTranslator, Translator};
 use crate::db;
+use super::clause_patterns::SharedClauseExtractor;
 use serde_json::json;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +17,18 @@ pub struct ExtractClausesRequest {
     pub extraction_method: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct TranslateClausesRequest {
+    pub document_id: Uuid,
+    pub method: Option<String>, // glossary (default) or llm
+    pub overwrite: Option<bool>, // retranslate clauses that already have a translation
+}
+
+#[derive(Deserialize)]
+pub struct TransparencyChecklistRequest {
+    pub document_id: Uuid,
+}
+
 #[derive(Deserialize)]
 pub struct MatchPrecedentsRequest {
     pub document_id: Uuid,
@@ -31,31 +45,27 @@ pub struct GenerateDraftRequest {
 
 pub async fn extract_clauses(
     pool: web::Data<SqlitePool>,
+    extractor: web::Data<SharedClauseExtractor>,
     request: web::Json<ExtractClausesRequest>,
 ) -> Result<HttpResponse> {
     let pool = pool.get_ref();
-    let extractor = ClauseExtractor::new();
+    let extractor = extractor.read().await;
     let language_detector = LanguageDetector::new();
 
     // Get the document
     match db::documents::get_document_by_id(pool, request.document_id).await {
         Ok(Some(document)) => {
             if let Some(text) = &document.extracted_text {
-                // Detect language if not provided
-                let language = request.language.as_deref().unwrap_or(&document.language);
-                let detected_language = if language == "unknown" {
-                    language_detector.detect_language(text).language
-                } else {
-                    language.to_string()
+                // An explicit language applies to the whole text; otherwise each
+                // language span is extracted with its own patterns
+                let extraction_result = match request.language.as_deref() {
+                    Some(language) => extractor.extract_clauses(document.id, text, language).await,
+                    None => {
+                        let spans = language_detector.detect_mixed_language(text);
+                        extractor.extract_clauses_by_span(document.id, text, &spans, &document.language).await
+                    },
                 };
 
-                // Extract clauses
-                let extraction_result = extractor.extract_clauses(
-                    document.id, 
-                    text, 
-                    &detected_language
-                ).await;
-
                 // Save extracted clauses to database
                 for clause in &extraction_result.clauses {
                     if let Err(e) = db::clauses::insert_extracted_clause(pool, clause.clone()).await {
@@ -67,6 +77,7 @@ pub async fn extract_clauses(
                     "document_id": document.id,
                     "extracted_clauses": extraction_result.clauses,
                     "language_detected": extraction_result.language_detected,
+                    "language_spans": extraction_result.language_spans,
                     "confidence_score": extraction_result.confidence,
                     "total_clauses": extraction_result.clauses.len()
                 })))
@@ -91,6 +102,162 @@ pub async fn extract_clauses(
     }
 }
 
+/// The bundled glossary, or the edited copy at `LEGAL_GLOSSARY_PATH` when set.
+fn load_glossary() -> GlossaryTranslator {
+    let Ok(path) = std::env::var("LEGAL_GLOSSARY_PATH") else { return GlossaryTranslator::new() };
+    GlossaryTranslator::load(std::path::Path::new(&path)).unwrap_or_else(|e| {
+        log::error!("Failed to load glossary from {}, using bundled glossary: {}", path, e);
+        GlossaryTranslator::new()
+    })
+}
+
+/// Fills `english_translation` for the document's clauses and records the
+/// method and date of each translation. English clauses are skipped.
+pub async fn translate_clauses(
+    pool: web::Data<SqlitePool>,
+    request: web::Json<TranslateClausesRequest>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+    let translator: Box<dyn Translator> = match request.method.as_deref().unwrap_or("glossary") {
+        "glossary" => Box::new(load_glossary()),
+        "llm" => match LlmTranslator::new(load_glossary()) {
+            Ok(translator) => Box::new(translator),
+            Err(e) => {
+                return Ok(HttpResponse::ServiceUnavailable().json(json!({
+                    "error": e.to_string()
+                })));
+            }
+        },
+        other => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": format!("Unknown translation method '{}'; use glossary or llm", other)
+            })));
+        }
+    };
+
+    let clauses = match db::clauses::get_clauses_by_document(pool, request.document_id).await {
+        Ok(clauses) => clauses,
+        Err(e) => {
+            log::error!("Failed to fetch clauses: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch clauses for document",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let overwrite = request.overwrite.unwrap_or(false);
+    let mut translated = Vec::new();
+    let mut skipped = Vec::new();
+    for clause in clauses {
+        if clause.original_language == "en" || (clause.english_translation.is_some() && !overwrite) {
+            skipped.push(json!({ "clause_id": clause.id, "reason": "already in English or translated" }));
+            continue;
+        }
+
+        match translator.translate(&clause.clause_text, &clause.original_language).await {
+            Ok(translation) => {
+                let record = translation.record();
+                if let Err(e) = db::clauses::update_clause_translation(pool, clause.id, &translation.text, &record).await {
+                    log::error!("Failed to save translation of clause {}: {}", clause.id, e);
+                    continue;
+                }
+                translated.push(json!({
+                    "clause_id": clause.id,
+                    "english_translation": translation.text,
+                    "translation_record": record
+                }));
+            },
+            Err(e) => skipped.push(json!({ "clause_id": clause.id, "reason": e.to_string() })),
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "document_id": request.document_id,
+        "method": translator.method(),
+        "translated": translated,
+        "skipped": skipped
+    })))
+}
+
+/// Runs the transparency checklist over the document's clauses and the
+/// correspondence filed under the same client.
+async fn build_transparency_checklist(pool: &SqlitePool, document_id: Uuid, clauses: &[ExtractedClause]) -> TransparencyChecklist {
+    let client_id = match db::documents::get_document_by_id(pool, document_id).await {
+        Ok(Some(document)) => document.client_id,
+        _ => None,
+    };
+    let correspondence = match client_id {
+        Some(client_id) => db::documents::get_documents_by_client(pool, &client_id).await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|d| d.document_type == "correspondence" && d.id != document_id)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut sources: Vec<ChecklistSource> = clauses.iter().map(ChecklistSource::clause).collect();
+    for letter in &correspondence {
+        if let Some(text) = &letter.extracted_text {
+            sources.push(ChecklistSource::correspondence(letter.id, text));
+        }
+    }
+
+    TransparencyChecklistEngine::new().evaluate(document_id, &sources)
+}
+
+/// Evaluates the Kásler/Andriciuc transparency elements for a document and
+/// stores the checklist on its research sheet, if one exists.
+pub async fn transparency_checklist(
+    pool: web::Data<SqlitePool>,
+    request: web::Json<TransparencyChecklistRequest>,
+) -> Result<HttpResponse> {
+    let pool = pool.get_ref();
+
+    let clauses = match db::clauses::get_clauses_by_document(pool, request.document_id).await {
+        Ok(clauses) if clauses.is_empty() => {
+            return Ok(HttpResponse::BadRequest().json(json!({
+                "error": "No clauses found for this document. Please extract clauses first."
+            })));
+        },
+        Ok(clauses) => clauses,
+        Err(e) => {
+            log::error!("Failed to fetch clauses: {}", e);
+            return Ok(HttpResponse::InternalServerError().json(json!({
+                "error": "Failed to fetch clauses for document",
+                "details": e.to_string()
+            })));
+        }
+    };
+
+    let checklist = build_transparency_checklist(pool, request.document_id, &clauses).await;
+
+    let research_sheet_id = match db::research::get_research_sheet_by_document(pool, request.document_id).await {
+        Ok(Some(mut sheet)) => {
+            let mut findings: LegalFindings = serde_json::from_value(sheet.legal_findings.clone())
+                .unwrap_or_else(|_| LegalFindings::new());
+            findings.transparency_checklist = Some(checklist.clone());
+            sheet.add_legal_findings(findings);
+            let id = sheet.id;
+            if let Err(e) = db::research::update_research_sheet(pool, sheet).await {
+                log::error!("Failed to attach transparency checklist to research sheet {}: {}", id, e);
+            }
+            Some(id)
+        },
+        Ok(None) => None,
+        Err(e) => {
+            log::error!("Failed to fetch research sheet: {}", e);
+            None
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(json!({
+        "document_id": request.document_id,
+        "research_sheet_id": research_sheet_id,
+        "checklist": checklist
+    })))
+}
+
 pub async fn match_precedents(
     pool: web::Data<SqlitePool>,
     request: web::Json<MatchPrecedentsRequest>,
@@ -133,13 +300,16 @@ pub async fn match_precedents(
 
                             // Add transparency issues
                             let transparency_clauses: Vec<Uuid> = clauses.iter()
-                                .filter(|c| c.clause_type == "transparency")
+                                .filter(|c| c.has_category("transparency"))
+                                .filter(|c| c.transparency_assessment.as_deref() != Some("adequate_warning"))
                                 .map(|c| c.id)
                                 .collect();
                             if !transparency_clauses.is_empty() {
                                 findings.add_transparency_issue(transparency_clauses);
                             }
 
+                            findings.transparency_checklist = Some(build_transparency_checklist(pool, request.document_id, &clauses).await);
+
                             new_sheet.add_legal_findings(findings);
                             new_sheet.add_precedent_citations(applicable_precedents.clone());
 
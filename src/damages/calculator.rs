@@ -0,0 +1,463 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::models::PaymentScheduleRow;
+use super::CounterfactualComparison;
+
+/// Midpoints of the annual rate ranges previously left to the LLM
+/// (interest 5-8%, inflation 3-4%, alternative investment 6-9%)
+pub const DEFAULT_LATE_INTEREST_RATE: f64 = 0.065;
+pub const DEFAULT_INFLATION_RATE: f64 = 0.035;
+pub const DEFAULT_OPPORTUNITY_RATE: f64 = 0.075;
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Damage categories of a claim, in HUF.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DamageBreakdown {
+    pub primary_restitution: f64,
+    pub lost_interest: f64,
+    pub inflation_adjustment: f64,
+    pub opportunity_cost: f64,
+    pub credit_rating_damages: f64,
+    pub psychological_damages: f64,
+    pub broker_liability: f64,
+    pub legal_costs: f64,
+    pub administrative_costs: f64,
+}
+
+impl DamageBreakdown {
+    pub fn total(&self) -> f64 {
+        self.primary_restitution
+            + self.lost_interest
+            + self.inflation_adjustment
+            + self.opportunity_cost
+            + self.credit_rating_damages
+            + self.psychological_damages
+            + self.broker_liability
+            + self.legal_costs
+            + self.administrative_costs
+    }
+}
+
+/// Every input besides the payment rows. Rates are annual; the claimed amounts
+/// (HUF) cover categories the payment rows cannot show and are documented
+/// separately. Stored with each assessment so its figures can be reproduced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageParameters {
+    pub valuation_date: NaiveDate, // interest and inflation run from each due date to this date
+    pub disbursement_rate: Option<f64>, // HUF per unit of loan currency at disbursement; None: earliest row's rate
+    pub late_interest_rate: f64, // simple interest at an assumed flat rate; not the statutory half-yearly rate
+    pub inflation_rate: f64, // compounded yearly
+    pub opportunity_rate: f64, // compounded yearly
+    pub credit_rating_damages: f64,
+    pub psychological_damages: f64,
+    pub broker_fees: f64,
+    pub legal_costs: f64,
+    pub administrative_costs: f64,
+}
+
+impl DamageParameters {
+    pub fn new(valuation_date: NaiveDate) -> Self {
+        Self {
+            valuation_date,
+            disbursement_rate: None,
+            late_interest_rate: DEFAULT_LATE_INTEREST_RATE,
+            inflation_rate: DEFAULT_INFLATION_RATE,
+            opportunity_rate: DEFAULT_OPPORTUNITY_RATE,
+            credit_rating_damages: 0.0,
+            psychological_damages: 0.0,
+            broker_fees: 0.0,
+            legal_costs: 0.0,
+            administrative_costs: 0.0,
+        }
+    }
+
+    /// Defaults overridden by `DAMAGES_LATE_INTEREST_RATE`,
+    /// `DAMAGES_INFLATION_RATE` and `DAMAGES_OPPORTUNITY_RATE`.
+    pub fn from_env(valuation_date: NaiveDate) -> Self {
+        let rate = |name: &str, default: f64| {
+            std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        };
+        Self {
+            late_interest_rate: rate("DAMAGES_LATE_INTEREST_RATE", DEFAULT_LATE_INTEREST_RATE),
+            inflation_rate: rate("DAMAGES_INFLATION_RATE", DEFAULT_INFLATION_RATE),
+            opportunity_rate: rate("DAMAGES_OPPORTUNITY_RATE", DEFAULT_OPPORTUNITY_RATE),
+            ..Self::new(valuation_date)
+        }
+    }
+}
+
+/// One paid instalment: what it cost in HUF against what it would have cost
+/// at the disbursement exchange rate, or under the counterfactual HUF loan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowDamage {
+    pub row_id: Option<Uuid>, // None for counterfactual table rows
+    pub due_date: NaiveDate,
+    pub instalment: f64, // loan currency
+    pub huf_paid: f64,
+    pub huf_counterfactual: f64, // the instalment at the disbursement rate, or of the counterfactual HUF loan
+    pub overpayment: f64, // negative while the forint was stronger than at disbursement
+    pub years_outstanding: f64,
+    pub lost_interest: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DamageAssessment {
+    pub breakdown: DamageBreakdown,
+    pub total_recovery: f64,
+    pub disbursement_rate: Option<f64>, // the rate actually applied
+    pub rows: Vec<RowDamage>,
+    pub parameters: DamageParameters,
+    pub notes: Vec<String>,
+    pub narrative: Option<String>, // prose written around the figures; never changes them
+    #[serde(default)]
+    pub counterfactuals: Vec<CounterfactualComparison>, // evidence for primary_restitution, when used
+}
+
+impl DamageAssessment {
+    /// Net overpayment of the rows due within `from..=to`.
+    pub fn overpayment_between(&self, from: NaiveDate, to: NaiveDate) -> f64 {
+        self.rows.iter()
+            .filter(|r| r.due_date >= from && r.due_date <= to)
+            .map(|r| r.overpayment)
+            .sum()
+    }
+}
+
+/// Payment rows attributed to one contract of a case, with the rate that
+/// contract was disbursed at.
+#[derive(Debug, Clone)]
+pub struct ContractRows {
+    pub contract_id: String,
+    pub disbursement_rate: Option<f64>, // None: the earliest row's rate
+    pub rows: Vec<PaymentScheduleRow>,
+}
+
+/// Computes the `DamageBreakdown` from repayment schedule or statement rows
+/// with fixed formulas, so the same rows and parameters always give the same
+/// figures:
+///
+/// - primary restitution: for each paid instalment, the HUF actually debited
+///   less the instalment converted at the disbursement rate, netted over all
+///   rows; with `calculate_against`, less the instalment of the counterfactual
+///   HUF loan instead;
+/// - lost interest: simple interest at the flat `late_interest_rate` on each
+///   row's overpayment from its due date to the valuation date;
+/// - inflation adjustment and opportunity cost: the yearly-compounded loss of
+///   purchasing power and foregone return on the same amounts, each counted
+///   only above the categories before it so nothing is claimed twice;
+/// - the remaining categories: the amounts claimed in the parameters.
+///
+/// Amounts are rounded to whole forints per category.
+pub struct DamageCalculator {
+    parameters: DamageParameters,
+}
+
+impl DamageCalculator {
+    pub fn new(parameters: DamageParameters) -> Self {
+        Self { parameters }
+    }
+
+    pub fn calculate(&self, rows: &[PaymentScheduleRow]) -> DamageAssessment {
+        let mut notes = Vec::new();
+        let (damages, disbursement_rate) = self.row_damages(rows, self.parameters.disbursement_rate, &mut notes);
+        self.assess(damages, disbursement_rate, notes, Vec::new())
+    }
+
+    /// Like `calculate` for a case with several contracts: each contract's rows
+    /// are converted at that contract's disbursement rate (the one in the
+    /// parameters is not used), and the claimed amounts are counted once.
+    pub fn calculate_contracts(&self, contracts: &[ContractRows]) -> DamageAssessment {
+        let mut notes = Vec::new();
+        let mut damages = Vec::new();
+        let mut rates = Vec::new();
+
+        for contract in contracts {
+            let mut contract_notes = Vec::new();
+            let (rows, rate) = self.row_damages(&contract.rows, contract.disbursement_rate, &mut contract_notes);
+            notes.extend(contract_notes.into_iter().map(|n| format!("{}: {}", contract.contract_id, n)));
+            if let Some(rate) = rate {
+                notes.push(format!("{}: {} row(s) converted at the disbursement rate {:.4}", contract.contract_id, rows.len(), rate));
+            }
+            damages.extend(rows);
+            rates.extend(rate);
+        }
+        damages.sort_by_key(|r| r.due_date);
+
+        // One rate for the assessment only when every contract used the same
+        let disbursement_rate = rates.first().copied().filter(|first| rates.iter().all(|r| r == first));
+        self.assess(damages, disbursement_rate, notes, Vec::new())
+    }
+
+    /// Like `calculate`, with each instalment measured against the chosen
+    /// counterfactual HUF loan instead of the disbursement rate alone; the
+    /// comparison tables are kept on the assessment as evidence.
+    pub fn calculate_against(&self, comparisons: Vec<CounterfactualComparison>) -> DamageAssessment {
+        let p = &self.parameters;
+        let mut notes = Vec::new();
+        let mut damages = Vec::new();
+
+        for comparison in &comparisons {
+            let basis = comparison.effective_basis();
+            if basis != comparison.basis {
+                notes.push("The BUBOR comparison could not be computed; the contractual rate comparison is used".to_string());
+            }
+            let mut future = 0;
+            for row in &comparison.rows {
+                let (Some(huf_paid), Some(counterfactual)) = (row.actual_huf, row.counterfactual_huf(basis)) else { continue };
+                if row.due_date > p.valuation_date {
+                    future += 1;
+                    continue;
+                }
+                damages.push(self.row_damage(None, row.due_date, row.instalment, huf_paid, counterfactual));
+            }
+            if future > 0 {
+                notes.push(format!("{} instalment(s) due after the valuation date {} are not counted", future, p.valuation_date));
+            }
+            notes.extend(comparison.notes.iter().cloned());
+        }
+        if comparisons.is_empty() {
+            notes.push("No counterfactual comparison could be built".to_string());
+        }
+
+        let disbursement_rate = comparisons.first().map(|c| c.disbursement_rate);
+        self.assess(damages, disbursement_rate, notes, comparisons)
+    }
+
+    /// Rows in the loan currency due by the valuation date, each measured
+    /// against its instalment at `disbursement_rate`, or at the earliest row's
+    /// rate when that is not known.
+    fn row_damages(&self, rows: &[PaymentScheduleRow], disbursement_rate: Option<f64>, notes: &mut Vec<String>) -> (Vec<RowDamage>, Option<f64>) {
+        let p = &self.parameters;
+
+        let mut rows: Vec<&PaymentScheduleRow> = rows.iter()
+            .filter(|r| !r.currency.as_deref().is_some_and(|c| c.eq_ignore_ascii_case("HUF")))
+            .collect();
+        rows.sort_by_key(|r| (r.due_date, r.row_index));
+
+        let future = rows.iter().filter(|r| r.due_date > p.valuation_date).count();
+        if future > 0 {
+            notes.push(format!("{} row(s) due after the valuation date {} are not counted", future, p.valuation_date));
+        }
+        rows.retain(|r| r.due_date <= p.valuation_date);
+
+        let disbursement_rate = disbursement_rate.or_else(|| {
+            let first = rows.iter().find_map(|r| r.exchange_rate.or_else(|| r.implied_exchange_rate()));
+            if let Some(rate) = first {
+                notes.push(format!("No disbursement rate given; the earliest row's rate {:.4} is used", rate));
+            }
+            first
+        });
+
+        let mut damages = Vec::new();
+        let mut skipped = 0;
+        for row in &rows {
+            match (instalment(row), huf_paid(row), disbursement_rate) {
+                (Some(instalment), Some(huf_paid), Some(rate)) => {
+                    damages.push(self.row_damage(Some(row.id), row.due_date, instalment, huf_paid, instalment * rate));
+                },
+                _ => skipped += 1,
+            }
+        }
+        if skipped > 0 {
+            notes.push(format!("{} row(s) lack the instalment, the HUF amount or a rate and are not counted", skipped));
+        }
+
+        (damages, disbursement_rate)
+    }
+
+    fn row_damage(&self, row_id: Option<Uuid>, due_date: NaiveDate, instalment: f64, huf_paid: f64, huf_counterfactual: f64) -> RowDamage {
+        let p = &self.parameters;
+        let overpayment = huf_paid - huf_counterfactual;
+        let years_outstanding = (p.valuation_date - due_date).num_days() as f64 / DAYS_PER_YEAR;
+        RowDamage {
+            row_id,
+            due_date,
+            instalment,
+            huf_paid,
+            huf_counterfactual,
+            overpayment,
+            years_outstanding,
+            lost_interest: overpayment * p.late_interest_rate * years_outstanding,
+        }
+    }
+
+    fn assess(
+        &self,
+        damages: Vec<RowDamage>,
+        disbursement_rate: Option<f64>,
+        mut notes: Vec<String>,
+        counterfactuals: Vec<CounterfactualComparison>,
+    ) -> DamageAssessment {
+        let p = &self.parameters;
+        let net_overpayment: f64 = damages.iter().map(|r| r.overpayment).sum();
+        if net_overpayment < 0.0 {
+            let against = if counterfactuals.is_empty() { "the disbursement rate" } else { "the counterfactual HUF loan" };
+            notes.push(format!("The rows show no net overpayment against {}", against));
+        }
+        let compounded = |rate: f64| -> f64 {
+            damages.iter().map(|r| r.overpayment * ((1.0 + rate).powf(r.years_outstanding) - 1.0)).sum()
+        };
+
+        let primary_restitution = net_overpayment.max(0.0).round();
+        let lost_interest = damages.iter().map(|r| r.lost_interest).sum::<f64>().max(0.0).round();
+        if lost_interest > 0.0 {
+            notes.push(format!(
+                "Lost interest is at an assumed flat {:.2}% a year, not the statutory late interest of Ptk. 6:48, which follows the MNB base rate of each calendar half-year",
+                p.late_interest_rate * 100.0
+            ));
+        }
+        let inflation_adjustment = (compounded(p.inflation_rate) - lost_interest).max(0.0).round();
+        let opportunity_cost = (compounded(p.opportunity_rate) - lost_interest - inflation_adjustment).max(0.0).round();
+
+        let breakdown = DamageBreakdown {
+            primary_restitution,
+            lost_interest,
+            inflation_adjustment,
+            opportunity_cost,
+            credit_rating_damages: p.credit_rating_damages.round(),
+            psychological_damages: p.psychological_damages.round(),
+            broker_liability: p.broker_fees.round(),
+            legal_costs: p.legal_costs.round(),
+            administrative_costs: p.administrative_costs.round(),
+        };
+
+        DamageAssessment {
+            total_recovery: breakdown.total(),
+            breakdown,
+            disbursement_rate,
+            rows: damages,
+            parameters: p.clone(),
+            notes,
+            narrative: None,
+            counterfactuals,
+        }
+    }
+}
+
+/// Instalment in the loan currency, from its parts when the total is not printed.
+fn instalment(row: &PaymentScheduleRow) -> Option<f64> {
+    row.instalment_amount.or(match (row.principal, row.interest) {
+        (Some(principal), Some(interest)) => Some(principal + interest),
+        _ => None,
+    })
+}
+
+fn huf_paid(row: &PaymentScheduleRow) -> Option<f64> {
+    row.huf_debit.or_else(|| Some(instalment(row)? * row.exchange_rate?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(index: i32, due: &str, instalment: f64, rate: f64) -> PaymentScheduleRow {
+        let mut row = PaymentScheduleRow::new(Uuid::nil(), index, due.parse().unwrap(), 0, String::new());
+        row.currency = Some("CHF".to_string());
+        row.instalment_amount = Some(instalment);
+        row.exchange_rate = Some(rate);
+        row
+    }
+
+    #[test]
+    fn test_restitution_and_interest_from_rows() {
+        let rows = vec![
+            row(0, "2010-01-05", 1000.0, 150.0),
+            row(1, "2011-01-05", 1000.0, 200.0),
+            row(2, "2031-01-05", 1000.0, 250.0),
+        ];
+        let mut parameters = DamageParameters::new("2012-01-05".parse().unwrap());
+        parameters.disbursement_rate = Some(150.0);
+        parameters.late_interest_rate = 0.1;
+        parameters.legal_costs = 100_000.0;
+
+        let assessment = DamageCalculator::new(parameters).calculate(&rows);
+
+        assert_eq!(assessment.rows.len(), 2);
+        assert_eq!(assessment.breakdown.primary_restitution, 50_000.0);
+        // 50 000 HUF overpaid on 2011-01-05, outstanding 365 days at 10%
+        assert_eq!(assessment.breakdown.lost_interest, 5_000.0);
+        assert_eq!(assessment.breakdown.legal_costs, 100_000.0);
+        assert_eq!(assessment.total_recovery, assessment.breakdown.total());
+        assert!(assessment.notes.iter().any(|n| n.contains("after the valuation date")));
+        assert!(assessment.notes.iter().any(|n| n.contains("assumed flat 10.00%")));
+    }
+
+    #[test]
+    fn test_each_contract_at_its_own_disbursement_rate() {
+        let mut parameters = DamageParameters::new("2012-01-05".parse().unwrap());
+        parameters.legal_costs = 100_000.0;
+        let contracts = [
+            ContractRows {
+                contract_id: "chf".to_string(),
+                disbursement_rate: Some(150.0),
+                rows: vec![row(0, "2011-01-05", 1000.0, 200.0)],
+            },
+            ContractRows {
+                contract_id: "eur".to_string(),
+                disbursement_rate: Some(260.0),
+                rows: vec![PaymentScheduleRow { currency: Some("EUR".to_string()), ..row(0, "2011-02-05", 100.0, 280.0) }],
+            },
+        ];
+
+        let assessment = DamageCalculator::new(parameters).calculate_contracts(&contracts);
+
+        // 50 000 HUF on the first contract and 2 000 HUF on the second, not at the earliest row's 200
+        assert_eq!(assessment.breakdown.primary_restitution, 52_000.0);
+        assert_eq!(assessment.breakdown.legal_costs, 100_000.0);
+        assert_eq!(assessment.disbursement_rate, None);
+        assert!(assessment.notes.iter().any(|n| n.starts_with("eur:") && n.contains("260.0000")));
+        assert!(!assessment.notes.iter().any(|n| n.contains("earliest row")));
+    }
+
+    #[test]
+    fn test_layers_are_not_counted_twice() {
+        let rows = vec![row(0, "2010-01-05", 1000.0, 150.0), row(1, "2015-01-05", 1000.0, 250.0)];
+        let mut parameters = DamageParameters::new("2020-01-05".parse().unwrap());
+        parameters.late_interest_rate = 0.05;
+        parameters.inflation_rate = 0.03;
+        parameters.opportunity_rate = 0.08;
+
+        let calculator = DamageCalculator::new(parameters);
+        let first = calculator.calculate(&rows);
+        let second = calculator.calculate(&rows);
+
+        assert_eq!(first.disbursement_rate, Some(150.0));
+        assert_eq!(first.breakdown, second.breakdown);
+        // 100 000 HUF for 5 years (one leap day): 8% compounded is about 46 950 in total,
+        // of which 25 000 is already claimed as interest; inflation stays below interest
+        let b = &first.breakdown;
+        assert_eq!(b.inflation_adjustment, 0.0);
+        assert!((b.lost_interest + b.opportunity_cost - 46_950.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn test_restitution_against_counterfactual_loan() {
+        use chrono::Months;
+        use crate::damages::{AmortisationSchedule, CounterfactualBasis, InterestRatePeriod, LoanTerms};
+        use crate::models::RateKind;
+        use crate::rates::RateTable;
+
+        let start: NaiveDate = "2010-01-01".parse().unwrap();
+        let schedule = AmortisationSchedule::reconstruct(LoanTerms {
+            principal: 1200.0,
+            currency: "CHF".to_string(),
+            start_date: start,
+            term_months: 12,
+            rate_history: vec![InterestRatePeriod { effective_from: start, annual_rate: 0.0 }],
+            disbursement_rate: Some(150.0),
+            margin: None,
+        }, Some(&RateTable::new(RateKind::BankSell, (1..=12).map(|m| (start + Months::new(m), 200.0)))));
+        let valuation_date = "2011-01-01".parse().unwrap();
+        let comparison = CounterfactualComparison::build(&schedule, &[], None, valuation_date, CounterfactualBasis::ContractualRate).unwrap();
+
+        let assessment = DamageCalculator::new(DamageParameters::new(valuation_date)).calculate_against(vec![comparison]);
+
+        // 100 CHF a month paid at 200 against a 15 000 HUF instalment
+        assert_eq!(assessment.breakdown.primary_restitution, 60_000.0);
+        assert_eq!(assessment.rows.len(), 12);
+        assert_eq!(assessment.disbursement_rate, Some(150.0));
+        assert_eq!(assessment.counterfactuals[0].rows.len(), 12);
+    }
+}
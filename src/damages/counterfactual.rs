@@ -0,0 +1,298 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::rates::RateTable;
+use super::{AmortisationSchedule, InterestRatePeriod, LoanTerms, ScheduledInstalment};
+
+/// HUF loans on a 3-month BUBOR were repriced quarterly
+pub const BUBOR_REPRICING_MONTHS: u32 = 3;
+
+/// Which counterfactual loan `primary_restitution` is measured against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CounterfactualBasis {
+    /// The contract's own interest rates on the HUF principal: only the
+    /// exchange rate risk is taken away
+    #[default]
+    ContractualRate,
+    /// 3-month BUBOR plus the contractual margin, as a comparable HUF loan
+    /// would have been priced
+    Bubor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentSource {
+    Statement, // debited amount on a bank statement or schedule
+    Reconstructed, // instalment of the rebuilt schedule at the stored rate
+}
+
+/// An instalment the client actually paid, in HUF.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActualPayment {
+    pub due_date: NaiveDate,
+    pub huf_paid: f64,
+}
+
+impl ActualPayment {
+    /// What was paid for `instalment`: a payment in the month it fell due,
+    /// otherwise the rebuilt schedule's HUF amount.
+    pub fn paid_for(actual: &[ActualPayment], instalment: &ScheduledInstalment) -> Option<(f64, PaymentSource)> {
+        actual.iter()
+            .find(|p| p.due_date.year() == instalment.due_date.year() && p.due_date.month() == instalment.due_date.month())
+            .map(|p| (p.huf_paid, PaymentSource::Statement))
+            .or_else(|| instalment.huf_amount.map(|huf| (huf, PaymentSource::Reconstructed)))
+    }
+}
+
+/// One line of the evidence table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CounterfactualRow {
+    pub number: u32,
+    pub due_date: NaiveDate,
+    pub instalment: f64, // loan currency, per the rebuilt schedule
+    pub actual_huf: Option<f64>,
+    pub actual_source: Option<PaymentSource>,
+    pub contractual_rate: f64, // percent per annum
+    pub contractual_huf: f64,
+    pub bubor_rate: Option<f64>, // BUBOR plus margin, percent per annum
+    pub bubor_huf: Option<f64>,
+    pub difference_contractual: Option<f64>, // actual less counterfactual
+    pub difference_bubor: Option<f64>,
+}
+
+impl CounterfactualRow {
+    pub fn counterfactual_huf(&self, basis: CounterfactualBasis) -> Option<f64> {
+        match basis {
+            CounterfactualBasis::ContractualRate => Some(self.contractual_huf),
+            CounterfactualBasis::Bubor => self.bubor_huf,
+        }
+    }
+}
+
+/// What the client paid against what the same loan would have cost had it
+/// been a HUF loan of the disbursed forint amount, instalment by instalment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CounterfactualComparison {
+    pub basis: CounterfactualBasis, // the basis the totals below are chosen by
+    pub disbursement_rate: f64,
+    pub huf_principal: f64,
+    pub margin: Option<f64>,
+    pub rows: Vec<CounterfactualRow>,
+    pub total_actual: f64, // rows with a known payment
+    pub total_contractual: f64,
+    pub total_bubor: Option<f64>,
+    pub difference_contractual: f64,
+    pub difference_bubor: Option<f64>,
+    pub notes: Vec<String>,
+}
+
+impl CounterfactualComparison {
+    /// Compares the instalments due up to `as_of`. A payment from `actual` is
+    /// matched to the instalment due in the same month; instalments without
+    /// one use the rebuilt schedule's HUF amount. Needs the disbursement rate;
+    /// the BUBOR variant also needs the margin and BUBOR rates from the start.
+    pub fn build(
+        schedule: &AmortisationSchedule,
+        actual: &[ActualPayment],
+        bubor: Option<&RateTable>,
+        as_of: NaiveDate,
+        basis: CounterfactualBasis,
+    ) -> Option<Self> {
+        let terms = &schedule.terms;
+        let disbursement_rate = terms.disbursement_rate.filter(|rate| *rate > 0.0)?;
+        if schedule.instalments.is_empty() {
+            return None;
+        }
+        let mut notes = Vec::new();
+
+        let huf_terms = LoanTerms {
+            principal: (terms.principal * disbursement_rate).round(),
+            currency: "HUF".to_string(),
+            disbursement_rate: None,
+            ..terms.clone()
+        };
+        let contractual = AmortisationSchedule::reconstruct(huf_terms.clone(), None);
+        // A principal that rounds to nothing in forints rebuilds no schedule
+        if contractual.instalments.len() != schedule.instalments.len() {
+            return None;
+        }
+
+        let bubor_schedule = match (bubor, terms.margin) {
+            (Some(table), Some(margin)) => bubor_rate_history(&huf_terms, table, margin, &mut notes)
+                .map(|rate_history| AmortisationSchedule::reconstruct(LoanTerms { rate_history, ..huf_terms.clone() }, None)),
+            (None, _) => {
+                notes.push("No BUBOR series imported; the BUBOR comparison is left out".to_string());
+                None
+            },
+            (_, None) => {
+                notes.push("The contractual margin is unknown; the BUBOR comparison is left out".to_string());
+                None
+            },
+        };
+
+        let mut rows = Vec::new();
+        let mut reconstructed = 0;
+        for (i, instalment) in schedule.instalments.iter().enumerate().filter(|(_, i)| i.due_date <= as_of) {
+            let paid = ActualPayment::paid_for(actual, instalment);
+            if matches!(paid, Some((_, PaymentSource::Reconstructed))) {
+                reconstructed += 1;
+            }
+
+            let Some(contractual_instalment) = contractual.instalments.get(i) else { break };
+            let bubor_instalment = bubor_schedule.as_ref().and_then(|s| s.instalments.get(i));
+            let actual_huf = paid.map(|(huf, _)| huf);
+            let bubor_huf = bubor_instalment.and_then(|b| b.huf_amount);
+            rows.push(CounterfactualRow {
+                number: instalment.number,
+                due_date: instalment.due_date,
+                instalment: instalment.instalment,
+                actual_huf,
+                actual_source: paid.map(|(_, source)| source),
+                contractual_rate: contractual_instalment.annual_rate,
+                contractual_huf: contractual_instalment.huf_amount.unwrap_or_default(),
+                bubor_rate: bubor_instalment.map(|b| b.annual_rate),
+                bubor_huf,
+                difference_contractual: actual_huf.zip(contractual_instalment.huf_amount).map(|(a, c)| a - c),
+                difference_bubor: actual_huf.zip(bubor_huf).map(|(a, b)| a - b),
+            });
+        }
+
+        if reconstructed > 0 {
+            notes.push(format!("{} instalment(s) have no statement; the rebuilt schedule's HUF amount is used", reconstructed));
+        }
+        let unpaid = rows.iter().filter(|r| r.actual_huf.is_none()).count();
+        if unpaid > 0 {
+            notes.push(format!("{} instalment(s) have neither a statement nor a stored exchange rate and are not counted", unpaid));
+        }
+
+        let known: Vec<&CounterfactualRow> = rows.iter().filter(|r| r.actual_huf.is_some()).collect();
+        let total_actual = known.iter().filter_map(|r| r.actual_huf).sum();
+        let total_contractual = known.iter().map(|r| r.contractual_huf).sum();
+        let total_bubor = bubor_schedule.as_ref().map(|_| known.iter().filter_map(|r| r.bubor_huf).sum::<f64>());
+
+        Some(Self {
+            basis,
+            disbursement_rate,
+            huf_principal: huf_terms.principal,
+            margin: terms.margin,
+            difference_contractual: total_actual - total_contractual,
+            difference_bubor: total_bubor.map(|t| total_actual - t),
+            total_actual,
+            total_contractual,
+            total_bubor,
+            rows,
+            notes,
+        })
+    }
+
+    /// The basis actually usable: the BUBOR variant falls back to the
+    /// contractual rate when it could not be computed.
+    pub fn effective_basis(&self) -> CounterfactualBasis {
+        match self.basis {
+            CounterfactualBasis::Bubor if self.total_bubor.is_none() => CounterfactualBasis::ContractualRate,
+            basis => basis,
+        }
+    }
+}
+
+/// BUBOR on each repricing date of `terms` plus the margin. Missing fixings
+/// keep the previous rate; without a fixing at the start there is no history.
+pub fn bubor_rate_history(terms: &LoanTerms, bubor: &RateTable, margin: f64, notes: &mut Vec<String>) -> Option<Vec<InterestRatePeriod>> {
+    let mut history: Vec<InterestRatePeriod> = Vec::new();
+    let mut missing = 0;
+
+    for offset in (0..terms.term_months).step_by(BUBOR_REPRICING_MONTHS as usize) {
+        let Some(fixing_date) = terms.start_date.checked_add_months(Months::new(offset)) else { break };
+        match bubor.rate_on(fixing_date) {
+            Some(lookup) => history.push(InterestRatePeriod { effective_from: fixing_date, annual_rate: lookup.value + margin }),
+            None if history.is_empty() => {
+                notes.push(format!("No BUBOR fixing for the start date {}", fixing_date));
+                return None;
+            },
+            None => missing += 1,
+        }
+    }
+
+    if missing > 0 {
+        notes.push(format!("{} BUBOR repricing date(s) without a fixing keep the previous rate", missing));
+    }
+    Some(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RateKind;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn schedule(margin: Option<f64>, chf: &RateTable) -> AmortisationSchedule {
+        AmortisationSchedule::reconstruct(LoanTerms {
+            principal: 10_000.0,
+            currency: "CHF".to_string(),
+            start_date: date("2008-01-10"),
+            term_months: 24,
+            rate_history: vec![InterestRatePeriod { effective_from: date("2008-01-10"), annual_rate: 0.0 }],
+            disbursement_rate: Some(150.0),
+            margin,
+        }, Some(chf))
+    }
+
+    #[test]
+    fn test_contractual_counterfactual_removes_fx_movement() {
+        // The forint weakens from 150 to 200 after the first year
+        let chf = RateTable::new(RateKind::BankSell, (1..=24).map(|m| {
+            let due = date("2008-01-10").checked_add_months(Months::new(m)).unwrap();
+            (due, if m <= 12 { 150.0 } else { 200.0 })
+        }));
+        let schedule = schedule(None, &chf);
+        let statements = vec![ActualPayment { due_date: date("2008-02-12"), huf_paid: 63_000.0 }];
+
+        let comparison = CounterfactualComparison::build(&schedule, &statements, None, date("2009-12-31"), CounterfactualBasis::ContractualRate).unwrap();
+
+        assert_eq!(comparison.huf_principal, 1_500_000.0);
+        assert_eq!(comparison.rows.len(), 23);
+        assert_eq!(comparison.rows[0].actual_source, Some(PaymentSource::Statement));
+        assert_eq!(comparison.rows[0].difference_contractual, Some(500.0));
+        assert!(comparison.rows[1].difference_contractual.unwrap().abs() <= 1.0);
+        // 416.67 CHF at 200 instead of 150 for the second year's 11 instalments
+        assert_eq!(comparison.rows[12].difference_contractual, Some(20_834.0));
+        assert!((comparison.difference_contractual - (500.0 + 11.0 * 20_834.0)).abs() < 20.0);
+        assert_eq!(comparison.effective_basis(), CounterfactualBasis::ContractualRate);
+        assert!(comparison.notes.iter().any(|n| n.contains("No BUBOR series")));
+    }
+
+    #[test]
+    fn test_unusable_disbursement_rate_builds_no_comparison() {
+        let chf = RateTable::new(RateKind::BankSell, [(date("2008-02-08"), 150.0)]);
+        let mut schedule = schedule(None, &chf);
+
+        for rate in [0.0, -150.0, 1e-6] {
+            schedule.terms.disbursement_rate = Some(rate);
+            let comparison = CounterfactualComparison::build(&schedule, &[], None, date("2009-12-31"), CounterfactualBasis::ContractualRate);
+            assert!(comparison.is_none(), "disbursement rate {rate}");
+        }
+    }
+
+    #[test]
+    fn test_bubor_counterfactual_reprices_quarterly() {
+        let chf = RateTable::new(RateKind::BankSell, [(date("2008-02-08"), 150.0)]);
+        let bubor = RateTable::new(RateKind::Bubor, [(date("2008-01-09"), 7.5), (date("2008-04-10"), 8.5)]);
+        let schedule = schedule(Some(2.0), &chf);
+
+        let comparison = CounterfactualComparison::build(&schedule, &[], Some(&bubor), date("2008-12-31"), CounterfactualBasis::Bubor).unwrap();
+
+        assert_eq!(comparison.rows[0].bubor_rate, Some(9.5));
+        assert_eq!(comparison.rows[2].bubor_rate, Some(9.5));
+        assert_eq!(comparison.rows[3].bubor_rate, Some(10.5));
+        // the fixing of 2008-04-10 is too old for July; the rate is kept
+        assert_eq!(comparison.rows[7].bubor_rate, Some(10.5));
+        assert!(comparison.notes.iter().any(|n| n.contains("keep the previous rate")));
+        // only the first instalment has an exchange rate to reconstruct the payment
+        assert_eq!(comparison.rows.iter().filter(|r| r.actual_huf.is_some()).count(), 1);
+        assert!(comparison.difference_bubor.unwrap() < 0.0, "a 0% CHF loan is cheaper than a BUBOR loan at the same rate");
+        assert_eq!(comparison.effective_basis(), CounterfactualBasis::Bubor);
+    }
+}
@@ -0,0 +1,9 @@
+pub mod calculator;
+pub mod schedule;
+pub mod counterfactual;
+pub mod remedies;
+
+pub use calculator::*;
+pub use schedule::*;
+pub use counterfactual::*;
+pub use remedies::*;
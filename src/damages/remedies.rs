@@ -0,0 +1,407 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::rates::RateTable;
+use super::{bubor_rate_history, ActualPayment, AmortisationSchedule, LoanTerms, ScheduledInstalment};
+
+/// Day the outstanding FX mortgage balances became forint debts (Act LXXVII of 2014)
+pub fn statutory_conversion_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2015, 2, 1).unwrap()
+}
+
+/// Exchange rates fixed by Act LXXVII of 2014 for the conversion: the MNB
+/// official rates of 7 November 2014, or the average since 16 June 2014 if
+/// lower.
+pub fn statutory_conversion_rate(currency: &str) -> Option<f64> {
+    match currency.to_uppercase().as_str() {
+        "CHF" => Some(256.47),
+        "EUR" => Some(308.97),
+        "JPY" => Some(2.1361),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Remedy {
+    FullInvalidity,
+    StatutoryConversion,
+    TermSubstitution,
+}
+
+impl Remedy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::FullInvalidity => "Full invalidity with mutual restitution",
+            Self::StatutoryConversion => "2014 settlement and 2015 statutory HUF conversion",
+            Self::TermSubstitution => "Unfair term replaced by a supplementary national rule",
+        }
+    }
+
+    pub fn authorities(&self) -> &'static [&'static str] {
+        match self {
+            Self::FullInvalidity => &[
+                "C-705/21 AxFina Hungary",
+                "C-630/23 AxFina Hungary",
+                "C-520/21 Bank M.",
+            ],
+            Self::StatutoryConversion => &[
+                "Act XL of 2014 (settlement)",
+                "Act LXXVII of 2014 (HUF conversion)",
+                "C-118/17 Dunai",
+            ],
+            Self::TermSubstitution => &[
+                "C-26/13 Kásler, paras 80-85",
+                "Act XXXVIII of 2014",
+            ],
+        }
+    }
+}
+
+/// The financial result of one remedy, valued on the same day as the others.
+/// `cost_of_credit` is what the loan costs the client beyond the forints
+/// disbursed: payments, less the settlement, plus the debt still owed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemedyScenario {
+    pub remedy: Remedy,
+    pub label: String,
+    pub authorities: Vec<String>,
+    pub assumptions: Vec<String>,
+    pub paid_to_date: f64, // HUF
+    pub settlement: f64, // owed by the bank to the client; negative when the client owes
+    pub remaining_debt: f64, // HUF still owed under the contract after the remedy
+    pub cost_of_credit: Option<f64>, // None without the disbursed amount
+    pub notes: Vec<String>,
+}
+
+impl RemedyScenario {
+    fn new(remedy: Remedy, assumptions: &[&str]) -> Self {
+        Self {
+            remedy,
+            label: remedy.label().to_string(),
+            authorities: remedy.authorities().iter().map(|a| a.to_string()).collect(),
+            assumptions: assumptions.iter().map(|a| a.to_string()).collect(),
+            paid_to_date: 0.0,
+            settlement: 0.0,
+            remaining_debt: 0.0,
+            cost_of_credit: None,
+            notes: Vec::new(),
+        }
+    }
+
+    fn finish(&mut self, disbursed_huf: Option<f64>) {
+        self.paid_to_date = self.paid_to_date.round();
+        self.settlement = self.settlement.round();
+        self.remaining_debt = self.remaining_debt.round();
+        self.cost_of_credit = disbursed_huf.map(|d| self.paid_to_date - self.settlement + self.remaining_debt - d);
+    }
+}
+
+/// One contract under each remedy a court may apply, side by side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemedyComparison {
+    pub valuation_date: NaiveDate,
+    pub currency: String,
+    pub disbursed_huf: Option<f64>,
+    pub scenarios: Vec<RemedyScenario>,
+    pub notes: Vec<String>, // why a scenario is missing
+}
+
+impl RemedyComparison {
+    /// Values the rebuilt `schedule` under every remedy that applies to it.
+    /// Payments come from `actual` where a statement exists, otherwise from
+    /// the schedule at the bank's rates. `official` is the MNB official rate
+    /// series of the loan currency; `bubor` prices the converted HUF loan.
+    pub fn build(
+        schedule: &AmortisationSchedule,
+        actual: &[ActualPayment],
+        official: Option<&RateTable>,
+        bubor: Option<&RateTable>,
+        valuation_date: NaiveDate,
+    ) -> Self {
+        let mut comparison = Self {
+            valuation_date,
+            currency: schedule.terms.currency.clone(),
+            disbursed_huf: schedule.disbursed_huf,
+            scenarios: Vec::new(),
+            notes: Vec::new(),
+        };
+        if schedule.instalments.is_empty() {
+            comparison.notes.push("No schedule could be rebuilt; no remedy is valued".to_string());
+            return comparison;
+        }
+
+        let due: Vec<&ScheduledInstalment> = schedule.instalments.iter()
+            .filter(|i| i.due_date <= valuation_date)
+            .collect();
+
+        match schedule.disbursed_huf {
+            Some(disbursed) => comparison.scenarios.push(full_invalidity(&due, actual, disbursed)),
+            None => comparison.notes.push("Full invalidity needs the disbursement rate".to_string()),
+        }
+
+        let conversion_date = statutory_conversion_date();
+        match statutory_conversion_rate(&schedule.terms.currency) {
+            Some(_) if schedule.terms.start_date >= conversion_date => {
+                comparison.notes.push("The loan started after the statutory conversion".to_string());
+            },
+            Some(_) if valuation_date < conversion_date => {
+                comparison.notes.push("The valuation date precedes the statutory conversion".to_string());
+            },
+            Some(rate) => comparison.scenarios.push(statutory_conversion(schedule, actual, official, bubor, rate, valuation_date)),
+            None => comparison.notes.push(format!("{} loans were not converted by statute", schedule.terms.currency)),
+        }
+
+        match official {
+            Some(official) => comparison.scenarios.push(term_substitution(&due, actual, official, schedule.terms.principal, valuation_date)),
+            None => comparison.notes.push(format!("Term substitution needs the MNB official {} rates", schedule.terms.currency)),
+        }
+
+        for scenario in &mut comparison.scenarios {
+            scenario.finish(schedule.disbursed_huf);
+        }
+        comparison
+    }
+}
+
+fn full_invalidity(due: &[&ScheduledInstalment], actual: &[ActualPayment], disbursed_huf: f64) -> RemedyScenario {
+    let mut scenario = RemedyScenario::new(Remedy::FullInvalidity, &[
+        "The contract cannot survive without the unfair exchange rate risk term and is void from the outset",
+        "The client repays the forints disbursed and the bank repays every instalment received",
+        "Neither side owes interest, and the bank has no claim for the use of the capital",
+    ]);
+    scenario.paid_to_date = paid_total(due, actual, &mut scenario.notes);
+    scenario.settlement = scenario.paid_to_date - disbursed_huf;
+    scenario
+}
+
+fn statutory_conversion(
+    schedule: &AmortisationSchedule,
+    actual: &[ActualPayment],
+    official: Option<&RateTable>,
+    bubor: Option<&RateTable>,
+    conversion_rate: f64,
+    valuation_date: NaiveDate,
+) -> RemedyScenario {
+    let terms = &schedule.terms;
+    let conversion_date = statutory_conversion_date();
+    let mut scenario = RemedyScenario::new(Remedy::StatutoryConversion, &[
+        "The bank's buying and selling rates are replaced by the MNB official rate and the difference is refunded",
+        "The balance outstanding on the conversion date becomes a forint debt at the statutory rate",
+        "The converted loan runs for the rest of the original term",
+        "The loan is a consumer mortgage loan in force on the conversion date",
+    ]);
+    scenario.notes.push("Refunds of unilateral interest rate increases under the 2014 settlement are not included".to_string());
+    scenario.notes.push("Act LXXVII of 2014 converted consumer mortgage loans only; check the loan type before relying on this scenario".to_string());
+
+    let before: Vec<&ScheduledInstalment> = schedule.instalments.iter()
+        .filter(|i| i.due_date < conversion_date)
+        .collect();
+    scenario.paid_to_date = paid_total(&before, actual, &mut scenario.notes);
+    scenario.settlement = match official {
+        Some(official) => spread_refund(&before, actual, official, &mut scenario.notes),
+        None => {
+            scenario.notes.push("No MNB official rates; the settlement refund is not computed".to_string());
+            0.0
+        }
+    };
+
+    let balance = before.last().map(|i| i.closing_balance).unwrap_or(terms.principal);
+    let converted = (balance * conversion_rate).round();
+    let remaining_months = terms.term_months.saturating_sub(before.len() as u32);
+    if remaining_months == 0 || balance <= 0.0 {
+        return scenario;
+    }
+
+    scenario.notes.push(format!("{:.2} {} converted at {} to {:.0} HUF", balance, terms.currency, conversion_rate, converted));
+
+    let mut converted_terms = LoanTerms {
+        principal: converted,
+        currency: "HUF".to_string(),
+        start_date: before.last().map(|i| i.due_date).unwrap_or(terms.start_date),
+        term_months: remaining_months,
+        rate_history: Vec::new(),
+        disbursement_rate: None,
+        margin: terms.margin,
+    };
+    // The loan-currency rates say nothing about the forint loan, so without
+    // BUBOR and the margin only the leg up to the conversion is valued
+    let history = match (bubor, terms.margin) {
+        (Some(bubor), Some(margin)) => bubor_rate_history(&converted_terms, bubor, margin, &mut scenario.notes),
+        _ => None,
+    };
+    let Some(history) = history else {
+        scenario.remaining_debt = converted;
+        scenario.notes.push(format!(
+            "BUBOR or the margin is unknown; the forint loan is not priced and the converted balance at {} stands as the remaining debt",
+            conversion_date
+        ));
+        return scenario;
+    };
+    converted_terms.rate_history = history;
+    scenario.assumptions.push("The forint loan bears 3-month BUBOR plus the original margin".to_string());
+
+    let converted_schedule = AmortisationSchedule::reconstruct(converted_terms, None);
+    let due_after: Vec<&ScheduledInstalment> = converted_schedule.instalments.iter()
+        .filter(|i| i.due_date <= valuation_date)
+        .collect();
+    scenario.paid_to_date += paid_total(&due_after, actual, &mut scenario.notes);
+    scenario.remaining_debt = due_after.last().map(|i| i.closing_balance).unwrap_or(converted);
+    scenario
+}
+
+fn term_substitution(
+    due: &[&ScheduledInstalment],
+    actual: &[ActualPayment],
+    official: &RateTable,
+    principal: f64,
+    valuation_date: NaiveDate,
+) -> RemedyScenario {
+    let mut scenario = RemedyScenario::new(Remedy::TermSubstitution, &[
+        "Only the exchange rate spread term is unfair; the MNB official rate replaces the bank's rates",
+        "The difference on every instalment paid is refunded",
+        "The exchange rate risk stays with the client; the balance is valued at the official rate on the valuation date",
+    ]);
+    scenario.paid_to_date = paid_total(due, actual, &mut scenario.notes);
+    scenario.settlement = spread_refund(due, actual, official, &mut scenario.notes);
+
+    let balance = due.last().map(|i| i.closing_balance).unwrap_or(principal);
+    let rate = official.rate_on(valuation_date).map(|l| l.value)
+        .or_else(|| due.iter().rev().find_map(|i| official.rate_on(i.due_date)).map(|l| l.value));
+    match rate {
+        Some(rate) => scenario.remaining_debt = balance * rate,
+        None if balance > 0.0 => scenario.notes.push("No official rate to value the outstanding balance".to_string()),
+        None => {},
+    }
+    scenario
+}
+
+fn paid_total(due: &[&ScheduledInstalment], actual: &[ActualPayment], notes: &mut Vec<String>) -> f64 {
+    let paid: Vec<Option<f64>> = due.iter()
+        .map(|i| ActualPayment::paid_for(actual, i).map(|(huf, _)| huf))
+        .collect();
+    let unknown = paid.iter().filter(|p| p.is_none()).count();
+    if unknown > 0 {
+        notes.push(format!("{} instalment(s) without a statement or stored rate are not counted as paid", unknown));
+    }
+    paid.into_iter().flatten().sum()
+}
+
+/// Paid less the instalment at the MNB official rate of its due date.
+fn spread_refund(due: &[&ScheduledInstalment], actual: &[ActualPayment], official: &RateTable, notes: &mut Vec<String>) -> f64 {
+    let mut refund = 0.0;
+    let mut missing = 0;
+    for instalment in due {
+        match (ActualPayment::paid_for(actual, instalment), official.rate_on(instalment.due_date)) {
+            (Some((paid, _)), Some(lookup)) => refund += paid - instalment.instalment * lookup.value,
+            _ => missing += 1,
+        }
+    }
+    if missing > 0 {
+        notes.push(format!("{} instalment(s) lack a payment or an official rate and are left out of the refund", missing));
+    }
+    refund
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Months;
+    use crate::damages::InterestRatePeriod;
+    use crate::models::RateKind;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn monthly(start: NaiveDate, months: u32, value: f64, kind: RateKind) -> RateTable {
+        RateTable::new(kind, (1..=months).map(|m| (start + Months::new(m), value)))
+    }
+
+    #[test]
+    fn test_remedies_side_by_side() {
+        let start = date("2014-01-01");
+        // 0% interest: 100 CHF a month, debited at 200 while the official rate is 195
+        let schedule = AmortisationSchedule::reconstruct(LoanTerms {
+            principal: 2400.0,
+            currency: "CHF".to_string(),
+            start_date: start,
+            term_months: 24,
+            rate_history: vec![InterestRatePeriod { effective_from: start, annual_rate: 0.0 }],
+            disbursement_rate: Some(150.0),
+            margin: Some(0.0),
+        }, Some(&monthly(start, 24, 200.0, RateKind::BankSell)));
+        let official = monthly(start, 24, 195.0, RateKind::Official);
+        let bubor = monthly(start, 36, 0.0, RateKind::Bubor);
+
+        let comparison = RemedyComparison::build(&schedule, &[], Some(&official), Some(&bubor), date("2016-01-01"));
+        let scenario = |remedy| comparison.scenarios.iter().find(|s| s.remedy == remedy).unwrap();
+
+        let invalidity = scenario(Remedy::FullInvalidity);
+        assert_eq!(invalidity.settlement, 480_000.0 - 360_000.0);
+        assert_eq!(invalidity.cost_of_credit, Some(0.0));
+
+        // 12 instalments before February 2015, then 1 200 CHF at 256.47 over 12 months
+        let conversion = scenario(Remedy::StatutoryConversion);
+        assert_eq!(conversion.settlement, 12.0 * 500.0);
+        assert_eq!(conversion.paid_to_date, 240_000.0 + 307_764.0);
+        assert_eq!(conversion.remaining_debt, 0.0);
+        assert_eq!(conversion.cost_of_credit, Some(547_764.0 - 6_000.0 - 360_000.0));
+
+        let substitution = scenario(Remedy::TermSubstitution);
+        assert_eq!(substitution.settlement, 24.0 * 500.0);
+        assert_eq!(substitution.cost_of_credit, Some(480_000.0 - 12_000.0 - 360_000.0));
+        assert!(comparison.notes.is_empty());
+    }
+
+    #[test]
+    fn test_conversion_without_bubor_values_only_the_first_leg() {
+        let start = date("2014-01-01");
+        let terms = LoanTerms {
+            principal: 2400.0,
+            currency: "CHF".to_string(),
+            start_date: start,
+            term_months: 24,
+            rate_history: vec![InterestRatePeriod { effective_from: start, annual_rate: 3.5 }],
+            disbursement_rate: Some(150.0),
+            margin: None,
+        };
+        let schedule = AmortisationSchedule::reconstruct(terms, Some(&monthly(start, 24, 200.0, RateKind::BankSell)));
+
+        let comparison = RemedyComparison::build(&schedule, &[], None, None, date("2016-01-01"));
+        let conversion = comparison.scenarios.iter().find(|s| s.remedy == Remedy::StatutoryConversion).unwrap();
+
+        let before: Vec<&ScheduledInstalment> = schedule.instalments.iter().take(12).collect();
+        let paid: f64 = before.iter().filter_map(|i| i.huf_amount).sum();
+        assert_eq!(conversion.paid_to_date, paid.round());
+        assert_eq!(conversion.remaining_debt, (before[11].closing_balance * 256.47).round());
+        assert!(conversion.notes.iter().any(|n| n.contains("not priced")));
+        assert!(!conversion.assumptions.iter().any(|a| a.contains("BUBOR")));
+    }
+
+    #[test]
+    fn test_outstanding_balance_and_missing_inputs() {
+        let start = date("2008-06-01");
+        let mut terms = LoanTerms {
+            principal: 1200.0,
+            currency: "USD".to_string(),
+            start_date: start,
+            term_months: 12,
+            rate_history: vec![InterestRatePeriod { effective_from: start, annual_rate: 0.0 }],
+            disbursement_rate: None,
+            margin: None,
+        };
+        let schedule = AmortisationSchedule::reconstruct(terms.clone(), None);
+        let comparison = RemedyComparison::build(&schedule, &[], None, None, date("2008-12-31"));
+        assert!(comparison.scenarios.is_empty());
+        assert_eq!(comparison.notes.len(), 3);
+
+        terms.currency = "CHF".to_string();
+        let schedule = AmortisationSchedule::reconstruct(terms, None);
+        let official = monthly(start, 12, 180.0, RateKind::Official);
+        let comparison = RemedyComparison::build(&schedule, &[], Some(&official), None, date("2008-12-31"));
+        // Six of twelve instalments paid; 600 CHF outstanding at 180
+        let substitution = comparison.scenarios.iter().find(|s| s.remedy == Remedy::TermSubstitution).unwrap();
+        assert_eq!(substitution.remaining_debt, 108_000.0);
+        assert_eq!(substitution.cost_of_credit, None);
+    }
+}
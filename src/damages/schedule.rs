@@ -0,0 +1,385 @@
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::rates::RateTable;
+
+/// Share of the scheduled amount a recorded payment may differ by before it
+/// is flagged; covers rounding and small fees booked with the instalment
+pub const DEFAULT_RECONCILIATION_TOLERANCE: f64 = 0.02;
+
+/// A contractual interest rate and the day it took effect.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterestRatePeriod {
+    pub effective_from: NaiveDate,
+    pub annual_rate: f64, // percent per annum
+}
+
+/// What the schedule is rebuilt from; see `LoanContract::loan_terms`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoanTerms {
+    pub principal: f64, // loan currency
+    pub currency: String,
+    pub start_date: NaiveDate, // disbursement; instalments fall due monthly from here
+    pub term_months: u32,
+    pub rate_history: Vec<InterestRatePeriod>,
+    pub disbursement_rate: Option<f64>, // HUF per unit of loan currency
+    pub margin: Option<f64>, // percentage points over the reference rate
+}
+
+impl LoanTerms {
+    pub fn is_huf(&self) -> bool {
+        self.currency.eq_ignore_ascii_case("HUF")
+    }
+
+    /// Rate in force on `date`: the latest change on or before it, or the
+    /// first known rate for dates before any change.
+    pub fn annual_rate_on(&self, date: NaiveDate) -> Option<f64> {
+        self.rate_history.iter()
+            .filter(|p| p.effective_from <= date)
+            .max_by_key(|p| p.effective_from)
+            .or_else(|| self.rate_history.iter().min_by_key(|p| p.effective_from))
+            .map(|p| p.annual_rate)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledInstalment {
+    pub number: u32,
+    pub due_date: NaiveDate,
+    pub annual_rate: f64,
+    pub opening_balance: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub instalment: f64, // loan currency
+    pub closing_balance: f64,
+    pub exchange_rate: Option<f64>,
+    pub rate_date: Option<NaiveDate>, // the day the applied rate was published
+    pub huf_amount: Option<f64>,
+}
+
+/// The annuity schedule a contract implies, in the loan currency and in HUF.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmortisationSchedule {
+    pub terms: LoanTerms,
+    pub disbursed_huf: Option<f64>,
+    pub instalments: Vec<ScheduledInstalment>,
+    pub notes: Vec<String>,
+}
+
+impl AmortisationSchedule {
+    /// Rebuilds the monthly annuity schedule. The instalment is recomputed
+    /// over the remaining term whenever the contractual rate changes, as the
+    /// banks did on each unilateral rate increase. Each instalment is
+    /// converted at the rate `rates` gives for its due date; without a table,
+    /// or for dates it does not cover, the HUF amount is left open.
+    pub fn reconstruct(terms: LoanTerms, rates: Option<&RateTable>) -> Self {
+        let mut notes = Vec::new();
+        let mut instalments = Vec::new();
+
+        if terms.term_months == 0 || terms.principal <= 0.0 || terms.rate_history.is_empty() {
+            notes.push("The contract lacks the amount, the term or an interest rate; no schedule is built".to_string());
+            return Self { terms, disbursed_huf: None, instalments, notes };
+        }
+
+        let mut balance = terms.principal;
+        let mut period_start = terms.start_date;
+        let mut current: Option<(f64, f64)> = None; // (annual rate, instalment)
+
+        for number in 1..=terms.term_months {
+            let due_date = terms.start_date
+                .checked_add_months(Months::new(number))
+                .unwrap_or(period_start);
+            let annual_rate = terms.annual_rate_on(period_start).unwrap_or_default();
+            let remaining = terms.term_months - number + 1;
+
+            let payment = match current {
+                Some((rate, payment)) if rate == annual_rate => payment,
+                _ => {
+                    let payment = round_cents(annuity(balance, annual_rate, remaining));
+                    current = Some((annual_rate, payment));
+                    payment
+                }
+            };
+
+            let interest = round_cents(balance * monthly_rate(annual_rate));
+            let principal = if number == terms.term_months { balance } else { (payment - interest).min(balance) };
+            let instalment = round_cents(principal + interest);
+            let closing_balance = round_cents(balance - principal);
+
+            let lookup = if terms.is_huf() { None } else { rates.and_then(|table| table.rate_on(due_date)) };
+            instalments.push(ScheduledInstalment {
+                number,
+                due_date,
+                annual_rate,
+                opening_balance: balance,
+                interest,
+                principal: round_cents(principal),
+                instalment,
+                closing_balance,
+                exchange_rate: lookup.as_ref().map(|l| l.value),
+                rate_date: lookup.as_ref().map(|l| l.rate_date),
+                huf_amount: if terms.is_huf() { Some(instalment.round()) } else { lookup.map(|l| (instalment * l.value).round()) },
+            });
+
+            balance = closing_balance;
+            period_start = due_date;
+        }
+
+        let unconverted = instalments.iter().filter(|i| i.huf_amount.is_none()).count();
+        if rates.is_none() && !terms.is_huf() {
+            notes.push(format!("No {} rate series available; instalments are not converted to HUF", terms.currency));
+        } else if unconverted > 0 {
+            notes.push(format!("{} instalment(s) fall on dates without a stored rate and are not converted to HUF", unconverted));
+        }
+        if terms.disbursement_rate.is_none() && !terms.is_huf() {
+            notes.push("No disbursement rate given".to_string());
+        }
+
+        Self {
+            disbursed_huf: terms.disbursement_rate.map(|rate| (terms.principal * rate).round()),
+            terms,
+            instalments,
+            notes,
+        }
+    }
+
+    /// A reconciliation with no payments compared, for when the records on
+    /// file cannot be told apart by contract.
+    pub fn unreconciled(&self, as_of: NaiveDate, tolerance: f64, note: String) -> ScheduleReconciliation {
+        ScheduleReconciliation {
+            as_of,
+            tolerance,
+            instalments_due: self.instalments.iter().filter(|i| i.due_date <= as_of).count(),
+            discrepancies: 0,
+            periods: Vec::new(),
+            gaps: Vec::new(),
+            notes: vec![note],
+        }
+    }
+
+    /// Compares the schedule with the payments on record up to `as_of`.
+    /// Each record is checked against the instalments due in its period, in
+    /// the record's currency; due instalments no record covers are gaps.
+    pub fn reconcile(&self, payments: &[RecordedPayment], as_of: NaiveDate, tolerance: f64) -> ScheduleReconciliation {
+        let mut periods = Vec::new();
+        let mut notes = Vec::new();
+        let due: Vec<&ScheduledInstalment> = self.instalments.iter()
+            .filter(|i| i.due_date <= as_of)
+            .collect();
+
+        for payment in payments {
+            let covered: Vec<&&ScheduledInstalment> = due.iter()
+                .filter(|i| payment.covers(i.due_date))
+                .collect();
+
+            let scheduled = if payment.currency.eq_ignore_ascii_case(&self.terms.currency) {
+                Some(covered.iter().map(|i| i.instalment).sum::<f64>())
+            } else if payment.currency.eq_ignore_ascii_case("HUF") {
+                covered.iter().map(|i| i.huf_amount).sum::<Option<f64>>()
+            } else {
+                notes.push(format!("{}: amounts in {} cannot be compared with a {} loan", payment.source, payment.currency, self.terms.currency));
+                continue;
+            };
+
+            let difference = scheduled.map(|s| payment.amount - s);
+            let discrepancy = match scheduled {
+                _ if covered.is_empty() => true,
+                Some(s) => (payment.amount - s).abs() > tolerance * s.abs(),
+                None => false,
+            };
+            if scheduled.is_none() {
+                notes.push(format!("{}: the HUF schedule is incomplete for {} to {}", payment.source, payment.period_start, payment.period_end));
+            }
+
+            periods.push(PeriodReconciliation {
+                source: payment.source.clone(),
+                period_start: payment.period_start,
+                period_end: payment.period_end,
+                currency: payment.currency.clone(),
+                instalments: covered.len(),
+                recorded: payment.amount,
+                scheduled,
+                difference,
+                discrepancy,
+            });
+        }
+
+        let mut gaps: Vec<ScheduleGap> = Vec::new();
+        for instalment in due.iter().filter(|i| !payments.iter().any(|p| p.covers(i.due_date))) {
+            match gaps.last_mut() {
+                Some(gap) if gap.last_number + 1 == instalment.number => gap.extend(instalment),
+                _ => gaps.push(ScheduleGap::start(instalment)),
+            }
+        }
+
+        ScheduleReconciliation {
+            as_of,
+            tolerance,
+            instalments_due: due.len(),
+            discrepancies: periods.iter().filter(|p| p.discrepancy).count(),
+            periods,
+            gaps,
+            notes,
+        }
+    }
+}
+
+/// A payment total from a statement, for the period it covers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedPayment {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub currency: String,
+    pub amount: f64,
+    pub source: String,
+}
+
+impl RecordedPayment {
+    fn covers(&self, date: NaiveDate) -> bool {
+        date >= self.period_start && date <= self.period_end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeriodReconciliation {
+    pub source: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub currency: String,
+    pub instalments: usize, // scheduled instalments due in the period
+    pub recorded: f64,
+    pub scheduled: Option<f64>, // None when an instalment has no HUF amount
+    pub difference: Option<f64>, // recorded less scheduled
+    pub discrepancy: bool,
+}
+
+/// Consecutive due instalments without any payment record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleGap {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub first_number: u32,
+    pub last_number: u32,
+    pub scheduled_amount: f64, // loan currency
+    pub scheduled_huf: Option<f64>,
+}
+
+impl ScheduleGap {
+    fn start(instalment: &ScheduledInstalment) -> Self {
+        Self {
+            from: instalment.due_date,
+            to: instalment.due_date,
+            first_number: instalment.number,
+            last_number: instalment.number,
+            scheduled_amount: instalment.instalment,
+            scheduled_huf: instalment.huf_amount,
+        }
+    }
+
+    fn extend(&mut self, instalment: &ScheduledInstalment) {
+        self.to = instalment.due_date;
+        self.last_number = instalment.number;
+        self.scheduled_amount = round_cents(self.scheduled_amount + instalment.instalment);
+        self.scheduled_huf = self.scheduled_huf.zip(instalment.huf_amount).map(|(a, b)| a + b);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleReconciliation {
+    pub as_of: NaiveDate,
+    pub tolerance: f64,
+    pub instalments_due: usize,
+    pub discrepancies: usize,
+    pub periods: Vec<PeriodReconciliation>,
+    pub gaps: Vec<ScheduleGap>,
+    pub notes: Vec<String>,
+}
+
+fn monthly_rate(annual_rate: f64) -> f64 {
+    annual_rate / 100.0 / 12.0
+}
+
+/// Level monthly payment that repays `balance` over `months` at `annual_rate`.
+fn annuity(balance: f64, annual_rate: f64, months: u32) -> f64 {
+    let r = monthly_rate(annual_rate);
+    if r == 0.0 {
+        return balance / months as f64;
+    }
+    balance * r / (1.0 - (1.0 + r).powi(-(months as i32)))
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RateKind;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn terms(rate_history: Vec<InterestRatePeriod>) -> LoanTerms {
+        LoanTerms {
+            principal: 100_000.0,
+            currency: "CHF".to_string(),
+            start_date: date("2007-01-15"),
+            term_months: 120,
+            rate_history,
+            disbursement_rate: Some(155.0),
+            margin: Some(2.9),
+        }
+    }
+
+    #[test]
+    fn test_annuity_repays_principal_and_follows_rate_changes() {
+        let schedule = AmortisationSchedule::reconstruct(terms(vec![
+            InterestRatePeriod { effective_from: date("2007-01-15"), annual_rate: 4.2 },
+            InterestRatePeriod { effective_from: date("2008-07-01"), annual_rate: 5.4 },
+        ]), None);
+
+        assert_eq!(schedule.instalments.len(), 120);
+        let first = &schedule.instalments[0];
+        assert_eq!(first.due_date, date("2007-02-15"));
+        // 100 000 CHF over 120 months at 4.2%
+        assert_eq!(first.instalment, 1021.98);
+        assert_eq!(first.interest, 350.0);
+
+        let raised = schedule.instalments.iter().find(|i| i.annual_rate == 5.4).unwrap();
+        assert_eq!(raised.due_date, date("2008-08-15"));
+        assert!(raised.instalment > first.instalment);
+        assert_eq!(schedule.instalments.last().unwrap().closing_balance, 0.0);
+        let repaid: f64 = schedule.instalments.iter().map(|i| i.principal).sum();
+        assert!((repaid - 100_000.0).abs() < 0.01);
+        assert_eq!(schedule.disbursed_huf, Some(15_500_000.0));
+    }
+
+    #[test]
+    fn test_reconciliation_flags_gaps_and_discrepancies() {
+        let rates = RateTable::new(RateKind::BankSell, (0..=40).map(|m| {
+            (date("2007-02-15").checked_add_months(Months::new(m)).unwrap(), 160.0)
+        }));
+        let schedule = AmortisationSchedule::reconstruct(
+            terms(vec![InterestRatePeriod { effective_from: date("2007-01-15"), annual_rate: 0.0 }]),
+            Some(&rates),
+        );
+        // 0% interest: 833.33 CHF a month, 133 333 HUF at 160
+        let payments = vec![
+            RecordedPayment { period_start: date("2007-02-01"), period_end: date("2007-12-31"), currency: "HUF".to_string(), amount: 11.0 * 133_333.0, source: "2007.pdf".to_string() },
+            RecordedPayment { period_start: date("2009-01-01"), period_end: date("2009-12-31"), currency: "CHF".to_string(), amount: 9_000.0, source: "2009.pdf".to_string() },
+        ];
+
+        let reconciliation = schedule.reconcile(&payments, date("2009-12-31"), DEFAULT_RECONCILIATION_TOLERANCE);
+
+        assert_eq!(reconciliation.instalments_due, 35);
+        assert!(!reconciliation.periods[0].discrepancy);
+        assert_eq!(reconciliation.periods[1].instalments, 12);
+        assert!(reconciliation.periods[1].discrepancy, "9 000 CHF paid against 10 000 scheduled");
+        assert_eq!(reconciliation.discrepancies, 1);
+        assert_eq!(reconciliation.gaps.len(), 1);
+        let gap = &reconciliation.gaps[0];
+        assert_eq!((gap.from, gap.to), (date("2008-01-15"), date("2008-12-15")));
+        assert_eq!(gap.scheduled_huf, Some(12.0 * 133_333.0));
+    }
+}
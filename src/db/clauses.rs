@@ -1,114 +1,78 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::{SqliteExecutor, SqlitePool, Result, types::Json};
 use uuid::Uuid;
-use crate::models::{ExtractedClause, ClausePattern};
+use chrono::{DateTime, Utc};
+use crate::models::{ExtractedClause, ClausePattern, TranslationRecord};
 
-pub async fn insert_extracted_clause(pool: &SqlitePool, clause: ExtractedClause) -> Result<ExtractedClause> {
-    let row = sqlx::query!(
+// The merged-clause fields (categories, triggers, spread terms, Annex points,
+// translation record) are JSON in TEXT columns. Clause rows are read through
+// ExtractedClause's FromRow, whose #[sqlx(json)] fields decode them.
+pub async fn insert_extracted_clause<'e, E>(executor: E, clause: ExtractedClause) -> Result<ExtractedClause>
+where
+    E: SqliteExecutor<'e>,
+{
+    sqlx::query_as::<_, ExtractedClause>(
         r#"
         INSERT INTO extracted_clauses (
             id, document_id, clause_type, clause_text, original_language,
             english_translation, start_position, end_position, confidence_score,
-            risk_level, created_at
+            risk_level, section_path, categories, matched_triggers, transparency_assessment,
+            exchange_spread, unilateral_modification, annex_points, translation_record, created_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
         RETURNING *
-        "#,
-        clause.id,
-        clause.document_id,
-        clause.clause_type,
-        clause.clause_text,
-        clause.original_language,
-        clause.english_translation,
-        clause.start_position,
-        clause.end_position,
-        clause.confidence_score,
-        clause.risk_level,
-        clause.created_at
+        "#
     )
-    .fetch_one(pool)
-    .await?;
-
-    Ok(ExtractedClause {
-        id: row.id,
-        document_id: row.document_id,
-        clause_type: row.clause_type,
-        clause_text: row.clause_text,
-        original_language: row.original_language,
-        english_translation: row.english_translation,
-        start_position: row.start_position,
-        end_position: row.end_position,
-        confidence_score: row.confidence_score,
-        risk_level: row.risk_level,
-        created_at: row.created_at,
-    })
+    .bind(clause.id)
+    .bind(clause.document_id)
+    .bind(clause.clause_type)
+    .bind(clause.clause_text)
+    .bind(clause.original_language)
+    .bind(clause.english_translation)
+    .bind(clause.start_position)
+    .bind(clause.end_position)
+    .bind(clause.confidence_score)
+    .bind(clause.risk_level)
+    .bind(clause.section_path)
+    .bind(Json(clause.categories))
+    .bind(Json(clause.matched_triggers))
+    .bind(clause.transparency_assessment)
+    .bind(clause.exchange_spread.map(Json))
+    .bind(clause.unilateral_modification.map(Json))
+    .bind(Json(clause.annex_points))
+    .bind(clause.translation_record.map(Json))
+    .bind(clause.created_at)
+    .fetch_one(executor)
+    .await
 }
 
 pub async fn get_clauses_by_document(pool: &SqlitePool, document_id: Uuid) -> Result<Vec<ExtractedClause>> {
-    let rows = sqlx::query!(
-        "SELECT * FROM extracted_clauses WHERE document_id = $1 ORDER BY start_position",
-        document_id
+    sqlx::query_as::<_, ExtractedClause>(
+        "SELECT * FROM extracted_clauses WHERE document_id = $1 ORDER BY start_position"
     )
+    .bind(document_id)
     .fetch_all(pool)
-    .await?;
-
-    Ok(rows.into_iter().map(|r| ExtractedClause {
-        id: r.id,
-        document_id: r.document_id,
-        clause_type: r.clause_type,
-        clause_text: r.clause_text,
-        original_language: r.original_language,
-        english_translation: r.english_translation,
-        start_position: r.start_position,
-        end_position: r.end_position,
-        confidence_score: r.confidence_score,
-        risk_level: r.risk_level,
-        created_at: r.created_at,
-    }).collect())
+    .await
 }
 
 pub async fn get_clauses_by_type(pool: &SqlitePool, clause_type: &str) -> Result<Vec<ExtractedClause>> {
-    let rows = sqlx::query!(
-        "SELECT * FROM extracted_clauses WHERE clause_type = $1 ORDER BY confidence_score DESC",
-        clause_type
+    sqlx::query_as::<_, ExtractedClause>(
+        r#"
+        SELECT * FROM extracted_clauses
+        WHERE clause_type = $1 OR EXISTS (SELECT 1 FROM json_each(categories) WHERE value = $1)
+        ORDER BY confidence_score DESC
+        "#
     )
+    .bind(clause_type)
     .fetch_all(pool)
-    .await?;
-
-    Ok(rows.into_iter().map(|r| ExtractedClause {
-        id: r.id,
-        document_id: r.document_id,
-        clause_type: r.clause_type,
-        clause_text: r.clause_text,
-        original_language: r.original_language,
-        english_translation: r.english_translation,
-        start_position: r.start_position,
-        end_position: r.end_position,
-        confidence_score: r.confidence_score,
-        risk_level: r.risk_level,
-        created_at: r.created_at,
-    }).collect())
+    .await
 }
 
 pub async fn get_high_risk_clauses(pool: &SqlitePool) -> Result<Vec<ExtractedClause>> {
-    let rows = sqlx::query!(
+    sqlx::query_as::<_, ExtractedClause>(
         "SELECT * FROM extracted_clauses WHERE risk_level IN ('high', 'critical') ORDER BY confidence_score DESC"
     )
     .fetch_all(pool)
-    .await?;
-
-    Ok(rows.into_iter().map(|r| ExtractedClause {
-        id: r.id,
-        document_id: r.document_id,
-        clause_type: r.clause_type,
-        clause_text: r.clause_text,
-        original_language: r.original_language,
-        english_translation: r.english_translation,
-        start_position: r.start_position,
-        end_position: r.end_position,
-        confidence_score: r.confidence_score,
-        risk_level: r.risk_level,
-        created_at: r.created_at,
-    }).collect())
+    .await
 }
 
 pub async fn insert_clause_pattern(pool: &SqlitePool, pattern: ClausePattern) -> Result<ClausePattern> {
@@ -176,39 +140,143 @@ pub async fn get_active_patterns(pool: &SqlitePool) -> Result<Vec<ClausePattern>
     }).collect())
 }
 
-pub async fn search_clauses(pool: &SqlitePool, search_term: &str) -> Result<Vec<ExtractedClause>> {
+pub async fn get_all_patterns(pool: &SqlitePool) -> Result<Vec<ClausePattern>> {
     let rows = sqlx::query!(
         r#"
-        SELECT * FROM extracted_clauses 
-        WHERE clause_text LIKE $1 
-           OR english_translation LIKE $1
-        ORDER BY confidence_score DESC
-        LIMIT 100
-        "#,
-        format!("%{}%", search_term)
+        SELECT id as "id!: Uuid", name, pattern_type, pattern_text, language, clause_category,
+               severity, description, legal_basis, is_active as "is_active: bool",
+               created_at as "created_at: DateTime<Utc>", updated_at as "updated_at: DateTime<Utc>"
+        FROM clause_patterns
+        ORDER BY clause_category, language, name
+        "#
     )
     .fetch_all(pool)
     .await?;
 
-    Ok(rows.into_iter().map(|r| ExtractedClause {
+    Ok(rows.into_iter().map(|r| ClausePattern {
         id: r.id,
-        document_id: r.document_id,
-        clause_type: r.clause_type,
-        clause_text: r.clause_text,
-        original_language: r.original_language,
-        english_translation: r.english_translation,
-        start_position: r.start_position,
-        end_position: r.end_position,
-        confidence_score: r.confidence_score,
-        risk_level: r.risk_level,
+        name: r.name,
+        pattern_type: r.pattern_type,
+        pattern_text: r.pattern_text,
+        language: r.language,
+        clause_category: r.clause_category,
+        severity: r.severity,
+        description: r.description,
+        legal_basis: r.legal_basis,
+        is_active: r.is_active,
         created_at: r.created_at,
+        updated_at: r.updated_at,
     }).collect())
 }
 
-pub async fn update_clause_translation(pool: &SqlitePool, clause_id: Uuid, translation: &str) -> Result<()> {
+pub async fn get_pattern_by_id(pool: &SqlitePool, pattern_id: Uuid) -> Result<Option<ClausePattern>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", name, pattern_type, pattern_text, language, clause_category,
+               severity, description, legal_basis, is_active as "is_active: bool",
+               created_at as "created_at: DateTime<Utc>", updated_at as "updated_at: DateTime<Utc>"
+        FROM clause_patterns
+        WHERE id = $1
+        "#,
+        pattern_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| ClausePattern {
+        id: r.id,
+        name: r.name,
+        pattern_type: r.pattern_type,
+        pattern_text: r.pattern_text,
+        language: r.language,
+        clause_category: r.clause_category,
+        severity: r.severity,
+        description: r.description,
+        legal_basis: r.legal_basis,
+        is_active: r.is_active,
+        created_at: r.created_at,
+        updated_at: r.updated_at,
+    }))
+}
+
+pub async fn update_clause_pattern(pool: &SqlitePool, pattern: ClausePattern) -> Result<ClausePattern> {
+    sqlx::query!(
+        r#"
+        UPDATE clause_patterns
+        SET name = $1, pattern_type = $2, pattern_text = $3, language = $4, clause_category = $5,
+            severity = $6, description = $7, legal_basis = $8, is_active = $9, updated_at = $10
+        WHERE id = $11
+        "#,
+        pattern.name,
+        pattern.pattern_type,
+        pattern.pattern_text,
+        pattern.language,
+        pattern.clause_category,
+        pattern.severity,
+        pattern.description,
+        pattern.legal_basis,
+        pattern.is_active,
+        pattern.updated_at,
+        pattern.id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(pattern)
+}
+
+pub async fn delete_clause_pattern(pool: &SqlitePool, pattern_id: Uuid) -> Result<bool> {
+    let result = sqlx::query!(
+        "DELETE FROM clause_patterns WHERE id = $1",
+        pattern_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Names of the default patterns seeded so far, whatever became of them since.
+pub async fn get_seeded_pattern_names(pool: &SqlitePool) -> Result<Vec<String>> {
+    let rows = sqlx::query!(r#"SELECT name as "name!" FROM seeded_clause_patterns"#)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().map(|r| r.name).collect())
+}
+
+pub async fn mark_pattern_seeded(pool: &SqlitePool, name: &str) -> Result<()> {
+    sqlx::query!(
+        "INSERT OR IGNORE INTO seeded_clause_patterns (name) VALUES ($1)",
+        name
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn search_clauses(pool: &SqlitePool, search_term: &str) -> Result<Vec<ExtractedClause>> {
+    sqlx::query_as::<_, ExtractedClause>(
+        r#"
+        SELECT * FROM extracted_clauses 
+        WHERE clause_text LIKE $1 
+           OR english_translation LIKE $1
+        ORDER BY confidence_score DESC
+        LIMIT 100
+        "#
+    )
+    .bind(format!("%{}%", search_term))
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn update_clause_translation(pool: &SqlitePool, clause_id: Uuid, translation: &str, record: &TranslationRecord) -> Result<()> {
+    let record = serde_json::to_string(record).ok();
     sqlx::query!(
-        "UPDATE extracted_clauses SET english_translation = $1 WHERE id = $2",
+        "UPDATE extracted_clauses SET english_translation = $1, translation_record = $2 WHERE id = $3",
         translation,
+        record,
         clause_id
     )
     .execute(pool)
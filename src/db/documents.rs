@@ -1,8 +1,12 @@
-use sqlx::{SqlitePool, Result};
+use sqlx::{SqliteExecutor, SqlitePool, Result};
 use uuid::Uuid;
-use crate::models::Document;
+use chrono::{DateTime, Utc};
+use crate::models::{Document, DocumentPage, DocumentSource};
 
-pub async fn insert_document(pool: &SqlitePool, document: Document) -> Result<Document> {
+pub async fn insert_document<'e, E>(executor: E, document: Document) -> Result<Document>
+where
+    E: SqliteExecutor<'e>,
+{
     let row = sqlx::query!(
         r#"
         INSERT INTO documents (
@@ -28,7 +32,7 @@ pub async fn insert_document(pool: &SqlitePool, document: Document) -> Result<Do
         document.created_at,
         document.updated_at
     )
-    .fetch_one(pool)
+    .fetch_one(executor)
     .await?;
 
     Ok(Document {
@@ -195,4 +199,112 @@ pub async fn get_documents_by_type(pool: &SqlitePool, document_type: &str) -> Re
         created_at: r.created_at,
         updated_at: r.updated_at,
     }).collect())
-}
\ No newline at end of file
+}
+
+pub async fn insert_document_page<'e, E>(executor: E, page: DocumentPage) -> Result<DocumentPage>
+where
+    E: SqliteExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO document_pages (
+            id, document_id, page_number, start_offset, end_offset, text, needs_ocr, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        page.id,
+        page.document_id,
+        page.page_number,
+        page.start_offset,
+        page.end_offset,
+        page.text,
+        page.needs_ocr,
+        page.created_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(page)
+}
+
+pub async fn get_pages_by_document(pool: &SqlitePool, document_id: Uuid) -> Result<Vec<DocumentPage>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", document_id as "document_id: Uuid", page_number, start_offset,
+               end_offset, text, needs_ocr as "needs_ocr: bool", created_at as "created_at: DateTime<Utc>"
+        FROM document_pages
+        WHERE document_id = $1
+        ORDER BY start_offset
+        "#,
+        document_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| DocumentPage {
+        id: r.id,
+        document_id: r.document_id,
+        page_number: r.page_number.map(|n| n as i32),
+        start_offset: r.start_offset as i32,
+        end_offset: r.end_offset as i32,
+        text: r.text,
+        needs_ocr: r.needs_ocr,
+        created_at: r.created_at,
+    }).collect())
+}
+
+pub async fn insert_document_source<'e, E>(executor: E, source: DocumentSource) -> Result<DocumentSource>
+where
+    E: SqliteExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO document_sources (
+            document_id, source_file, markdown_file, processor, fallback_method,
+            processed_date, metadata, imported_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        source.document_id,
+        source.source_file,
+        source.markdown_file,
+        source.processor,
+        source.fallback_method,
+        source.processed_date,
+        source.metadata,
+        source.imported_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(source)
+}
+
+pub async fn get_document_source_by_markdown_file<'e, E>(executor: E, markdown_file: &str) -> Result<Option<DocumentSource>>
+where
+    E: SqliteExecutor<'e>,
+{
+    let row = sqlx::query!(
+        r#"
+        SELECT document_id as "document_id!: Uuid", source_file, markdown_file, processor,
+               fallback_method, processed_date, metadata as "metadata: serde_json::Value",
+               imported_at as "imported_at: DateTime<Utc>"
+        FROM document_sources
+        WHERE markdown_file = $1
+        "#,
+        markdown_file
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(row.map(|r| DocumentSource {
+        document_id: r.document_id,
+        source_file: r.source_file,
+        markdown_file: r.markdown_file,
+        processor: r.processor,
+        fallback_method: r.fallback_method,
+        processed_date: r.processed_date,
+        metadata: r.metadata,
+        imported_at: r.imported_at,
+    }))
+}
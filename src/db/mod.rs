@@ -2,6 +2,8 @@ pub mod cases;
 pub mod documents;
 pub mod clauses;
 pub mod research;
+pub mod payments;
+pub mod rates;
 
 use sqlx::{SqlitePool, Result};
 use uuid::Uuid;
@@ -29,13 +31,29 @@ pub async fn seed_precedent_data(pool: &SqlitePool) -> Result<()> {
         cases::insert_case(pool, case).await?;
     }
     
-    // Insert default clause patterns
-    let patterns = crate::models::ClausePattern::get_default_patterns();
-    for pattern in patterns {
+    Ok(())
+}
+
+/// Inserts the default clause patterns that have never been seeded, so
+/// patterns added in a new release reach existing databases. Each seeded name
+/// is recorded, so a default edited, renamed, deactivated or deleted through
+/// the API is left alone on the next start.
+pub async fn seed_clause_patterns(pool: &SqlitePool) -> Result<usize> {
+    let mut known = clauses::get_seeded_pattern_names(pool).await?;
+    known.extend(clauses::get_all_patterns(pool).await?.into_iter().map(|p| p.name));
+
+    let mut inserted = 0;
+    for pattern in crate::models::ClausePattern::get_default_patterns() {
+        if known.contains(&pattern.name) {
+            continue;
+        }
+        let name = pattern.name.clone();
         clauses::insert_clause_pattern(pool, pattern).await?;
+        clauses::mark_pattern_seeded(pool, &name).await?;
+        inserted += 1;
     }
-    
-    Ok(())
+
+    Ok(inserted)
 }
 
 fn get_default_precedent_cases() -> Vec<crate::models::LegalCase> {
@@ -0,0 +1,74 @@
+use sqlx::{SqliteExecutor, SqlitePool, Result};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::models::PaymentScheduleRow;
+
+pub async fn insert_payment_schedule_row<'e, E>(executor: E, row: PaymentScheduleRow) -> Result<PaymentScheduleRow>
+where
+    E: SqliteExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO payment_schedule_rows (
+            id, document_id, page_number, row_index, due_date, currency, instalment_amount,
+            huf_debit, exchange_rate, principal, interest, outstanding_balance,
+            source_offset, source_text, created_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        "#,
+        row.id,
+        row.document_id,
+        row.page_number,
+        row.row_index,
+        row.due_date,
+        row.currency,
+        row.instalment_amount,
+        row.huf_debit,
+        row.exchange_rate,
+        row.principal,
+        row.interest,
+        row.outstanding_balance,
+        row.source_offset,
+        row.source_text,
+        row.created_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn get_payment_schedule_by_document(pool: &SqlitePool, document_id: Uuid) -> Result<Vec<PaymentScheduleRow>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", document_id as "document_id: Uuid", page_number, row_index,
+               due_date as "due_date: NaiveDate", currency, instalment_amount, huf_debit,
+               exchange_rate, principal, interest, outstanding_balance, source_offset,
+               source_text, created_at as "created_at: DateTime<Utc>"
+        FROM payment_schedule_rows
+        WHERE document_id = $1
+        ORDER BY row_index
+        "#,
+        document_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| PaymentScheduleRow {
+        id: r.id,
+        document_id: r.document_id,
+        page_number: r.page_number.map(|n| n as i32),
+        row_index: r.row_index as i32,
+        due_date: r.due_date,
+        currency: r.currency,
+        instalment_amount: r.instalment_amount,
+        huf_debit: r.huf_debit,
+        exchange_rate: r.exchange_rate,
+        principal: r.principal,
+        interest: r.interest,
+        outstanding_balance: r.outstanding_balance,
+        source_offset: r.source_offset as i32,
+        source_text: r.source_text,
+        created_at: r.created_at,
+    }).collect())
+}
@@ -0,0 +1,156 @@
+use sqlx::{SqliteConnection, SqliteExecutor, SqlitePool, Result};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::models::{RateObservation, RateSeries};
+use crate::rates::RateTable;
+
+pub async fn get_all_series(pool: &SqlitePool) -> Result<Vec<RateSeries>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", source, kind, currency, created_at as "created_at: DateTime<Utc>"
+        FROM rate_series
+        ORDER BY source, kind, currency
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| RateSeries {
+        id: r.id,
+        source: r.source,
+        kind: r.kind,
+        currency: r.currency,
+        created_at: r.created_at,
+    }).collect())
+}
+
+pub async fn find_series<'e, E>(executor: E, source: &str, kind: &str, currency: Option<&str>) -> Result<Option<RateSeries>>
+where
+    E: SqliteExecutor<'e>,
+{
+    let row = sqlx::query!(
+        r#"
+        SELECT id as "id!: Uuid", source, kind, currency, created_at as "created_at: DateTime<Utc>"
+        FROM rate_series
+        WHERE source = $1 AND kind = $2 AND currency IS $3
+        "#,
+        source,
+        kind,
+        currency
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    Ok(row.map(|r| RateSeries {
+        id: r.id,
+        source: r.source,
+        kind: r.kind,
+        currency: r.currency,
+        created_at: r.created_at,
+    }))
+}
+
+/// Returns the stored series with the same source, kind and currency, or stores `series`.
+pub async fn get_or_insert_series(conn: &mut SqliteConnection, series: RateSeries) -> Result<RateSeries> {
+    if let Some(existing) = find_series(&mut *conn, &series.source, &series.kind, series.currency.as_deref()).await? {
+        return Ok(existing);
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO rate_series (id, source, kind, currency, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        series.id,
+        series.source,
+        series.kind,
+        series.currency,
+        series.created_at
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(series)
+}
+
+/// Stores an observation, replacing the value of a date imported before.
+pub async fn upsert_observation<'e, E>(executor: E, observation: &RateObservation) -> Result<()>
+where
+    E: SqliteExecutor<'e>,
+{
+    sqlx::query!(
+        r#"
+        INSERT INTO rate_observations (series_id, rate_date, value, source_file, imported_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (series_id, rate_date) DO UPDATE SET
+            value = excluded.value,
+            source_file = excluded.source_file,
+            imported_at = excluded.imported_at
+        "#,
+        observation.series_id,
+        observation.rate_date,
+        observation.value,
+        observation.source_file,
+        observation.imported_at
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Latest observation on or before `date`; the caller applies the series' fallback rule.
+pub async fn get_observation_on_or_before(pool: &SqlitePool, series_id: Uuid, date: NaiveDate) -> Result<Option<RateObservation>> {
+    let row = sqlx::query!(
+        r#"
+        SELECT series_id as "series_id!: Uuid", rate_date as "rate_date: NaiveDate", value, source_file,
+               imported_at as "imported_at: DateTime<Utc>"
+        FROM rate_observations
+        WHERE series_id = $1 AND rate_date <= $2
+        ORDER BY rate_date DESC
+        LIMIT 1
+        "#,
+        series_id,
+        date
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|r| RateObservation {
+        series_id: r.series_id,
+        rate_date: r.rate_date,
+        value: r.value,
+        source_file: r.source_file,
+        imported_at: r.imported_at,
+    }))
+}
+
+pub async fn get_observations(pool: &SqlitePool, series_id: Uuid) -> Result<Vec<RateObservation>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT series_id as "series_id!: Uuid", rate_date as "rate_date: NaiveDate", value, source_file,
+               imported_at as "imported_at: DateTime<Utc>"
+        FROM rate_observations
+        WHERE series_id = $1
+        ORDER BY rate_date
+        "#,
+        series_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| RateObservation {
+        series_id: r.series_id,
+        rate_date: r.rate_date,
+        value: r.value,
+        source_file: r.source_file,
+        imported_at: r.imported_at,
+    }).collect())
+}
+
+/// A whole series in memory, for calculations that look up many dates.
+pub async fn load_rate_table(pool: &SqlitePool, series: &RateSeries) -> Result<Option<RateTable>> {
+    let Some(kind) = series.rate_kind() else { return Ok(None) };
+    let observations = get_observations(pool, series.id).await?;
+    Ok(Some(RateTable::from_observations(kind, &observations)))
+}
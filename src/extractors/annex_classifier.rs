@@ -0,0 +1,129 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::models::{AnnexMatch, ExtractedClause};
+
+/// Rules shipped with the binary; `ANNEX_RULES_PATH` points the server at an edited copy.
+const DEFAULT_RULES: &str = include_str!("../../data/annex_rules.json");
+
+/// One point of the Directive 93/13/EEC Annex as stored in `data/annex_rules.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnexRule {
+    pub point: String,
+    pub title: String,
+    pub text: String, // wording of the Annex point
+    #[serde(default)]
+    pub categories: Vec<String>, // clause categories the rule is limited to; empty for all
+    #[serde(default)]
+    pub patterns: Vec<String>, // any match is evidence; empty means the category alone is
+}
+
+struct CompiledRule {
+    rule: AnnexRule,
+    patterns: Vec<Regex>,
+}
+
+/// Maps extracted clauses to the points of the Annex they fall under. A clause
+/// matches a rule when it carries one of the rule's categories (if any are
+/// listed) and one of its patterns matches the clause text.
+pub struct AnnexClassifier {
+    rules: Vec<CompiledRule>,
+}
+
+impl AnnexClassifier {
+    /// Classifier over the rules bundled at build time.
+    pub fn new() -> Self {
+        let rules: Vec<AnnexRule> = serde_json::from_str(DEFAULT_RULES).expect("bundled annex rules are valid JSON");
+        Self::from_rules(rules)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let rules: Vec<AnnexRule> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(Self::from_rules(rules))
+    }
+
+    /// Compiles the rules; patterns that do not compile are logged and skipped.
+    pub fn from_rules(rules: Vec<AnnexRule>) -> Self {
+        let rules = rules.into_iter()
+            .map(|rule| {
+                let patterns = rule.patterns.iter()
+                    .filter_map(|p| match Regex::new(p) {
+                        Ok(regex) => Some(regex),
+                        Err(e) => {
+                            log::warn!("Skipping pattern of Annex point {}: {}", rule.point, e);
+                            None
+                        }
+                    })
+                    .collect();
+                CompiledRule { rule, patterns }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    pub fn rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn classify(&self, clause: &ExtractedClause) -> Vec<AnnexMatch> {
+        self.rules.iter()
+            .filter(|r| r.rule.categories.is_empty() || r.rule.categories.iter().any(|c| clause.has_category(c)))
+            .filter_map(|r| {
+                let evidence = if r.rule.patterns.is_empty() {
+                    format!("{} clause", clause.clause_type)
+                } else {
+                    r.patterns.iter()
+                        .find_map(|p| p.find(&clause.clause_text))
+                        .map(|m| m.as_str().trim().to_string())?
+                };
+                Some(AnnexMatch {
+                    point: r.rule.point.clone(),
+                    title: r.rule.title.clone(),
+                    evidence,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn clause(category: &str, text: &str) -> ExtractedClause {
+        ExtractedClause::new(Uuid::nil(), category.to_string(), text.to_string(), "hu".to_string(), 0.8)
+    }
+
+    #[test]
+    fn test_bundled_rules_cover_the_annex() {
+        let classifier = AnnexClassifier::new();
+        assert_eq!(classifier.rule_count(), 17);
+        assert!(classifier.rules.iter().all(|r| r.patterns.len() == r.rule.patterns.len()));
+    }
+
+    #[test]
+    fn test_clause_maps_to_several_points() {
+        let classifier = AnnexClassifier::new();
+        let points: Vec<String> = classifier
+            .classify(&clause(
+                "unfair_term",
+                "A Bank jogosult a kamatot egyoldalúan módosítani. A tartozásról közjegyzői okiratot kell kiállítani.",
+            ))
+            .into_iter()
+            .map(|m| m.point)
+            .collect();
+
+        assert_eq!(points, vec!["1(j)".to_string(), "1(q)".to_string()]);
+    }
+
+    #[test]
+    fn test_category_restricted_rule_needs_the_category() {
+        let classifier = AnnexClassifier::new();
+        let text = "A késedelmi kamat mértéke az ügyleti kamat kétszerese.";
+
+        assert!(classifier.classify(&clause("penalty", text)).iter().any(|m| m.point == "1(e)"));
+        assert!(classifier.classify(&clause("transparency", text)).iter().all(|m| m.point != "1(e)"));
+    }
+}
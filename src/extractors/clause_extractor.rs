@@ -1,357 +1,242 @@
+use std::collections::HashSet;
 use regex::Regex;
-use std::collections::HashMap;
 use uuid::Uuid;
-use crate::models::{ExtractedClause, ClausePattern};
-
+use crate::models::{ExtractedClause, ClausePattern, PATTERN_SEVERITIES};
+use super::section_parser::{SectionParser, SectionTree};
+use super::context_window::{ContextWindow, char_offset};
+use super::transparency_classifier::{TransparencyClassifier, TransparencyAssessment};
+use super::exchange_spread::ExchangeSpreadExtractor;
+use super::unilateral_modification::UnilateralModificationAnalyzer;
+use super::annex_classifier::AnnexClassifier;
+use super::language_detector::{LanguageSpan, UNDETERMINED};
+
+/// Numbered points longer than this are quoted through a window instead of in full.
+const MAX_POINT_CHARS: usize = 1500;
+
+/// Matches clauses using the active rows of `clause_patterns`. Patterns apply to
+/// text in their own language; "multilingual" patterns apply to every document.
 pub struct ClauseExtractor {
-    patterns: HashMap<String, Vec<ClausePattern>>,
-    fx_risk_regex: Regex,
-    transparency_regex: Regex,
-    interest_rate_regex: Regex,
-    penalty_regex: Regex,
+    patterns: Vec<CompiledPattern>,
+    transparency: TransparencyClassifier,
+    spread: ExchangeSpreadExtractor,
+    modification: UnilateralModificationAnalyzer,
+    annex: AnnexClassifier,
+}
+
+struct CompiledPattern {
+    pattern: ClausePattern,
+    regex: Regex,
 }
 
 #[derive(Debug)]
 pub struct ExtractionResult {
     pub clauses: Vec<ExtractedClause>,
     pub confidence: f32,
-    pub language_detected: String,
+    pub language_detected: String, // the language covering most of the text
+    pub language_spans: Vec<LanguageSpan>, // languages the patterns were applied by
 }
 
 impl ClauseExtractor {
+    /// Extractor over the built-in default patterns. The server builds its
+    /// extractor from the database with `from_patterns` instead.
     pub fn new() -> Self {
-        let mut patterns = HashMap::new();
-        
-        // Load default patterns
-        let default_patterns = ClausePattern::get_default_patterns();
-        for pattern in default_patterns {
-            patterns.entry(pattern.language.clone())
-                .or_insert_with(Vec::new)
-                .push(pattern);
-        }
+        Self::from_patterns(ClausePattern::get_default_patterns())
+    }
+
+    /// Compiles the active patterns; patterns that do not compile are logged and skipped.
+    pub fn from_patterns(patterns: Vec<ClausePattern>) -> Self {
+        let patterns = patterns.into_iter()
+            .filter(|p| p.is_active)
+            .filter_map(|pattern| match Self::compile_pattern(&pattern) {
+                Ok(regex) => Some(CompiledPattern { pattern, regex }),
+                Err(e) => {
+                    log::warn!("Skipping clause pattern '{}': {}", pattern.name, e);
+                    None
+                }
+            })
+            .collect();
 
         Self {
             patterns,
-            fx_risk_regex: Regex::new(
-                r"(?i)(deviza|foreign\s+currency|árfolyam|exchange\s+rate|currency\s+risk|CHF|švýcarský\s+frank|švajcarski\s+franak|waluta|kurs\s+wymiany|měnové\s+riziko)"
-            ).unwrap(),
-            transparency_regex: Regex::new(
-                r"(?i)(tájékoztatás|information|disclosure|warning|risk|figyelmeztetés|informování|varování|ostrzeżenie|informacja)"
-            ).unwrap(),
-            interest_rate_regex: Regex::new(
-                r"(?i)(kamat|interest\s+rate|úrok|změna\s+úroku|rate\s+change|oprocentowanie|stopa\s+procentowa)"
-            ).unwrap(),
-            penalty_regex: Regex::new(
-                r"(?i)(penalty|fee|költség|díj|sankce|poplatek|fine|kara|opłata)"
-            ).unwrap(),
+            transparency: TransparencyClassifier::new(),
+            spread: ExchangeSpreadExtractor::new(),
+            modification: UnilateralModificationAnalyzer::new(),
+            annex: AnnexClassifier::new(),
         }
     }
 
-    pub async fn extract_clauses(&self, document_id: Uuid, text: &str, language: &str) -> ExtractionResult {
-        let mut clauses = Vec::new();
-        let mut total_confidence = 0.0;
-        let mut clause_count = 0;
-
-        // Extract FX risk clauses
-        let fx_clauses = self.extract_fx_risk_clauses(document_id, text, language);
-        total_confidence += fx_clauses.iter().map(|c| c.confidence_score).sum::<f32>();
-        clause_count += fx_clauses.len();
-        clauses.extend(fx_clauses);
-
-        // Extract transparency clauses
-        let transparency_clauses = self.extract_transparency_clauses(document_id, text, language);
-        total_confidence += transparency_clauses.iter().map(|c| c.confidence_score).sum::<f32>();
-        clause_count += transparency_clauses.len();
-        clauses.extend(transparency_clauses);
-
-        // Extract interest rate clauses
-        let interest_clauses = self.extract_interest_rate_clauses(document_id, text, language);
-        total_confidence += interest_clauses.iter().map(|c| c.confidence_score).sum::<f32>();
-        clause_count += interest_clauses.len();
-        clauses.extend(interest_clauses);
-
-        // Extract penalty clauses
-        let penalty_clauses = self.extract_penalty_clauses(document_id, text, language);
-        total_confidence += penalty_clauses.iter().map(|c| c.confidence_score).sum::<f32>();
-        clause_count += penalty_clauses.len();
-        clauses.extend(penalty_clauses);
-
-        // Extract contextual clauses (sophisticated pattern matching)
-        let contextual_clauses = self.extract_contextual_clauses(document_id, text, language);
-        total_confidence += contextual_clauses.iter().map(|c| c.confidence_score).sum::<f32>();
-        clause_count += contextual_clauses.len();
-        clauses.extend(contextual_clauses);
-
-        let average_confidence = if clause_count > 0 {
-            total_confidence / clause_count as f32
-        } else {
-            0.0
-        };
-
-        ExtractionResult {
-            clauses,
-            confidence: average_confidence,
-            language_detected: language.to_string(),
+    /// Builds the matcher for a pattern. `regex` patterns are used as written;
+    /// `keyword` patterns are `|`, `,` or newline separated words matched
+    /// case-insensitively from a word start, so "díj" also finds "díjat".
+    pub fn compile_pattern(pattern: &ClausePattern) -> Result<Regex, String> {
+        match pattern.pattern_type.as_str() {
+            "regex" => Regex::new(&pattern.pattern_text).map_err(|e| e.to_string()),
+            "keyword" => {
+                let keywords: Vec<String> = pattern.pattern_text
+                    .split(['|', ',', '\n'])
+                    .map(str::trim)
+                    .filter(|k| !k.is_empty())
+                    .map(|k| regex::escape(k).replace(' ', r"\s+"))
+                    .collect();
+                if keywords.is_empty() {
+                    return Err("keyword pattern has no keywords".to_string());
+                }
+                Regex::new(&format!(r"(?i)\b(?:{})", keywords.join("|"))).map_err(|e| e.to_string())
+            },
+            other => Err(format!("unsupported pattern type '{}'", other)),
         }
     }
 
-    fn extract_fx_risk_clauses(&self, document_id: Uuid, text: &str, language: &str) -> Vec<ExtractedClause> {
-        let mut clauses = Vec::new();
-        
-        // Hungarian FX risk patterns
-        let hungarian_patterns = [
-            r"(?i)devizaalapú\s+hitel",
-            r"(?i)árfolyamkockázat",
-            r"(?i)deviza.*kockázat",
-            r"(?i)svájci\s+frank",
-            r"(?i)CHF.*alapú",
-            r"(?i)devizában\s+denominált",
-            r"(?i)árfolyam.*változás",
-            r"(?i)deviza.*kamat",
-        ];
+    /// Replaces the bundled Annex rules, e.g. with an edited rules file.
+    pub fn with_annex_classifier(mut self, annex: AnnexClassifier) -> Self {
+        self.annex = annex;
+        self
+    }
 
-        // English FX risk patterns
-        let english_patterns = [
-            r"(?i)foreign\s+currency\s+loan",
-            r"(?i)exchange\s+rate\s+risk",
-            r"(?i)currency\s+fluctuation",
-            r"(?i)Swiss\s+franc",
-            r"(?i)CHF\s+loan",
-            r"(?i)foreign\s+exchange",
-            r"(?i)currency\s+exposure",
-            r"(?i)FX\s+risk",
-        ];
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
 
-        let patterns = match language {
-            "hu" | "hungarian" => &hungarian_patterns[..],
-            "en" | "english" => &english_patterns[..],
-            _ => &hungarian_patterns[..], // Default to Hungarian for region
+    pub async fn extract_clauses(&self, document_id: Uuid, text: &str, language: &str) -> ExtractionResult {
+        let whole_text = LanguageSpan {
+            start: 0,
+            end: text.chars().count(),
+            language: language.to_string(),
+            confidence: 1.0,
         };
+        self.extract_clauses_by_span(document_id, text, &[whole_text], language).await
+    }
 
-        for pattern_str in patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                for mat in regex.find_iter(text) {
-                    let context = self.extract_context(text, mat.start(), mat.end(), 200);
-                    let confidence = self.calculate_fx_confidence(&context);
-                    
-                    if confidence > 0.3 {
-                        let mut clause = ExtractedClause::new(
-                            document_id,
-                            "fx_risk".to_string(),
-                            context,
-                            language.to_string(),
-                            confidence,
-                        );
-                        clause.start_position = Some(mat.start() as i32);
-                        clause.end_position = Some(mat.end() as i32);
-                        clause.calculate_risk_level();
-                        clauses.push(clause);
-                    }
-                }
+    /// Extraction for documents in several languages (see
+    /// `LanguageDetector::detect_mixed_language`): each match is kept only if
+    /// its pattern is in the language of the span the match starts in.
+    /// Undetermined spans, and text no span covers, use `default_language`.
+    pub async fn extract_clauses_by_span(
+        &self,
+        document_id: Uuid,
+        text: &str,
+        spans: &[LanguageSpan],
+        default_language: &str,
+    ) -> ExtractionResult {
+        let default_language = normalize_language(default_language);
+        let span_language = |span: &LanguageSpan| -> String {
+            match span.language.as_str() {
+                UNDETERMINED => default_language.to_string(),
+                language => normalize_language(language).to_string(),
             }
-        }
-
-        clauses
-    }
+        };
+        let language_at = |offset: usize| -> String {
+            spans.iter()
+                .find(|s| s.contains(offset))
+                .map_or_else(|| default_language.to_string(), span_language)
+        };
 
-    fn extract_transparency_clauses(&self, document_id: Uuid, text: &str, language: &str) -> Vec<ExtractedClause> {
+        let sections = SectionParser::new().parse(text);
         let mut clauses = Vec::new();
 
-        // Hungarian transparency patterns
-        let hungarian_patterns = [
-            r"(?i)tájékoztatás.*kockázat",
-            r"(?i)figyelmeztetés",
-            r"(?i)kockázat.*ismertetés",
-            r"(?i)információ.*nyújtás",
-            r"(?i)kockázat.*felvilágosítás",
-        ];
-
-        // English transparency patterns  
-        let english_patterns = [
-            r"(?i)risk\s+disclosure",
-            r"(?i)information\s+provided",
-            r"(?i)warning.*risk",
-            r"(?i)disclosure.*currency",
-            r"(?i)informed.*decision",
-        ];
-
-        let patterns = match language {
-            "hu" | "hungarian" => &hungarian_patterns[..],
-            "en" | "english" => &english_patterns[..],
-            _ => &hungarian_patterns[..],
-        };
-
-        for pattern_str in patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                for mat in regex.find_iter(text) {
-                    let context = self.extract_context(text, mat.start(), mat.end(), 150);
-                    let confidence = self.calculate_transparency_confidence(&context);
-                    
-                    if confidence > 0.4 {
-                        let mut clause = ExtractedClause::new(
-                            document_id,
-                            "transparency".to_string(),
-                            context,
-                            language.to_string(),
-                            confidence,
-                        );
-                        clause.start_position = Some(mat.start() as i32);
-                        clause.end_position = Some(mat.end() as i32);
-                        clause.calculate_risk_level();
-                        clauses.push(clause);
-                    }
-                }
+        let mut languages: Vec<String> = spans.iter().map(span_language).collect();
+        languages.push(default_language.to_string());
+        languages.sort();
+        languages.dedup();
+        for language in &languages {
+            if !self.patterns.iter().any(|p| &p.pattern.language == language) {
+                log::info!("No clause patterns for language '{}'; only multilingual patterns apply", language);
             }
         }
 
-        clauses
-    }
-
-    fn extract_interest_rate_clauses(&self, document_id: Uuid, text: &str, language: &str) -> Vec<ExtractedClause> {
-        let mut clauses = Vec::new();
-
-        // Hungarian interest rate patterns
-        let hungarian_patterns = [
-            r"(?i)kamat.*változás",
-            r"(?i)kamatláb.*módosítás",
-            r"(?i)kamat.*emelés",
-            r"(?i)változó\s+kamat",
-            r"(?i)kamat.*feltétel",
-        ];
-
-        // English interest rate patterns
-        let english_patterns = [
-            r"(?i)interest\s+rate\s+change",
-            r"(?i)variable\s+interest",
-            r"(?i)rate\s+adjustment",
-            r"(?i)interest.*modification",
-            r"(?i)rate\s+variation",
-        ];
-
-        let patterns = match language {
-            "hu" | "hungarian" => &hungarian_patterns[..],
-            "en" | "english" => &english_patterns[..],
-            _ => &hungarian_patterns[..],
-        };
+        for compiled in self.patterns.iter().filter(|p| p.pattern.language == "multilingual" || languages.contains(&p.pattern.language)) {
+            let category = compiled.pattern.clause_category.as_str();
+            for mat in compiled.regex.find_iter(text) {
+                let match_offset = char_offset(text, mat.start());
+                let language = language_at(match_offset);
+                if compiled.pattern.language != "multilingual" && compiled.pattern.language != language {
+                    continue;
+                }
 
-        for pattern_str in patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                for mat in regex.find_iter(text) {
-                    let context = self.extract_context(text, mat.start(), mat.end(), 120);
-                    let confidence = 0.7; // Standard confidence for interest clauses
-                    
-                    let mut clause = ExtractedClause::new(
-                        document_id,
-                        "interest_rate".to_string(),
-                        context,
-                        language.to_string(),
-                        confidence,
-                    );
-                    clause.start_position = Some(mat.start() as i32);
-                    clause.end_position = Some(mat.end() as i32);
-                    clause.calculate_risk_level();
-                    clauses.push(clause);
+                let window = self.clause_context(text, &sections, mat.start(), mat.end(), context_length(category));
+                let confidence = self.pattern_confidence(&compiled.pattern, &window.text);
+                if confidence <= minimum_confidence(category) {
+                    continue;
                 }
+
+                let mut clause = ExtractedClause::new(
+                    document_id,
+                    category.to_string(),
+                    window.text,
+                    language,
+                    confidence,
+                );
+                clause.start_position = Some(window.start as i32);
+                clause.end_position = Some(window.end as i32);
+                clause.section_path = sections.describe(match_offset);
+                clause.matched_triggers.push(mat.as_str().trim().to_string());
+                clause.calculate_risk_level();
+                raise_risk_level(&mut clause, &compiled.pattern.severity);
+                clauses.push(clause);
             }
         }
 
-        clauses
-    }
-
-    fn extract_penalty_clauses(&self, document_id: Uuid, text: &str, language: &str) -> Vec<ExtractedClause> {
-        let mut clauses = Vec::new();
-
-        // Hungarian penalty patterns
-        let hungarian_patterns = [
-            r"(?i)késedelmi\s+kamat",
-            r"(?i)díj.*felszámítás",
-            r"(?i)költség.*visel",
-            r"(?i)bírság",
-            r"(?i)pótlék.*fizetés",
-        ];
-
-        // English penalty patterns
-        let english_patterns = [
-            r"(?i)penalty.*fee",
-            r"(?i)additional\s+charges",
-            r"(?i)late\s+payment",
-            r"(?i)default\s+interest",
-            r"(?i)administrative\s+fee",
-        ];
+        let mut clauses = merge_overlapping_clauses(text, clauses);
+        for clause in clauses.iter_mut().filter(|c| c.has_category("transparency")) {
+            let assessment = self.transparency.classify(&clause.clause_text).assessment;
+            clause.transparency_assessment = Some(assessment.as_str().to_string());
+        }
+        for clause in clauses.iter_mut().filter(|c| c.has_category("exchange_spread")) {
+            clause.exchange_spread = Some(self.spread.extract(&clause.clause_text));
+        }
+        for clause in clauses.iter_mut().filter(|c| c.has_category("unfair_term") || c.has_category("interest_rate")) {
+            clause.unilateral_modification = self.modification.analyze(&clause.clause_text);
+        }
+        for clause in clauses.iter_mut() {
+            clause.annex_points = self.annex.classify(clause);
+        }
 
-        let patterns = match language {
-            "hu" | "hungarian" => &hungarian_patterns[..],
-            "en" | "english" => &english_patterns[..],
-            _ => &hungarian_patterns[..],
+        let average_confidence = if clauses.is_empty() {
+            0.0
+        } else {
+            clauses.iter().map(|c| c.confidence_score).sum::<f32>() / clauses.len() as f32
         };
 
-        for pattern_str in patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                for mat in regex.find_iter(text) {
-                    let context = self.extract_context(text, mat.start(), mat.end(), 100);
-                    let confidence = 0.8; // High confidence for penalty clauses
-                    
-                    let mut clause = ExtractedClause::new(
-                        document_id,
-                        "penalty".to_string(),
-                        context,
-                        language.to_string(),
-                        confidence,
-                    );
-                    clause.start_position = Some(mat.start() as i32);
-                    clause.end_position = Some(mat.end() as i32);
-                    clause.calculate_risk_level();
-                    clauses.push(clause);
-                }
-            }
+        ExtractionResult {
+            clauses,
+            confidence: average_confidence,
+            language_detected: dominant_language(spans).map_or_else(|| default_language.to_string(), span_language),
+            language_spans: spans.to_vec(),
         }
-
-        clauses
     }
 
-    fn extract_contextual_clauses(&self, document_id: Uuid, text: &str, language: &str) -> Vec<ExtractedClause> {
-        let mut clauses = Vec::new();
-
-        // Look for contextual patterns that might indicate unfair terms
-        let contextual_patterns = [
-            r"(?i)(bank|hitelező).*jogosult.*egyoldalú",
-            r"(?i)bank.*right.*unilateral",
-            r"(?i)szerződés.*módosítás.*bank",
-            r"(?i)contract.*modification.*bank",
-            r"(?i)kizárólag.*bank.*dönt",
-            r"(?i)solely.*bank.*discretion",
-        ];
+    fn pattern_confidence(&self, pattern: &ClausePattern, context: &str) -> f32 {
+        match pattern.clause_category.as_str() {
+            "fx_risk" => self.calculate_fx_confidence(context),
+            "transparency" => self.calculate_transparency_confidence(context),
+            "exchange_spread" => if self.spread.extract(context).is_quantified() { 0.85 } else { 0.7 },
+            "interest_rate" => 0.7,
+            "penalty" => 0.8,
+            "early_repayment" => 0.7,
+            "insurance_tie_in" => 0.65,
+            // Acceleration triggered by exchange-rate moves is the case our clients face
+            "collateral_acceleration" => if is_exchange_rate_linked(context) { 0.9 } else { 0.75 },
+            // Each failed DH act principle makes the clause a stronger finding
+            "unfair_term" => match self.modification.analyze(context) {
+                Some(analysis) => (0.6 + 0.05 * analysis.failed().count() as f32).min(0.95),
+                None => 0.6,
+            },
+            // 0.5 for informational up to 0.8 for critical
+            _ => 0.5 + 0.1 * severity_rank(&pattern.severity) as f32,
+        }
+    }
 
-        for pattern_str in contextual_patterns {
-            if let Ok(regex) = Regex::new(pattern_str) {
-                for mat in regex.find_iter(text) {
-                    let context = self.extract_context(text, mat.start(), mat.end(), 180);
-                    let confidence = 0.6;
-                    
-                    let mut clause = ExtractedClause::new(
-                        document_id,
-                        "unfair_term".to_string(),
-                        context,
-                        language.to_string(),
-                        confidence,
-                    );
-                    clause.start_position = Some(mat.start() as i32);
-                    clause.end_position = Some(mat.end() as i32);
-                    clause.calculate_risk_level();
-                    clauses.push(clause);
-                }
+    /// The whole numbered point around a match when the contract has one of a
+    /// reasonable size, otherwise a sentence-bounded window. Positions are
+    /// character offsets into `text`.
+    fn clause_context(&self, text: &str, sections: &SectionTree, start: usize, end: usize, context_length: usize) -> ContextWindow {
+        if let Some((point_start, point_end)) = sections.point_range_at(char_offset(text, start)) {
+            if point_end - point_start <= MAX_POINT_CHARS {
+                return ContextWindow::from_char_range(text, point_start, point_end);
             }
         }
 
-        clauses
-    }
-
-    fn extract_context(&self, text: &str, start: usize, end: usize, context_length: usize) -> String {
-        let half_context = context_length / 2;
-        let context_start = start.saturating_sub(half_context);
-        let context_end = std::cmp::min(end + half_context, text.len());
-        
-        text[context_start..context_end].to_string()
+        ContextWindow::around_match(text, start, end, context_length)
     }
 
     fn calculate_fx_confidence(&self, context: &str) -> f32 {
@@ -384,28 +269,282 @@ impl ClauseExtractor {
         confidence.clamp(0.0, 1.0)
     }
 
+    /// Clauses the classifier finds negated or reduced to a borrower declaration
+    /// are the strongest transparency findings; a bank warning is the weakest.
+    /// A trigger without any transparency signal is still emitted, just above
+    /// the threshold: a disclosure clause that informs of nothing is a finding.
     fn calculate_transparency_confidence(&self, context: &str) -> f32 {
-        let mut confidence: f32 = 0.4;
-        
-        // Look for transparency indicators
-        let transparency_terms = [
-            "tájékoztatás", "information", "disclosure", "warning", 
-            "figyelmeztetés", "risk", "kockázat"
-        ];
-        
-        for term in transparency_terms {
-            if context.to_lowercase().contains(&term.to_lowercase()) {
-                confidence += 0.1;
-            }
+        match self.transparency.classify(context).assessment {
+            TransparencyAssessment::Negated => 0.9,
+            TransparencyAssessment::BoilerplateAcknowledgement => 0.8,
+            TransparencyAssessment::AdequateWarning => 0.6,
+            TransparencyAssessment::Absent => 0.45,
         }
-        
-        // Check if it's actually providing information vs. just mentioning it
-        if context.to_lowercase().contains("nem") && 
-           context.to_lowercase().contains("tájékoztat") {
-            // "nem tájékoztatott" = "did not inform" - this is bad for the bank
-            confidence += 0.3;
+    }
+}
+
+fn normalize_language(language: &str) -> &str {
+    match language {
+        "hungarian" => "hu",
+        "english" => "en",
+        "czech" => "cs",
+        "polish" => "pl",
+        "romanian" => "ro",
+        other => other,
+    }
+}
+
+/// The span language covering the most characters.
+fn dominant_language(spans: &[LanguageSpan]) -> Option<&LanguageSpan> {
+    let mut totals: Vec<(&LanguageSpan, usize)> = Vec::new();
+    for span in spans {
+        match totals.iter_mut().find(|(s, _)| s.language == span.language) {
+            Some((_, total)) => *total += span.end - span.start,
+            None => totals.push((span, span.end - span.start)),
         }
+    }
+    totals.into_iter().max_by_key(|(_, total)| *total).map(|(span, _)| span)
+}
 
-        confidence.clamp(0.0, 1.0)
+fn context_length(category: &str) -> usize {
+    match category {
+        "fx_risk" => 200,
+        "exchange_spread" => 200,
+        "collateral_acceleration" => 200,
+        "early_repayment" | "insurance_tie_in" => 180,
+        "unfair_term" => 180,
+        "transparency" => 150,
+        "interest_rate" => 120,
+        "penalty" => 100,
+        _ => 150,
     }
-}
\ No newline at end of file
+}
+
+fn is_exchange_rate_linked(context: &str) -> bool {
+    let lower = context.to_lowercase();
+    ["árfolyam", "deviza", "exchange rate", "currency", "kurs", "kurz"].iter().any(|t| lower.contains(t))
+}
+
+fn minimum_confidence(category: &str) -> f32 {
+    match category {
+        "fx_risk" => 0.3,
+        "transparency" => 0.4,
+        _ => 0.0,
+    }
+}
+
+/// Position in PATTERN_SEVERITIES; an unknown severity ranks as informational
+fn severity_rank(severity: &str) -> usize {
+    PATTERN_SEVERITIES.iter().position(|s| *s == severity).unwrap_or(0)
+}
+
+fn risk_rank(level: &str) -> usize {
+    const LEVELS: [&str; 4] = ["low", "medium", "high", "critical"];
+    LEVELS.iter().position(|l| *l == level).unwrap_or(0)
+}
+
+/// A pattern's severity sets a floor on the clause risk level: critical
+/// patterns never yield less than "high", warnings never less than "medium".
+fn raise_risk_level(clause: &mut ExtractedClause, severity: &str) {
+    let floor = match severity {
+        "critical" => "high",
+        "warning" => "medium",
+        _ => return,
+    };
+
+    if risk_rank(&clause.risk_level) < risk_rank(floor) {
+        clause.risk_level = floor.to_string();
+    }
+}
+
+fn span(clause: &ExtractedClause) -> (usize, usize) {
+    (
+        clause.start_position.unwrap_or(0) as usize,
+        clause.end_position.unwrap_or(0) as usize,
+    )
+}
+
+/// Two spans describe the same clause when they share at least half of the shorter one.
+/// Windows that merely touch at a sentence edge stay separate clauses.
+pub(super) fn spans_overlap(a: (usize, usize), b: (usize, usize)) -> bool {
+    let shared = a.1.min(b.1).saturating_sub(a.0.max(b.0));
+    let shorter = (a.1 - a.0).min(b.1 - b.0);
+    shared > 0 && shared * 2 >= shorter
+}
+
+/// Several patterns (and several matches of one pattern) usually hit the same
+/// contract point. Overlapping clauses are merged into one covering the union
+/// of their spans: the highest-confidence match decides `clause_type`,
+/// confidence and section, the other categories and all triggers are kept,
+/// and the highest risk level wins.
+fn merge_overlapping_clauses(text: &str, mut clauses: Vec<ExtractedClause>) -> Vec<ExtractedClause> {
+    clauses.sort_by_key(|c| {
+        let (start, end) = span(c);
+        (start, std::cmp::Reverse(end))
+    });
+
+    let mut merged: Vec<ExtractedClause> = Vec::new();
+    for clause in clauses {
+        match merged.last_mut() {
+            Some(last) if spans_overlap(span(last), span(&clause)) => absorb_clause(text, last, clause),
+            _ => merged.push(clause),
+        }
+    }
+
+    merged
+}
+
+fn absorb_clause(text: &str, target: &mut ExtractedClause, other: ExtractedClause) {
+    let (target_start, target_end) = span(target);
+    let (other_start, other_end) = span(&other);
+
+    let mut categories = std::mem::take(&mut target.categories);
+    if other.confidence_score > target.confidence_score {
+        target.clause_type = other.clause_type.clone();
+        target.confidence_score = other.confidence_score;
+        if other.section_path.is_some() {
+            target.section_path = other.section_path.clone();
+        }
+        categories = other.categories.iter().chain(&categories).cloned().collect();
+    } else {
+        categories.extend(other.categories.iter().cloned());
+    }
+    let mut seen = HashSet::new();
+    categories.retain(|c| seen.insert(c.clone()));
+    target.categories = categories;
+
+    for trigger in other.matched_triggers {
+        if !target.matched_triggers.contains(&trigger) {
+            target.matched_triggers.push(trigger);
+        }
+    }
+
+    if risk_rank(&other.risk_level) > risk_rank(&target.risk_level) {
+        target.risk_level = other.risk_level;
+    }
+
+    let start = target_start.min(other_start);
+    let end = target_end.max(other_end);
+    if (start, end) != (target_start, target_end) {
+        let window = ContextWindow::from_char_range(text, start, end);
+        target.clause_text = window.text;
+        target.start_position = Some(window.start as i32);
+        target.end_position = Some(window.end as i32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_patterns_use_the_shared_severities() {
+        for pattern in ClausePattern::get_default_patterns() {
+            assert!(PATTERN_SEVERITIES.contains(&pattern.severity.as_str()), "{}: {}", pattern.name, pattern.severity);
+        }
+
+        let mut clause = ExtractedClause::new(Uuid::nil(), "penalty".to_string(), String::new(), "hu".to_string(), 0.5);
+        clause.risk_level = "low".to_string();
+        raise_risk_level(&mut clause, "warning");
+        assert_eq!(clause.risk_level, "medium");
+        raise_risk_level(&mut clause, "critical");
+        assert_eq!(clause.risk_level, "high");
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_matches_merge_into_one_clause() {
+        let text = "6. Árfolyamkockázat\n\
+            6.1. Az Adós tudomásul veszi, hogy a kölcsön svájci frank (CHF) alapú devizahitel, \
+            az árfolyamkockázatot az Adós viseli, és a törlesztőrészlet forintban a deviza árfolyam \
+            változásával emelkedhet.\n\
+            7. Késedelmi kamat\n\
+            7.1. Késedelem esetén a Bank késedelmi kamatot számít fel.\n";
+
+        let result = ClauseExtractor::new().extract_clauses(Uuid::nil(), text, "hu").await;
+
+        let fx: Vec<&ExtractedClause> = result.clauses.iter().filter(|c| c.has_category("fx_risk")).collect();
+        assert_eq!(fx.len(), 1);
+        assert!(fx[0].matched_triggers.len() > 1);
+        assert_eq!(fx[0].categories[0], fx[0].clause_type);
+        assert!(fx[0].clause_text.starts_with("6. Árfolyamkockázat"));
+        assert!(fx[0].clause_text.ends_with("emelkedhet."));
+
+        // The penalty point is a separate span and stays a separate clause
+        assert!(result.clauses.iter().any(|c| c.has_category("penalty") && !c.has_category("fx_risk")));
+    }
+
+    #[tokio::test]
+    async fn test_early_repayment_insurance_and_acceleration_families() {
+        let text = "8. Előtörlesztés\n\
+            8.1. Előtörlesztés esetén az Adós az előtörlesztett összeg 2%-ának megfelelő díjat fizet.\n\
+            9. Biztosítás\n\
+            9.1. Az Adós köteles az ingatlanra vagyonbiztosítást kötni, amelynek kedvezményezettje a Bank.\n\
+            10. Fedezet\n\
+            10.1. Ha a fedezet értéke az árfolyam változása miatt elégtelenné válik, a Bank pótfedezetet kérhet, \
+            vagy a kölcsönt felmondhatja és egy összegben esedékessé teheti.\n";
+
+        let result = ClauseExtractor::new().extract_clauses(Uuid::nil(), text, "hu").await;
+        let find = |category: &str| result.clauses.iter().find(|c| c.has_category(category));
+
+        assert!(find("early_repayment").is_some());
+        assert!(find("insurance_tie_in").is_some());
+        let acceleration = find("collateral_acceleration").unwrap();
+        assert!(acceleration.risk_level == "high" || acceleration.risk_level == "critical");
+    }
+
+    #[tokio::test]
+    async fn test_spans_select_patterns_by_language() {
+        let hungarian = "1. Az Adós tudomásul veszi, hogy a kölcsön svájci frank alapú devizahitel, \
+            és az árfolyamkockázatot az Adós viseli.\n";
+        let english = "2. The borrower bears the exchange rate risk of the Swiss franc loan.\n";
+        let text = format!("{hungarian}{english}");
+        let split = hungarian.chars().count();
+        let spans = [
+            LanguageSpan { start: 0, end: split, language: "hu".to_string(), confidence: 0.99 },
+            LanguageSpan { start: split, end: text.chars().count(), language: "en".to_string(), confidence: 0.99 },
+        ];
+
+        let result = ClauseExtractor::new().extract_clauses_by_span(Uuid::nil(), &text, &spans, "hu").await;
+
+        let languages: HashSet<&str> = result.clauses.iter().map(|c| c.original_language.as_str()).collect();
+        assert_eq!(languages, HashSet::from(["hu", "en"]));
+        assert!(result.clauses.iter()
+            .filter(|c| c.original_language == "en")
+            .all(|c| c.start_position.unwrap() as usize >= split));
+        assert_eq!(result.language_spans.len(), 2);
+
+        // With the document-level language alone the English point is only seen by multilingual patterns
+        let single = ClauseExtractor::new().extract_clauses(Uuid::nil(), &text, "hu").await;
+        assert!(single.clauses.iter().all(|c| c.original_language == "hu"));
+    }
+
+    #[tokio::test]
+    async fn test_transparency_trigger_without_signal_is_emitted_as_absent() {
+        // A reference to the annexed form matches the Polish disclosure pattern but informs nobody
+        let text = "12. Wzór oświadczenia o ryzyku walutowym stanowi załącznik nr 3 do umowy.\n";
+        let extractor = ClauseExtractor::new();
+        assert_eq!(extractor.transparency.classify(text).assessment, TransparencyAssessment::Absent);
+
+        let result = extractor.extract_clauses(Uuid::nil(), text, "pl").await;
+        let clause = result.clauses.iter().find(|c| c.has_category("transparency")).unwrap();
+        assert_eq!(clause.transparency_assessment.as_deref(), Some("absent"));
+        assert!(clause.confidence_score > minimum_confidence("transparency"));
+    }
+
+    #[tokio::test]
+    async fn test_hungarian_acknowledgement_reaches_the_classifier() {
+        let text = "3. Árfolyamkockázat\n\
+            3.1. Az Adós tudomásul veszi, hogy az árfolyamkockázatot teljes egészében ő viseli, és a \
+            törlesztőrészletek forintban kifejezett összege az árfolyam változásával jelentősen emelkedhet.\n\
+            4. Tájékoztatás\n\
+            4.1. A Bank felhívja az Adós figyelmét, hogy a törlesztőrészlet a forint gyengülése esetén korlátlanul növekedhet.\n";
+
+        let result = ClauseExtractor::new().extract_clauses(Uuid::nil(), text, "hu").await;
+        let assessment = |point: &str| result.clauses.iter()
+            .find(|c| c.has_category("transparency") && c.clause_text.contains(point))
+            .and_then(|c| c.transparency_assessment.clone());
+
+        assert_eq!(assessment("3.1. Az Adós tudomásul veszi").as_deref(), Some("boilerplate_acknowledgement"));
+        assert_eq!(assessment("4.1. A Bank felhívja").as_deref(), Some("adequate_warning"));
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How far (in graphemes) a window may grow past its nominal size to reach a sentence boundary.
+const MAX_SENTENCE_REACH: usize = 300;
+
+/// A span of document text quoted as clause context. `start`/`end` are
+/// character offsets into the full text, so `text.chars().skip(start).take(end - start)`
+/// reproduces `self.text` exactly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextWindow {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ContextWindow {
+    /// Window around the byte range of a regex match: `context_chars` graphemes
+    /// split around the match, widened to the enclosing sentences. A sentence
+    /// reaching more than `MAX_SENTENCE_REACH` past the window (tables, run-on
+    /// OCR text) is cut at a word boundary instead.
+    pub fn around_match(text: &str, match_start: usize, match_end: usize, context_chars: usize) -> Self {
+        let half = context_chars / 2;
+        let window_start = graphemes_before(text, match_start, half);
+        let window_end = graphemes_after(text, match_end, half);
+
+        // Segment only a local slab instead of the whole document per match
+        let slab_start = graphemes_before(text, window_start, MAX_SENTENCE_REACH);
+        let slab_end = graphemes_after(text, window_end, MAX_SENTENCE_REACH);
+        let slab = &text[slab_start..slab_end];
+
+        let mut start = window_start;
+        let mut end = window_end;
+        for (offset, sentence) in slab.split_sentence_bound_indices() {
+            let sentence_start = slab_start + offset;
+            let sentence_end = sentence_start + sentence.len();
+            if sentence_start <= window_start && window_start < sentence_end {
+                start = sentence_start;
+            }
+            if sentence_start < window_end && window_end <= sentence_end {
+                end = sentence_end;
+            }
+        }
+
+        // Sentences running into the slab edge are not real sentence boundaries
+        if start == slab_start && slab_start > 0 {
+            start = word_boundary_before(text, window_start);
+        }
+        if end == slab_end && slab_end < text.len() {
+            end = word_boundary_after(text, window_end);
+        }
+
+        Self::from_byte_range(text, start, end)
+    }
+
+    /// Window over a character range (e.g. a whole numbered point), trimmed of
+    /// surrounding whitespace.
+    pub fn from_char_range(text: &str, start: usize, end: usize) -> Self {
+        let byte_start = byte_offset(text, start);
+        let byte_end = byte_offset(text, end);
+        Self::from_byte_range(text, byte_start, byte_end)
+    }
+
+    fn from_byte_range(text: &str, start: usize, end: usize) -> Self {
+        let slice = &text[start..end];
+        let leading = slice.len() - slice.trim_start().len();
+        let trimmed = slice.trim();
+        let start = start + leading;
+
+        let char_start = char_offset(text, start);
+        Self {
+            text: trimmed.to_string(),
+            start: char_start,
+            end: char_start + trimmed.chars().count(),
+        }
+    }
+}
+
+/// Converts a byte offset (e.g. from a regex match) into a character offset.
+pub fn char_offset(text: &str, byte_offset: usize) -> usize {
+    text[..byte_offset].chars().count()
+}
+
+/// Converts a character offset into a byte offset, clamped to the end of the text.
+pub fn byte_offset(text: &str, char_offset: usize) -> usize {
+    text.char_indices()
+        .nth(char_offset)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+fn graphemes_before(text: &str, byte: usize, count: usize) -> usize {
+    if count == 0 {
+        return byte;
+    }
+    text[..byte].grapheme_indices(true)
+        .rev()
+        .take(count)
+        .last()
+        .map_or(byte, |(i, _)| i)
+}
+
+fn graphemes_after(text: &str, byte: usize, count: usize) -> usize {
+    text[byte..].grapheme_indices(true)
+        .nth(count)
+        .map_or(text.len(), |(i, _)| byte + i)
+}
+
+fn word_boundary_before(text: &str, byte: usize) -> usize {
+    text[..byte].split_word_bound_indices()
+        .next_back()
+        .filter(|(_, word)| !word.trim().is_empty())
+        .map_or(byte, |(i, _)| i)
+}
+
+fn word_boundary_after(text: &str, byte: usize) -> usize {
+    text[byte..].split_word_bound_indices()
+        .next()
+        .filter(|(_, word)| !word.trim().is_empty())
+        .map_or(byte, |(_, word)| byte + word.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_expands_to_sentences_on_char_offsets() {
+        let text = "Az adós kijelenti, hogy a tájékoztatást megkapta. Az árfolyamkockázatot az adós viseli. A hitel futamideje 20 év.";
+        let byte = text.find("árfolyamkockázatot").unwrap();
+
+        let window = ContextWindow::around_match(text, byte, byte + "árfolyamkockázatot".len(), 4);
+
+        assert_eq!(window.text, "Az árfolyamkockázatot az adós viseli.");
+        let highlighted: String = text.chars().skip(window.start).take(window.end - window.start).collect();
+        assert_eq!(highlighted, window.text);
+    }
+
+    #[test]
+    fn test_multibyte_text_without_sentence_breaks_never_panics() {
+        let text = "őűáéí ".repeat(200);
+        for byte in text.char_indices().map(|(i, _)| i).step_by(37) {
+            let window = ContextWindow::around_match(&text, byte, byte, 50);
+            assert!(window.text.chars().count() <= 50 + 2 * MAX_SENTENCE_REACH);
+        }
+    }
+}
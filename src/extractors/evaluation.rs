@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use uuid::Uuid;
+use crate::models::ExtractedClause;
+use super::clause_extractor::{ClauseExtractor, spans_overlap};
+
+/// F1 may drop by this much against the baseline before it counts as a regression
+pub const F1_TOLERANCE: f64 = 0.01;
+
+/// Annotations of one gold contract, stored next to its text as `<name>.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldAnnotation {
+    pub language: String,
+    pub clauses: Vec<GoldSpan>,
+}
+
+/// A clause the extractor should find; a span with several types is listed once per type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldSpan {
+    pub clause_type: String,
+    pub start: usize, // character offsets into the contract text
+    pub end: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoldDocument {
+    pub name: String,
+    pub text: String,
+    pub annotation: GoldAnnotation,
+}
+
+/// Reads every `<name>.txt` in `dir` that has a `<name>.json` annotation, sorted by name.
+pub fn load_gold_corpus(dir: &Path) -> Result<Vec<GoldDocument>, Box<dyn std::error::Error>> {
+    let mut documents = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+            continue;
+        }
+        let annotation_path = path.with_extension("json");
+        if !annotation_path.exists() {
+            continue;
+        }
+
+        let text = std::fs::read_to_string(&path)?;
+        let annotation: GoldAnnotation = serde_json::from_str(&std::fs::read_to_string(&annotation_path)?)?;
+        let length = text.chars().count();
+        if let Some(span) = annotation.clauses.iter().find(|s| s.start >= s.end || s.end > length) {
+            return Err(format!("{}: invalid span {}..{}", annotation_path.display(), span.start, span.end).into());
+        }
+
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+        documents.push(GoldDocument { name, text, annotation });
+    }
+
+    documents.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(documents)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClassCounts {
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+}
+
+impl ClassCounts {
+    pub fn precision(&self) -> f64 {
+        ratio(self.true_positives, self.true_positives + self.false_positives)
+    }
+
+    pub fn recall(&self) -> f64 {
+        ratio(self.true_positives, self.true_positives + self.false_negatives)
+    }
+
+    pub fn f1(&self) -> f64 {
+        let (p, r) = (self.precision(), self.recall());
+        if p + r == 0.0 { 0.0 } else { 2.0 * p * r / (p + r) }
+    }
+
+    fn add(&mut self, other: &ClassCounts) {
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 { 0.0 } else { numerator as f64 / denominator as f64 }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Scores {
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+/// Stored scores per class ("clause_type/language") that later runs must not fall below.
+pub type EvaluationBaseline = BTreeMap<String, Scores>;
+
+/// Extraction quality against the gold corpus. Classes are keyed
+/// "clause_type/language"; a predicted clause counts for every category it
+/// carries, and matches a gold span of that type when the spans overlap the
+/// way the extractor merges clauses (at least half of the shorter span).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvaluationReport {
+    pub documents: usize,
+    pub classes: BTreeMap<String, ClassCounts>,
+}
+
+impl EvaluationReport {
+    pub fn add_document(&mut self, document: &GoldDocument, predicted: &[ExtractedClause]) {
+        let language = &document.annotation.language;
+        let predicted_span = |c: &ExtractedClause| {
+            (c.start_position.unwrap_or(0) as usize, c.end_position.unwrap_or(0) as usize)
+        };
+
+        for gold in &document.annotation.clauses {
+            let found = predicted.iter()
+                .any(|c| c.has_category(&gold.clause_type) && spans_overlap(predicted_span(c), (gold.start, gold.end)));
+            let counts = self.class(&gold.clause_type, language);
+            if found { counts.true_positives += 1 } else { counts.false_negatives += 1 }
+        }
+
+        for clause in predicted {
+            for category in &clause.categories {
+                let matches_gold = document.annotation.clauses.iter()
+                    .any(|g| &g.clause_type == category && spans_overlap(predicted_span(clause), (g.start, g.end)));
+                if !matches_gold {
+                    self.class(category, language).false_positives += 1;
+                }
+            }
+        }
+
+        self.documents += 1;
+    }
+
+    fn class(&mut self, clause_type: &str, language: &str) -> &mut ClassCounts {
+        self.classes.entry(format!("{}/{}", clause_type, language)).or_default()
+    }
+
+    /// Micro-averaged counts over all classes.
+    pub fn overall(&self) -> ClassCounts {
+        let mut total = ClassCounts::default();
+        for counts in self.classes.values() {
+            total.add(counts);
+        }
+        total
+    }
+
+    pub fn scores(&self) -> EvaluationBaseline {
+        let round = |x: f64| (x * 1000.0).round() / 1000.0;
+        self.classes.iter()
+            .map(|(class, c)| (class.clone(), Scores { precision: round(c.precision()), recall: round(c.recall()), f1: round(c.f1()) }))
+            .collect()
+    }
+
+    /// Classes whose F1 fell more than `tolerance` below the baseline; classes
+    /// missing from this run count as F1 0.
+    pub fn regressions(&self, baseline: &EvaluationBaseline, tolerance: f64) -> Vec<String> {
+        let current = self.scores();
+        baseline.iter()
+            .filter_map(|(class, expected)| {
+                let f1 = current.get(class).map_or(0.0, |s| s.f1);
+                (f1 < expected.f1 - tolerance).then(|| format!("{}: F1 {:.3} (baseline {:.3})", class, f1, expected.f1))
+            })
+            .collect()
+    }
+
+    /// Classes with gold annotations that the extractor misses entirely. A
+    /// baseline cannot guard them, since an F1 of 0 has nowhere to fall.
+    pub fn undetected(&self) -> Vec<String> {
+        self.classes.iter()
+            .filter(|(_, c)| c.true_positives + c.false_negatives > 0 && c.f1() == 0.0)
+            .map(|(class, c)| format!("{}: none of {} gold clause(s) found", class, c.false_negatives))
+            .collect()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("{:<32} {:>4} {:>4} {:>4} {:>6} {:>6} {:>6}", "class", "tp", "fp", "fn", "P", "R", "F1")];
+        let rows = self.classes.iter().map(|(class, c)| (class.as_str(), *c)).chain([("overall", self.overall())]);
+        for (class, c) in rows {
+            lines.push(format!(
+                "{:<32} {:>4} {:>4} {:>4} {:>6.3} {:>6.3} {:>6.3}",
+                class, c.true_positives, c.false_positives, c.false_negatives, c.precision(), c.recall(), c.f1()
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Runs the extractor over each gold document in its annotated language.
+pub async fn evaluate(extractor: &ClauseExtractor, corpus: &[GoldDocument]) -> EvaluationReport {
+    let mut report = EvaluationReport::default();
+    for document in corpus {
+        let result = extractor.extract_clauses(Uuid::nil(), &document.text, &document.annotation.language).await;
+        report.add_document(document, &result.clauses);
+    }
+    report
+}
+
+pub fn load_baseline(path: &Path) -> Result<EvaluationBaseline, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+pub fn save_baseline(path: &Path, baseline: &EvaluationBaseline) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::write(path, serde_json::to_string_pretty(baseline)? + "\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOLD_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/gold");
+
+    #[test]
+    fn test_counts_by_category_and_overlap() {
+        let document = GoldDocument {
+            name: "sample".to_string(),
+            text: "x".repeat(100),
+            annotation: GoldAnnotation {
+                language: "hu".to_string(),
+                clauses: vec![
+                    GoldSpan { clause_type: "fx_risk".to_string(), start: 0, end: 40 },
+                    GoldSpan { clause_type: "penalty".to_string(), start: 60, end: 90 },
+                ],
+            },
+        };
+        let mut clause = ExtractedClause::new(Uuid::nil(), "fx_risk".to_string(), String::new(), "hu".to_string(), 0.9);
+        clause.categories.push("transparency".to_string());
+        clause.start_position = Some(5);
+        clause.end_position = Some(45);
+
+        let mut report = EvaluationReport::default();
+        report.add_document(&document, &[clause]);
+
+        assert_eq!(report.classes["fx_risk/hu"], ClassCounts { true_positives: 1, false_positives: 0, false_negatives: 0 });
+        assert_eq!(report.classes["transparency/hu"].false_positives, 1);
+        assert_eq!(report.classes["penalty/hu"].false_negatives, 1);
+        assert_eq!(report.undetected(), vec!["penalty/hu: none of 1 gold clause(s) found".to_string()]);
+        assert!((report.overall().f1() - 0.5).abs() < 1e-9);
+    }
+
+    /// Fails when a change to the patterns or the extractor lowers F1 on the
+    /// gold corpus, or when an annotated class is not detected at all. After an intended change, rerun with UPDATE_EVAL_BASELINE=1
+    /// to store the new scores in data/gold/baseline.json.
+    #[tokio::test]
+    async fn test_gold_corpus_against_baseline() {
+        let dir = Path::new(GOLD_DIR);
+        let corpus = load_gold_corpus(dir).unwrap();
+        assert!(corpus.iter().any(|d| d.annotation.language == "hu"));
+        assert!(corpus.iter().any(|d| d.annotation.language == "pl"));
+
+        let report = evaluate(&ClauseExtractor::new(), &corpus).await;
+        println!("{}", report.summary());
+
+        let undetected = report.undetected();
+        assert!(undetected.is_empty(), "Classes never detected:\n{}\n\n{}", undetected.join("\n"), report.summary());
+
+        let baseline_path = dir.join("baseline.json");
+        if std::env::var_os("UPDATE_EVAL_BASELINE").is_some() {
+            save_baseline(&baseline_path, &report.scores()).unwrap();
+            return;
+        }
+
+        let regressions = report.regressions(&load_baseline(&baseline_path).unwrap(), F1_TOLERANCE);
+        assert!(regressions.is_empty(), "Extraction regressed:\n{}\n\n{}", regressions.join("\n"), report.summary());
+    }
+}
@@ -0,0 +1,136 @@
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+use crate::models::ExchangeSpreadTerms;
+
+const BUYING: &[&str] = &["vételi", "buying", "bid rate", "kupna", "nákup", "cumpărare"];
+const SELLING: &[&str] = &["eladási", "selling", "offer rate", "sprzedaży", "prodej", "vânzare"];
+const MIDDLE: &[&str] = &["közép", "middle", "mid-market", "mid rate", "średni", "střed", "mediu"];
+
+const DISBURSEMENT: &[&str] = &["folyósít", "disburs", "drawdown", "wypłat", "uruchomieni", "čerpán", "tragere"];
+const REPAYMENT: &[&str] = &["törleszt", "visszafizet", "repay", "instalment", "installment", "spłat", "splát", "rambursa"];
+
+/// Words that put a percentage in a sentence about the spread rather than the interest rate
+const SPREAD_CUES: &[&str] = &["árfolyamrés", "különbözet", "eltér", "spread", "margin", "marża", "rozpětí", "marj"];
+
+/// Named rate sources, checked in order; the label is what gets stored
+const RATE_SOURCES: &[(&str, &[&str])] = &[
+    ("MNB official rate", &["magyar nemzeti bank", "mnb"]),
+    ("ECB reference rate", &["európai központi bank", "european central bank", "ecb", "ekb"]),
+    ("NBP average rate", &["narodowy bank polski", "nbp"]),
+    ("ČNB rate", &["česká národní banka", "čnb"]),
+    ("BNR reference rate", &["banca națională", "bnr"]),
+    ("bank's own quotation", &[
+        "saját árfolyam", "által jegyzett", "által megállapított", "hirdetmény",
+        "own rate", "quoted by the bank", "tabel kursów", "tabeli kursów", "kurzovní lístek", "kursovní lístek",
+    ]),
+];
+
+/// Reads exchange-rate spread clauses (árfolyamrés): which rate the bank uses
+/// when converting the disbursed amount and the instalments, the spread if it
+/// is stated as a percentage, and where the rates come from.
+pub struct ExchangeSpreadExtractor {
+    percent: Regex,
+}
+
+impl ExchangeSpreadExtractor {
+    pub fn new() -> Self {
+        Self {
+            percent: Regex::new(r"(?i)(\d+(?:[.,]\d+)?)\s*(?:%|százalék|per\s*cent|procent|procenta)").unwrap(),
+        }
+    }
+
+    pub fn extract(&self, text: &str) -> ExchangeSpreadTerms {
+        let mut terms = ExchangeSpreadTerms::default();
+
+        for sentence in text.split_sentence_bounds() {
+            let lower = sentence.to_lowercase();
+
+            // "folyósítás a vételi, törlesztés az eladási árfolyamon": each event takes the
+            // nearest rate within its own clause, or within the sentence if its clause names none
+            let bases = positions(&lower, &[("buying", BUYING), ("selling", SELLING), ("middle", MIDDLE)]);
+            let events = positions(&lower, &[("disbursement", DISBURSEMENT), ("repayment", REPAYMENT)]);
+            for (event_at, event) in events {
+                let (clause_start, clause_end) = clause_bounds(&lower, event_at);
+                let in_clause: Vec<&(usize, &str)> = bases.iter()
+                    .filter(|(at, _)| (clause_start..clause_end).contains(at))
+                    .collect();
+                let candidates = if in_clause.is_empty() { bases.iter().collect() } else { in_clause };
+                let Some((_, basis)) = candidates.into_iter().min_by_key(|(at, _)| at.abs_diff(event_at)) else { continue };
+
+                let slot = match event {
+                    "disbursement" => &mut terms.disbursement_rate,
+                    _ => &mut terms.repayment_rate,
+                };
+                slot.get_or_insert_with(|| basis.to_string());
+            }
+
+            if terms.spread_percent.is_none() && SPREAD_CUES.iter().any(|cue| lower.contains(cue)) {
+                terms.spread_percent = self.percent.captures(&lower)
+                    .and_then(|c| c[1].replace(',', ".").parse().ok());
+            }
+        }
+
+        let lower = text.to_lowercase();
+        terms.rate_sources = RATE_SOURCES.iter()
+            .filter(|(_, cues)| cues.iter().any(|cue| contains_word(&lower, cue)))
+            .map(|(label, _)| label.to_string())
+            .collect();
+
+        terms
+    }
+}
+
+/// Byte positions of every cue, tagged with its group label.
+fn positions(lower: &str, groups: &[(&'static str, &[&str])]) -> Vec<(usize, &'static str)> {
+    groups.iter()
+        .flat_map(|(label, cues)| cues.iter().flat_map(move |cue| lower.match_indices(cue).map(move |(i, _)| (i, *label))))
+        .collect()
+}
+
+/// Byte range of the comma- or semicolon-delimited clause around `at`.
+fn clause_bounds(sentence: &str, at: usize) -> (usize, usize) {
+    let start = sentence[..at].rfind([',', ';']).map_or(0, |i| i + 1);
+    let end = sentence[at..].find([',', ';']).map_or(sentence.len(), |i| at + i);
+    (start, end)
+}
+
+/// Cues match from a word start; acronyms such as "mnb" must also end at a word
+/// end, while words may carry suffixes ("hirdetményében").
+fn contains_word(text: &str, cue: &str) -> bool {
+    let acronym = cue.chars().count() <= 4 && !cue.contains(' ');
+    text.match_indices(cue).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + cue.len()..].chars().next();
+        before.is_none_or(|c| !c.is_alphanumeric()) && (!acronym || after.is_none_or(|c| !c.is_alphanumeric()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kasler_style_clause() {
+        let text = "A kölcsön folyósítása a Bank deviza vételi árfolyamán, a törlesztőrészletek megfizetése \
+            a Bank által jegyzett deviza eladási árfolyamon történik. Az árfolyamrés mértéke legfeljebb 2,5%.";
+
+        let terms = ExchangeSpreadExtractor::new().extract(text);
+
+        assert_eq!(terms.disbursement_rate.as_deref(), Some("buying"));
+        assert_eq!(terms.repayment_rate.as_deref(), Some("selling"));
+        assert_eq!(terms.spread_percent, Some(2.5));
+        assert_eq!(terms.rate_sources, vec!["bank's own quotation".to_string()]);
+        assert!(terms.has_asymmetric_rates());
+    }
+
+    #[test]
+    fn test_middle_rate_clause_is_symmetric() {
+        let terms = ExchangeSpreadExtractor::new()
+            .extract("Folyósítás és törlesztés egyaránt az MNB hivatalos deviza-középárfolyamán történik.");
+
+        assert_eq!(terms.disbursement_rate.as_deref(), Some("middle"));
+        assert_eq!(terms.repayment_rate.as_deref(), Some("middle"));
+        assert_eq!(terms.rate_sources, vec!["MNB official rate".to_string()]);
+        assert!(!terms.is_quantified());
+    }
+}
@@ -1,156 +1,259 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::OnceLock;
+use unicode_segmentation::UnicodeSegmentation;
 
-pub struct LanguageDetector {
-    hungarian_patterns: Vec<&'static str>,
-    english_patterns: Vec<&'static str>,
-    czech_patterns: Vec<&'static str>,
-    polish_patterns: Vec<&'static str>,
+/// Language code returned when the text is too short or too ambiguous to call
+pub const UNDETERMINED: &str = "unknown";
+
+/// Training text per language, embedded so detection needs no files or network
+const PROFILE_SOURCES: &[(&str, &str)] = &[
+    ("hu", include_str!("../../data/language_profiles/hu.txt")),
+    ("en", include_str!("../../data/language_profiles/en.txt")),
+    ("de", include_str!("../../data/language_profiles/de.txt")),
+    ("cs", include_str!("../../data/language_profiles/cs.txt")),
+    ("sk", include_str!("../../data/language_profiles/sk.txt")),
+    ("pl", include_str!("../../data/language_profiles/pl.txt")),
+    ("ro", include_str!("../../data/language_profiles/ro.txt")),
+    ("hr", include_str!("../../data/language_profiles/hr.txt")),
+    ("sl", include_str!("../../data/language_profiles/sl.txt")),
+];
+
+/// Character n-grams of length 1 to MAX_NGRAM are scored
+const MAX_NGRAM: usize = 3;
+
+/// Add-alpha smoothing for n-grams a profile has not seen
+const SMOOTHING: f64 = 0.5;
+
+/// Below this many letters the text is undetermined
+const MIN_LETTERS: usize = 15;
+
+/// Only the start of long documents is scored
+const MAX_CHARS: usize = 10_000;
+
+/// Overlapping n-grams are far from independent, so the summed log-likelihoods
+/// are divided by this before the softmax. Fitted by minimum log loss on the
+/// calibration set in `test_temperature_is_fitted_on_calibration_set`,
+/// which share no text with the profiles or the held-out accuracy test.
+const EVIDENCE_TEMPERATURE: f64 = 4.0;
+
+/// A winning posterior below this is reported as undetermined
+const MIN_CONFIDENCE: f32 = 0.5;
+
+/// In mixed-language segmentation a sentence must be at least this certain to
+/// switch languages; weaker calls continue the current span.
+const SPAN_SWITCH_CONFIDENCE: f32 = 0.8;
+
+/// A span shorter than this between two spans of one language is taken as a
+/// misdetection (a name, a figure, a quoted term) and folded into them.
+const MIN_SPAN_CHARS: usize = 200;
+
+struct LanguageProfile {
+    code: &'static str,
+    counts: HashMap<String, u32>,
+    totals: [u32; MAX_NGRAM],
+}
+
+struct ProfileSet {
+    profiles: Vec<LanguageProfile>,
+    vocabulary: [usize; MAX_NGRAM], // distinct n-grams per length across all profiles
 }
 
+fn profiles() -> &'static ProfileSet {
+    static PROFILES: OnceLock<ProfileSet> = OnceLock::new();
+    PROFILES.get_or_init(|| {
+        let profiles: Vec<LanguageProfile> = PROFILE_SOURCES.iter()
+            .map(|(code, text)| {
+                let mut counts = HashMap::new();
+                let mut totals = [0; MAX_NGRAM];
+                for gram in ngrams(text) {
+                    totals[gram.chars().count() - 1] += 1;
+                    *counts.entry(gram).or_insert(0) += 1;
+                }
+                LanguageProfile { code, counts, totals }
+            })
+            .collect();
+
+        let mut vocabulary = [0; MAX_NGRAM];
+        let distinct: std::collections::HashSet<&String> = profiles.iter().flat_map(|p| p.counts.keys()).collect();
+        for gram in distinct {
+            vocabulary[gram.chars().count() - 1] += 1;
+        }
+
+        ProfileSet { profiles, vocabulary }
+    })
+}
+
+/// Lowercased character n-grams of every word, padded with a space on each
+/// side so word beginnings and endings are features of their own.
+fn ngrams(text: &str) -> Vec<String> {
+    let lower = text.to_lowercase();
+    let mut grams = Vec::new();
+
+    for word in lower.split(|c: char| !c.is_alphabetic()).filter(|w| !w.is_empty()) {
+        let padded: Vec<char> = std::iter::once(' ').chain(word.chars()).chain(std::iter::once(' ')).collect();
+        for n in 1..=MAX_NGRAM {
+            for window in padded.windows(n) {
+                if n == 1 && window[0] == ' ' {
+                    continue;
+                }
+                grams.push(window.iter().collect());
+            }
+        }
+    }
+
+    grams
+}
+
+/// Naive Bayes language identification over character n-gram profiles for
+/// the languages of the FX loan cases: hu, en, de, cs, sk, pl, ro, hr and sl.
+pub struct LanguageDetector;
+
 #[derive(Debug, Clone)]
 pub struct LanguageDetection {
+    pub language: String, // ISO 639-1 code, or UNDETERMINED
+    pub confidence: f32,  // posterior probability of the best language
+    pub scores: HashMap<String, f32>, // posterior per language code, summing to 1
+}
+
+/// A run of text in one language; offsets are character offsets into the
+/// segmented text, and consecutive spans cover it without gaps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageSpan {
+    pub start: usize,
+    pub end: usize,
     pub language: String,
-    pub confidence: f32,
-    pub scores: HashMap<String, f32>,
+    pub confidence: f32, // length-weighted mean of the sentence confidences
 }
 
-impl LanguageDetector {
-    pub fn new() -> Self {
-        Self {
-            hungarian_patterns: vec![
-                "a", "az", "és", "vagy", "de", "hogy", "nem", "van", "volt", "lesz",
-                "hitel", "bank", "szerződés", "kamat", "deviza", "kockázat", "árfolyam",
-                "tájékoztatás", "figyelmeztetés", "költség", "díj", "kölcsön", "jelzálog",
-                "törlesztés", "részlet", "feltétel", "módosítás", "felmondás"
-            ],
-            english_patterns: vec![
-                "the", "and", "or", "but", "that", "not", "is", "was", "will", "have",
-                "loan", "bank", "contract", "interest", "currency", "risk", "exchange",
-                "information", "warning", "cost", "fee", "mortgage", "payment",
-                "installment", "condition", "modification", "termination"
-            ],
-            czech_patterns: vec![
-                "a", "je", "se", "na", "do", "za", "od", "po", "před", "při",
-                "úvěr", "banka", "smlouva", "úrok", "měna", "riziko", "kurz",
-                "informace", "varování", "náklad", "poplatek", "hypotéka"
-            ],
-            polish_patterns: vec![
-                "i", "a", "w", "na", "z", "do", "od", "po", "przez", "przy",
-                "kredyt", "bank", "umowa", "odsetki", "waluta", "ryzyko", "kurs",
-                "informacja", "ostrzeżenie", "koszt", "opłata", "hipoteka"
-            ],
-        }
+impl LanguageSpan {
+    fn len(&self) -> usize {
+        self.end - self.start
     }
 
-    pub fn detect_language(&self, text: &str) -> LanguageDetection {
-        let text_lower = text.to_lowercase();
-        let words: Vec<&str> = text_lower.split_whitespace().collect();
-        let total_words = words.len() as f32;
-
-        if total_words == 0.0 {
-            return LanguageDetection {
-                language: "unknown".to_string(),
-                confidence: 0.0,
-                scores: HashMap::new(),
-            };
+    fn extend(&mut self, end: usize, confidence: Option<f32>) {
+        if let Some(confidence) = confidence {
+            let (old, new) = (self.len() as f32, (end - self.end) as f32);
+            self.confidence = (self.confidence * old + confidence * new) / (old + new).max(1.0);
         }
+        self.end = end;
+    }
 
-        let mut scores = HashMap::new();
+    pub fn contains(&self, offset: usize) -> bool {
+        offset >= self.start && offset < self.end
+    }
+}
 
-        // Calculate Hungarian score
-        let hungarian_matches = self.count_pattern_matches(&words, &self.hungarian_patterns);
-        scores.insert("hungarian".to_string(), hungarian_matches / total_words);
+impl LanguageDetection {
+    fn undetermined(scores: HashMap<String, f32>) -> Self {
+        let confidence = scores.values().copied().fold(0.0, f32::max);
+        Self { language: UNDETERMINED.to_string(), confidence, scores }
+    }
 
-        // Calculate English score
-        let english_matches = self.count_pattern_matches(&words, &self.english_patterns);
-        scores.insert("english".to_string(), english_matches / total_words);
+    pub fn is_determined(&self) -> bool {
+        self.language != UNDETERMINED
+    }
+}
 
-        // Calculate Czech score
-        let czech_matches = self.count_pattern_matches(&words, &self.czech_patterns);
-        scores.insert("czech".to_string(), czech_matches / total_words);
+impl LanguageDetector {
+    pub fn new() -> Self {
+        Self
+    }
 
-        // Calculate Polish score
-        let polish_matches = self.count_pattern_matches(&words, &self.polish_patterns);
-        scores.insert("polish".to_string(), polish_matches / total_words);
+    pub fn supported_languages(&self) -> Vec<&'static str> {
+        profiles().profiles.iter().map(|p| p.code).collect()
+    }
 
-        // Additional language-specific patterns
-        let mut bonus_scores = HashMap::new();
-        
-        // Hungarian specific patterns
-        if text_lower.contains("deviza") || text_lower.contains("árfolyam") || text_lower.contains("huf") {
-            bonus_scores.insert("hungarian".to_string(), 0.2);
-        }
-        
-        // English specific patterns
-        if text_lower.contains("foreign currency") || text_lower.contains("exchange rate") {
-            bonus_scores.insert("english".to_string(), 0.2);
-        }
+    pub fn detect_language(&self, text: &str) -> LanguageDetection {
+        self.detect_with_temperature(text, EVIDENCE_TEMPERATURE)
+    }
 
-        // Czech specific patterns
-        if text_lower.contains("měnové riziko") || text_lower.contains("směnný kurz") {
-            bonus_scores.insert("czech".to_string(), 0.2);
+    fn detect_with_temperature(&self, text: &str, temperature: f64) -> LanguageDetection {
+        let sample: String = text.chars().take(MAX_CHARS).collect();
+        if sample.chars().filter(|c| c.is_alphabetic()).count() < MIN_LETTERS {
+            return LanguageDetection::undetermined(HashMap::new());
         }
 
-        // Polish specific patterns
-        if text_lower.contains("ryzyko walutowe") || text_lower.contains("kurs wymiany") {
-            bonus_scores.insert("polish".to_string(), 0.2);
-        }
+        let grams = ngrams(&sample);
+        let set = profiles();
+        let log_likelihoods: Vec<f64> = set.profiles.iter()
+            .map(|profile| {
+                grams.iter()
+                    .map(|gram| {
+                        let order = gram.chars().count() - 1;
+                        let count = profile.counts.get(gram).copied().unwrap_or(0) as f64;
+                        let total = profile.totals[order] as f64 + SMOOTHING * set.vocabulary[order] as f64;
+                        ((count + SMOOTHING) / total).ln()
+                    })
+                    .sum::<f64>() / temperature
+            })
+            .collect();
 
-        // Apply bonus scores
-        for (lang, bonus) in bonus_scores {
-            if let Some(score) = scores.get_mut(&lang) {
-                *score += bonus;
-            }
-        }
+        // Softmax, shifted by the maximum to stay finite
+        let best = log_likelihoods.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = log_likelihoods.iter().map(|ll| (ll - best).exp()).collect();
+        let sum: f64 = weights.iter().sum();
+        let scores: HashMap<String, f32> = set.profiles.iter()
+            .zip(&weights)
+            .map(|(profile, w)| (profile.code.to_string(), (w / sum) as f32))
+            .collect();
 
-        // Find the language with highest score
-        let (detected_language, confidence) = scores
-            .iter()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-            .map(|(lang, score)| (lang.clone(), *score))
-            .unwrap_or(("unknown".to_string(), 0.0));
-
-        // Convert to standard language codes
-        let language_code = match detected_language.as_str() {
-            "hungarian" => "hu",
-            "english" => "en", 
-            "czech" => "cs",
-            "polish" => "pl",
-            _ => "unknown",
-        };
-
-        LanguageDetection {
-            language: language_code.to_string(),
-            confidence,
-            scores,
-        }
-    }
+        let (language, confidence) = scores.iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(code, p)| (code.clone(), *p))
+            .unwrap_or_else(|| (UNDETERMINED.to_string(), 0.0));
 
-    fn count_pattern_matches(&self, words: &[&str], patterns: &[&str]) -> f32 {
-        let mut matches = 0;
-        for word in words {
-            if patterns.contains(word) {
-                matches += 1;
-            }
+        if confidence < MIN_CONFIDENCE {
+            return LanguageDetection::undetermined(scores);
         }
-        matches as f32
+
+        LanguageDetection { language, confidence, scores }
     }
 
-    pub fn detect_mixed_language(&self, text: &str) -> Vec<LanguageDetection> {
-        // For documents that might contain multiple languages
-        let sentences: Vec<&str> = text.split('.').collect();
-        let mut detections = Vec::new();
+    /// Splits bilingual documents into language runs. Each sentence is detected
+    /// on its own; sentences too short or too uncertain to call (headings,
+    /// numbers, names) join the span that follows them, and adjacent spans of
+    /// the same language are merged.
+    pub fn detect_mixed_language(&self, text: &str) -> Vec<LanguageSpan> {
+        let mut spans: Vec<LanguageSpan> = Vec::new();
+        let mut pending: Option<usize> = None; // start of sentences not yet given to a span
+        let mut offset = 0;
+
+        for sentence in text.split_sentence_bounds() {
+            let start = offset;
+            offset += sentence.chars().count();
 
-        for sentence in sentences {
-            if sentence.trim().len() > 20 { // Only analyze substantial sentences
-                let detection = self.detect_language(sentence);
-                if detection.confidence > 0.1 {
-                    detections.push(detection);
+            let detection = self.detect_language(sentence);
+            let determined = detection.is_determined();
+            match spans.last_mut() {
+                Some(last) if determined && last.language == detection.language => {
+                    last.extend(offset, Some(detection.confidence));
+                    pending = None;
+                }
+                // Headings and numbering before a language switch go with the text they introduce
+                Some(_) if !determined || detection.confidence < SPAN_SWITCH_CONFIDENCE => {
+                    pending.get_or_insert(start);
                 }
+                // Leading undetermined text is claimed by the first determined sentence
+                None if !determined => {}
+                _ => spans.push(LanguageSpan {
+                    start: if spans.is_empty() { 0 } else { pending.take().unwrap_or(start) },
+                    end: offset,
+                    language: detection.language,
+                    confidence: detection.confidence,
+                }),
             }
         }
+        if let Some(last) = spans.last_mut() {
+            last.extend(offset, None);
+        }
+
+        if spans.is_empty() {
+            let detection = self.detect_language(text);
+            return vec![LanguageSpan { start: 0, end: offset, language: detection.language, confidence: detection.confidence }];
+        }
 
-        detections
+        fold_short_spans(spans)
     }
 
     pub fn is_central_european_language(&self, language: &str) -> bool {
@@ -161,7 +264,8 @@ impl LanguageDetector {
         match code {
             "hu" => "Hungarian",
             "en" => "English",
-            "cs" => "Czech", 
+            "de" => "German",
+            "cs" => "Czech",
             "pl" => "Polish",
             "sk" => "Slovak",
             "ro" => "Romanian",
@@ -170,4 +274,270 @@ impl LanguageDetector {
             _ => "Unknown",
         }
     }
-}
\ No newline at end of file
+}
+
+/// Folds short spans sandwiched between two spans of one language into them.
+fn fold_short_spans(spans: Vec<LanguageSpan>) -> Vec<LanguageSpan> {
+    let mut folded: Vec<LanguageSpan> = Vec::new();
+    let mut iter = spans.into_iter().peekable();
+
+    while let Some(span) = iter.next() {
+        let sandwiched = span.len() < MIN_SPAN_CHARS
+            && folded.last().is_some_and(|prev| iter.peek().is_some_and(|next| next.language == prev.language));
+        match folded.last_mut() {
+            Some(prev) if sandwiched => {
+                prev.extend(span.end, None);
+                let next = iter.next().expect("sandwiched span has a successor");
+                prev.extend(next.end, Some(next.confidence));
+            }
+            Some(prev) if prev.language == span.language => prev.extend(span.end, Some(span.confidence)),
+            _ => folded.push(span),
+        }
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> LanguageDetection {
+        LanguageDetector::new().detect_language(text)
+    }
+
+    #[test]
+    fn test_detects_each_supported_language() {
+        let samples = [
+            ("hu", "Az árfolyamkockázatot teljes egészében az adós viseli a futamidő alatt."),
+            ("en", "The borrower bears the full exchange rate risk for the whole term of the loan."),
+            ("de", "Der Kreditnehmer trägt das gesamte Wechselkursrisiko während der Laufzeit."),
+            ("cs", "Dlužník nese veškeré kurzové riziko po celou dobu trvání úvěru."),
+            ("sk", "Dlžník znáša celé kurzové riziko počas celej doby trvania úveru."),
+            ("pl", "Kredytobiorca ponosi całe ryzyko kursowe przez cały okres kredytowania."),
+            ("ro", "Împrumutatul suportă întregul risc valutar pe toată durata creditului."),
+            ("hr", "Korisnik kredita snosi cjelokupni valutni rizik tijekom cijelog razdoblja otplate."),
+            ("sl", "Kreditojemalec nosi celotno valutno tveganje ves čas trajanja kredita."),
+        ];
+
+        for (expected, text) in samples {
+            let detection = detect(text);
+            assert_eq!(detection.language, expected, "{text}: {:?}", detection.scores);
+            assert!(detection.confidence >= MIN_CONFIDENCE);
+        }
+    }
+
+    /// Log loss of the true language over the calibration sentences
+    fn calibration_loss(temperature: f64) -> f64 {
+        // Disjoint from the profile training text and from the held-out set below
+        let calibration = [
+            ("hu", "A szerződés a felek aláírásával lép hatályba."),
+            ("hu", "Az Adós a kölcsönt bármikor előtörlesztheti."),
+            ("hu", "A Bank az árfolyamokat minden munkanapon közzéteszi."),
+            ("hu", "A kezes az Adóssal egyetemlegesen felel."),
+            ("hu", "A szerződés módosítása csak írásban érvényes."),
+            ("hu", "Az ügyfél panaszát a Bank harminc napon belül kivizsgálja."),
+            ("en", "The agreement enters into force when both parties have signed it."),
+            ("en", "The borrower may prepay the loan at any time."),
+            ("en", "The bank publishes its exchange rates on every business day."),
+            ("en", "The guarantor is jointly and severally liable with the borrower."),
+            ("en", "Any amendment to this agreement must be made in writing."),
+            ("en", "Customer complaints are investigated within thirty days."),
+            ("de", "Der Vertrag tritt mit der Unterzeichnung durch beide Parteien in Kraft."),
+            ("de", "Der Kreditnehmer kann das Darlehen jederzeit vorzeitig tilgen."),
+            ("de", "Die Bank veröffentlicht ihre Wechselkurse an jedem Geschäftstag."),
+            ("de", "Der Bürge haftet gesamtschuldnerisch mit dem Kreditnehmer."),
+            ("de", "Änderungen dieses Vertrages bedürfen der Schriftform."),
+            ("de", "Beschwerden der Kunden werden innerhalb von dreißig Tagen bearbeitet."),
+            ("cs", "Smlouva nabývá účinnosti podpisem obou stran."),
+            ("cs", "Dlužník může úvěr kdykoli splatit předčasně."),
+            ("cs", "Banka zveřejňuje své kurzy každý pracovní den."),
+            ("cs", "Ručitel ručí společně a nerozdílně s dlužníkem."),
+            ("cs", "Změny této smlouvy musí být učiněny písemně."),
+            ("cs", "Banka vyřídí stížnost klienta do třiceti dnů."),
+            ("sk", "Zmluva nadobúda účinnosť podpisom oboch strán."),
+            ("sk", "Dlžník môže úver kedykoľvek predčasne splatiť."),
+            ("sk", "Banka zverejňuje svoje kurzy každý pracovný deň."),
+            ("sk", "Ručiteľ ručí spoločne a nerozdielne s dlžníkom."),
+            ("sk", "Zmeny tejto zmluvy musia byť vykonané písomne."),
+            ("sk", "Banka vybaví sťažnosť klienta do tridsiatich dní."),
+            ("pl", "Umowa wchodzi w życie z chwilą podpisania przez obie strony."),
+            ("pl", "Kredytobiorca może w każdej chwili spłacić kredyt przed terminem."),
+            ("pl", "Bank publikuje kursy walut w każdym dniu roboczym."),
+            ("pl", "Poręczyciel odpowiada solidarnie z kredytobiorcą."),
+            ("pl", "Zmiany niniejszej umowy wymagają formy pisemnej."),
+            ("pl", "Bank rozpatruje reklamacje klientów w ciągu trzydziestu dni."),
+            ("ro", "Contractul intră în vigoare la data semnării de către ambele părți."),
+            ("ro", "Împrumutatul poate rambursa creditul oricând înainte de termen."),
+            ("ro", "Banca își publică cursurile de schimb în fiecare zi lucrătoare."),
+            ("ro", "Fidejusorul răspunde solidar cu împrumutatul."),
+            ("ro", "Orice modificare a contractului se face în scris."),
+            ("ro", "Banca soluționează reclamațiile clienților în termen de treizeci de zile."),
+            ("hr", "Ugovor stupa na snagu potpisom obiju ugovornih strana."),
+            ("hr", "Korisnik kredita može kredit otplatiti u bilo kojem trenutku."),
+            ("hr", "Banka objavljuje tečajnu listu svakog radnog dana."),
+            ("hr", "Jamac odgovara solidarno s korisnikom kredita."),
+            ("hr", "Izmjene ovog ugovora moraju biti u pisanom obliku."),
+            ("hr", "Banka rješava prigovore klijenata u roku od trideset dana."),
+            ("sl", "Pogodba začne veljati s podpisom obeh pogodbenih strank."),
+            ("sl", "Kreditojemalec lahko kredit kadar koli predčasno odplača."),
+            ("sl", "Banka vsak delovni dan objavi tečajnico."),
+            ("sl", "Porok odgovarja solidarno s kreditojemalcem."),
+            ("sl", "Spremembe te pogodbe morajo biti v pisni obliki."),
+            ("sl", "Banka reši pritožbo stranke v tridesetih dneh."),
+            // Clause headings, short enough that neighbouring languages get confused
+            ("hu", "Egyetemleges kezesség"),
+            ("hu", "Előtörlesztési díj mértéke"),
+            ("hu", "Panaszkezelés rendje"),
+            ("hu", "Szerződésmódosítás írásban"),
+            ("hu", "Árfolyamok közzététele"),
+            ("hu", "Hatálybalépés napja"),
+            ("en", "Joint and several liability"),
+            ("en", "Prepayment fee amount"),
+            ("en", "Complaint handling procedure"),
+            ("en", "Amendments in writing"),
+            ("en", "Publication of exchange rates"),
+            ("en", "Date of entry into force"),
+            ("de", "Gesamtschuldnerische Bürgschaft"),
+            ("de", "Höhe der Vorfälligkeitsentschädigung"),
+            ("de", "Beschwerdeverfahren der Bank"),
+            ("de", "Änderungen nur schriftlich"),
+            ("de", "Veröffentlichung der Wechselkurse"),
+            ("de", "Tag des Inkrafttretens"),
+            ("cs", "Společné a nerozdílné ručení"),
+            ("cs", "Výše poplatku za předčasné splacení"),
+            ("cs", "Vyřizování stížností klientů"),
+            ("cs", "Písemné změny smlouvy"),
+            ("cs", "Zveřejňování směnných kurzů"),
+            ("cs", "Den nabytí účinnosti"),
+            ("sk", "Spoločné a nerozdielne ručenie"),
+            ("sk", "Výška poplatku za predčasné splatenie"),
+            ("sk", "Vybavovanie sťažností klientov"),
+            ("sk", "Písomné zmeny zmluvy"),
+            ("sk", "Zverejňovanie výmenných kurzov"),
+            ("sk", "Deň nadobudnutia účinnosti"),
+            ("pl", "Solidarna odpowiedzialność poręczyciela"),
+            ("pl", "Wysokość opłaty za wcześniejszą spłatę"),
+            ("pl", "Rozpatrywanie reklamacji klientów"),
+            ("pl", "Zmiany umowy na piśmie"),
+            ("pl", "Publikacja kursów walut"),
+            ("pl", "Dzień wejścia w życie"),
+            ("ro", "Răspunderea solidară a fidejusorului"),
+            ("ro", "Comisionul de rambursare anticipată"),
+            ("ro", "Soluționarea reclamațiilor"),
+            ("ro", "Modificări numai în scris"),
+            ("ro", "Publicarea cursurilor de schimb"),
+            ("ro", "Data intrării în vigoare"),
+            ("hr", "Solidarna odgovornost jamca"),
+            ("hr", "Naknada za prijevremenu otplatu"),
+            ("hr", "Rješavanje prigovora klijenata"),
+            ("hr", "Izmjene ugovora u pisanom obliku"),
+            ("hr", "Objava tečajne liste"),
+            ("hr", "Dan stupanja na snagu"),
+            ("sl", "Solidarna odgovornost poroka"),
+            ("sl", "Nadomestilo za predčasno odplačilo"),
+            ("sl", "Reševanje pritožb strank"),
+            ("sl", "Spremembe pogodbe v pisni obliki"),
+            ("sl", "Objava tečajnice"),
+            ("sl", "Dan začetka veljavnosti"),
+        ];
+
+        let detector = LanguageDetector::new();
+        calibration.iter()
+            .map(|(expected, text)| {
+                let p = detector.detect_with_temperature(text, temperature).scores[*expected] as f64;
+                -p.max(1e-9).ln()
+            })
+            .sum::<f64>() / calibration.len() as f64
+    }
+
+    #[test]
+    fn test_temperature_is_fitted_on_calibration_set() {
+        let candidates = (2..=80).map(|t| t as f64 * 0.25);
+        let fitted = candidates.min_by(|a, b| calibration_loss(*a).total_cmp(&calibration_loss(*b))).unwrap();
+
+        assert!((EVIDENCE_TEMPERATURE - fitted).abs() <= 0.5, "log loss is lowest at temperature {fitted}");
+    }
+
+    #[test]
+    fn test_confidence_tracks_held_out_accuracy() {
+        // None of these sentences appear in the profile training text
+        let held_out = [
+            ("hu", "A hitel kamata változó, a referencia-kamatláb alakulásától függ."),
+            ("hu", "Késedelem esetén a Bank késedelmi kamatot számít fel."),
+            ("hu", "Az ingatlant jelzálogjog terheli a kölcsön visszafizetéséig."),
+            ("en", "Late payments incur default interest at the contractual rate."),
+            ("en", "The property is charged with a mortgage until repayment."),
+            ("en", "Early repayment is permitted subject to a fee."),
+            ("de", "Bei Zahlungsverzug werden Verzugszinsen berechnet."),
+            ("de", "Die Immobilie wird bis zur Rückzahlung mit einer Hypothek belastet."),
+            ("de", "Eine vorzeitige Rückzahlung ist gegen Gebühr möglich."),
+            ("cs", "Při prodlení s platbou účtuje banka úroky z prodlení."),
+            ("cs", "Nemovitost je zatížena zástavním právem až do splacení."),
+            ("cs", "Předčasné splacení je možné za poplatek."),
+            ("sk", "Pri omeškaní s platbou banka účtuje úroky z omeškania."),
+            ("sk", "Nehnuteľnosť je zaťažená záložným právom až do splatenia."),
+            ("sk", "Predčasné splatenie je možné za poplatok."),
+            ("pl", "W przypadku opóźnienia bank nalicza odsetki za zwłokę."),
+            ("pl", "Nieruchomość jest obciążona hipoteką do czasu spłaty."),
+            ("pl", "Wcześniejsza spłata jest możliwa za opłatą."),
+            ("ro", "În caz de întârziere, banca percepe dobânzi penalizatoare."),
+            ("ro", "Imobilul este grevat de ipotecă până la rambursare."),
+            ("ro", "Rambursarea anticipată este posibilă contra unui comision."),
+            ("hr", "U slučaju kašnjenja banka obračunava zateznu kamatu."),
+            ("hr", "Nekretnina je opterećena hipotekom do otplate."),
+            ("hr", "Prijevremena otplata moguća je uz naknadu."),
+            ("sl", "V primeru zamude banka zaračuna zamudne obresti."),
+            ("sl", "Nepremičnina je obremenjena s hipoteko do odplačila."),
+            ("sl", "Predčasno odplačilo je mogoče proti plačilu nadomestila."),
+        ];
+
+        let detections: Vec<(bool, f32)> = held_out.iter()
+            .map(|(expected, text)| {
+                let detection = detect(text);
+                (detection.language == *expected, detection.confidence)
+            })
+            .collect();
+        let n = detections.len() as f32;
+        let accuracy = detections.iter().filter(|(correct, _)| *correct).count() as f32 / n;
+        let mean_confidence = detections.iter().map(|(_, confidence)| confidence).sum::<f32>() / n;
+
+        assert!(accuracy >= 0.9, "accuracy {accuracy}");
+        assert!((accuracy - mean_confidence).abs() < 0.02, "accuracy {accuracy}, mean confidence {mean_confidence}");
+    }
+
+    #[test]
+    fn test_scores_are_a_distribution() {
+        let detection = detect("A kölcsön folyósítása a Bank deviza vételi árfolyamán történik.");
+        let total: f32 = detection.scores.values().sum();
+
+        assert_eq!(detection.scores.len(), 9);
+        assert!((total - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bilingual_contract_is_segmented() {
+        let hungarian = "1. Árfolyamkockázat\nAz Adós tudomásul veszi, hogy a törlesztőrészletek forintban kifejezett összege \
+            az árfolyam változásával jelentősen emelkedhet. A Bank a kamatot egyoldalúan módosíthatja.\n";
+        let english = "1. Exchange rate risk\nThe Borrower acknowledges that the amount of the instalments in forints \
+            may increase significantly as the exchange rate changes. The Bank may unilaterally amend the interest rate.\n";
+        let text = format!("{hungarian}{english}");
+
+        let spans = LanguageDetector::new().detect_mixed_language(&text);
+
+        let languages: Vec<&str> = spans.iter().map(|s| s.language.as_str()).collect();
+        assert_eq!(languages, vec!["hu", "en"]);
+        assert_eq!(spans[0].start, 0);
+        assert_eq!(spans[0].end, hungarian.chars().count());
+        assert_eq!(spans[1].end, text.chars().count());
+        assert!(spans.iter().all(|s| s.confidence >= SPAN_SWITCH_CONFIDENCE));
+    }
+
+    #[test]
+    fn test_short_text_is_undetermined() {
+        let detection = detect("CHF 2008.");
+        assert!(!detection.is_determined());
+        assert_eq!(detection.language, UNDETERMINED);
+        assert_eq!(detection.confidence, 0.0);
+    }
+}
@@ -1,7 +1,31 @@
 pub mod clause_extractor;
 pub mod text_processor;
 pub mod language_detector;
+pub mod office_extractor;
+pub mod ocr_engine;
+pub mod ocr_markdown;
+pub mod payment_table;
+pub mod section_parser;
+pub mod context_window;
+pub mod transparency_classifier;
+pub mod exchange_spread;
+pub mod unilateral_modification;
+pub mod annex_classifier;
+pub mod evaluation;
+pub mod transparency_checklist;
 
 pub use clause_extractor::*;
 pub use text_processor::*;
-pub use language_detector::*;
\ No newline at end of file
+pub use language_detector::*;
+pub use office_extractor::*;
+pub use ocr_engine::*;
+pub use ocr_markdown::*;
+pub use payment_table::*;
+pub use section_parser::*;
+pub use context_window::*;
+pub use transparency_classifier::*;
+pub use exchange_spread::*;
+pub use unilateral_modification::*;
+pub use annex_classifier::*;
+pub use evaluation::*;
+pub use transparency_checklist::*;
\ No newline at end of file
@@ -0,0 +1,400 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// A pluggable OCR backend. Engines report failure (including "not installed")
+/// as errors; they never hand back placeholder text as a successful result.
+pub trait OcrEngine: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn is_available(&self) -> bool;
+
+    /// Recognizes the image at `image_path`. `languages` are ISO 639-1 codes
+    /// (hu, en, cs, pl, ro, hr, ...) in order of preference.
+    fn recognize(&self, image_path: &Path, languages: &[&str]) -> Result<OcrOutput, OcrError>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OcrError {
+    #[error("OCR engine '{0}' is not available")]
+    EngineUnavailable(String),
+    #[error("None of the requested OCR languages are installed: {0}")]
+    LanguageUnavailable(String),
+    #[error("OCR failed: {0}")]
+    Failed(String),
+    #[error("OCR I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub left: u32,
+    pub top: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrWord {
+    pub text: String,
+    pub bbox: BoundingBox,
+    pub confidence: f32, // 0.0 - 1.0
+    pub page: u32,
+    pub block: u32,
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrOutput {
+    pub engine: String,
+    pub languages: Vec<String>,
+    pub words: Vec<OcrWord>,
+}
+
+impl OcrOutput {
+    /// Rebuilds running text from the recognized words, one line per OCR line
+    /// and a blank line between blocks.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        let mut previous: Option<(u32, u32, u32)> = None;
+
+        for word in &self.words {
+            let key = (word.page, word.block, word.line);
+            match previous {
+                Some((page, block, _)) if (page, block) != (key.0, key.1) => text.push_str("\n\n"),
+                Some(prev) if prev != key => text.push('\n'),
+                Some(_) => text.push(' '),
+                None => {}
+            }
+            text.push_str(&word.text);
+            previous = Some(key);
+        }
+
+        text
+    }
+
+    /// Character-weighted mean word confidence, so a stray low-confidence
+    /// punctuation mark does not weigh as much as a long word.
+    pub fn mean_confidence(&self) -> f32 {
+        let (weighted, chars) = self.words.iter().fold((0.0f32, 0usize), |(sum, n), w| {
+            let len = w.text.chars().count();
+            (sum + w.confidence * len as f32, n + len)
+        });
+
+        if chars == 0 { 0.0 } else { weighted / chars as f32 }
+    }
+}
+
+/// Maps ISO 639-1 codes (as produced by `LanguageDetector`) to Tesseract traineddata names.
+pub fn tesseract_language(code: &str) -> Option<&'static str> {
+    match code {
+        "hu" => Some("hun"),
+        "en" => Some("eng"),
+        "de" => Some("deu"),
+        "cs" => Some("ces"),
+        "sk" => Some("slk"),
+        "pl" => Some("pol"),
+        "ro" => Some("ron"),
+        "hr" => Some("hrv"),
+        "sl" => Some("slv"),
+        _ => None,
+    }
+}
+
+/// The Tesseract output config words are read from. TSV needs Tesseract 3.05
+/// or later; older installations only produce hOCR.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TesseractOutput {
+    Tsv,
+    Hocr,
+}
+
+impl TesseractOutput {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "tsv" => Some(Self::Tsv),
+            "hocr" => Some(Self::Hocr),
+            _ => None,
+        }
+    }
+
+    fn config(&self) -> &'static str {
+        match self {
+            Self::Tsv => "tsv",
+            Self::Hocr => "hocr",
+        }
+    }
+
+    fn parse(&self, output: &str) -> Vec<OcrWord> {
+        match self {
+            Self::Tsv => parse_tesseract_tsv(output),
+            Self::Hocr => parse_hocr(output),
+        }
+    }
+}
+
+/// Tesseract invoked through its command line interface. The binary and its
+/// language list are probed once per engine, on first use.
+pub struct TesseractEngine {
+    binary: PathBuf,
+    output: TesseractOutput,
+    available: OnceLock<bool>,
+    languages: OnceLock<Option<Vec<String>>>, // None when the binary could not be run
+}
+
+impl TesseractEngine {
+    pub fn new() -> Self {
+        let binary = std::env::var("TESSERACT_PATH")
+            .unwrap_or_else(|_| "tesseract".to_string());
+        let output = std::env::var("TESSERACT_OUTPUT").ok()
+            .and_then(|name| TesseractOutput::from_name(&name))
+            .unwrap_or(TesseractOutput::Tsv);
+
+        Self::with_binary(PathBuf::from(binary), output)
+    }
+
+    fn with_binary(binary: PathBuf, output: TesseractOutput) -> Self {
+        Self { binary, output, available: OnceLock::new(), languages: OnceLock::new() }
+    }
+
+    pub fn installed_languages(&self) -> Result<Vec<String>, OcrError> {
+        self.languages
+            .get_or_init(|| {
+                let output = Command::new(&self.binary).arg("--list-langs").output().ok()?;
+
+                // The first line is a "List of available languages ..." banner
+                let listing = String::from_utf8_lossy(&output.stdout);
+                Some(listing.lines()
+                    .skip(1)
+                    .map(|l| l.trim().to_string())
+                    .filter(|l| !l.is_empty())
+                    .collect())
+            })
+            .clone()
+            .ok_or_else(|| OcrError::EngineUnavailable(self.binary.display().to_string()))
+    }
+}
+
+impl OcrEngine for TesseractEngine {
+    fn name(&self) -> &str {
+        "tesseract"
+    }
+
+    fn is_available(&self) -> bool {
+        *self.available.get_or_init(|| {
+            Command::new(&self.binary)
+                .arg("--version")
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        })
+    }
+
+    fn recognize(&self, image_path: &Path, languages: &[&str]) -> Result<OcrOutput, OcrError> {
+        if !self.is_available() {
+            return Err(OcrError::EngineUnavailable(self.binary.display().to_string()));
+        }
+
+        let installed = self.installed_languages()?;
+        let mut selected: Vec<&str> = Vec::new();
+        for code in languages {
+            if let Some(lang) = tesseract_language(code) {
+                if installed.iter().any(|l| l == lang) && !selected.contains(&lang) {
+                    selected.push(lang);
+                }
+            }
+        }
+        if selected.is_empty() {
+            return Err(OcrError::LanguageUnavailable(languages.join(", ")));
+        }
+
+        let output = Command::new(&self.binary)
+            .arg(image_path)
+            .arg("stdout")
+            .arg("-l")
+            .arg(selected.join("+"))
+            .arg(self.output.config())
+            .output()?;
+
+        if !output.status.success() {
+            return Err(OcrError::Failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+
+        Ok(OcrOutput {
+            engine: self.name().to_string(),
+            languages: selected.iter().map(|l| l.to_string()).collect(),
+            words: self.output.parse(&String::from_utf8_lossy(&output.stdout)),
+        })
+    }
+}
+
+/// Parses Tesseract TSV output (`tesseract img stdout tsv`). Only word rows
+/// (level 5) with a confidence are kept.
+pub fn parse_tesseract_tsv(tsv: &str) -> Vec<OcrWord> {
+    let mut words = Vec::new();
+
+    for line in tsv.lines().skip(1) {
+        let cols: Vec<&str> = line.splitn(12, '\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+
+        let text = cols[11].trim();
+        let confidence = cols[10].parse::<f32>().unwrap_or(-1.0);
+        if text.is_empty() || confidence < 0.0 {
+            continue;
+        }
+
+        let num = |i: usize| cols[i].parse::<u32>().unwrap_or(0);
+        words.push(OcrWord {
+            text: text.to_string(),
+            bbox: BoundingBox {
+                left: num(6),
+                top: num(7),
+                width: num(8),
+                height: num(9),
+            },
+            confidence: (confidence / 100.0).clamp(0.0, 1.0),
+            page: num(1),
+            block: num(2),
+            line: num(3) * 1000 + num(4), // paragraph and line together identify a line
+        });
+    }
+
+    words
+}
+
+/// Parses hOCR output (`tesseract img stdout hocr`), reading word boxes and
+/// `x_wconf` confidences from the `ocrx_word` spans.
+pub fn parse_hocr(hocr: &str) -> Vec<OcrWord> {
+    let element = Regex::new(
+        r#"<(?:div|p|span)[^>]*class=['"](ocr_page|ocr_carea|ocr_line|ocr_header|ocr_caption|ocr_textfloat|ocrx_word)['"][^>]*?title=(?:'([^']*)'|"([^"]*)")[^>]*>"#
+    ).unwrap();
+    let bbox = Regex::new(r"bbox (\d+) (\d+) (\d+) (\d+)").unwrap();
+    let wconf = Regex::new(r"x_wconf (\d+)").unwrap();
+    let tags = Regex::new(r"<[^>]+>").unwrap();
+
+    let mut words = Vec::new();
+    let (mut page, mut block, mut line) = (0u32, 0u32, 0u32);
+
+    for cap in element.captures_iter(hocr) {
+        let title = cap.get(2).or_else(|| cap.get(3)).map_or("", |m| m.as_str());
+        match &cap[1] {
+            "ocr_page" => page += 1,
+            "ocr_carea" => block += 1,
+            // Tesseract tags headings, captions and floating text as line types of their own
+            "ocr_line" | "ocr_header" | "ocr_caption" | "ocr_textfloat" => line += 1,
+            _ => {
+                // Word spans may wrap their text in <strong>/<em>; read up to the closing span
+                let rest = &hocr[cap.get(0).unwrap().end()..];
+                let content = &rest[..rest.find("</span>").unwrap_or(rest.len())];
+                let raw = tags.replace_all(content, "");
+                let text = decode_entities(raw.trim());
+                if text.is_empty() {
+                    continue;
+                }
+
+                let Some(b) = bbox.captures(title) else { continue };
+                let coord = |i: usize| b[i].parse::<u32>().unwrap_or(0);
+                let confidence = wconf.captures(title)
+                    .and_then(|c| c[1].parse::<f32>().ok())
+                    .unwrap_or(0.0);
+
+                words.push(OcrWord {
+                    text,
+                    bbox: BoundingBox {
+                        left: coord(1),
+                        top: coord(2),
+                        width: coord(3).saturating_sub(coord(1)),
+                        height: coord(4).saturating_sub(coord(2)),
+                    },
+                    confidence: (confidence / 100.0).clamp(0.0, 1.0),
+                    page: page.max(1),
+                    block,
+                    line,
+                });
+            }
+        }
+    }
+
+    words
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tsv_keeps_word_rows_with_confidence() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t2480\t3508\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t100\t200\t180\t40\t96.5\tÁrfolyam\n\
+                   5\t1\t1\t1\t1\t2\t290\t200\t60\t40\t42\tkockázat\n\
+                   5\t1\t2\t1\t1\t1\t100\t400\t90\t40\t88\tCHF\n";
+
+        let output = OcrOutput {
+            engine: "tesseract".to_string(),
+            languages: vec!["hun".to_string()],
+            words: parse_tesseract_tsv(tsv),
+        };
+
+        assert_eq!(output.words.len(), 3);
+        assert_eq!(output.words[0].bbox, BoundingBox { left: 100, top: 200, width: 180, height: 40 });
+        assert_eq!(output.text(), "Árfolyam kockázat\n\nCHF");
+        assert!((output.mean_confidence() - 0.7245).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_parse_hocr_reads_bbox_and_wconf() {
+        let hocr = r#"<div class='ocr_page' id='page_1' title='image "x.png"; bbox 0 0 2480 3508; ppageno 0'>
+            <div class='ocr_carea' id='block_1_1' title="bbox 100 200 500 260">
+            <span class='ocr_line' id='line_1_1' title="bbox 100 200 500 240; baseline 0 -8">
+            <span class='ocrx_word' id='word_1_1' title='bbox 100 200 280 240; x_wconf 91'>Kölcsön&amp;hitel</span>
+            </span></div></div>"#;
+
+        let words = parse_hocr(hocr);
+        assert_eq!(words.len(), 1);
+        assert_eq!(words[0].text, "Kölcsön&hitel");
+        assert_eq!(words[0].bbox.width, 180);
+        assert!((words[0].confidence - 0.91).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_hocr_starts_a_line_at_headers_and_captions() {
+        let hocr = r#"<div class='ocr_page' id='page_1' title='bbox 0 0 2480 3508'>
+            <div class='ocr_carea' id='block_1_1' title="bbox 100 100 900 400">
+            <span class='ocr_header' id='line_1_1' title="bbox 100 100 900 140">
+            <span class='ocrx_word' id='word_1_1' title='bbox 100 100 400 140; x_wconf 95'>Árfolyamkockázat</span></span>
+            <span class='ocr_line' id='line_1_2' title="bbox 100 160 900 200">
+            <span class='ocrx_word' id='word_1_2' title='bbox 100 160 300 200; x_wconf 90'>Az</span></span>
+            <span class='ocr_caption' id='line_1_3' title="bbox 100 300 900 340">
+            <span class='ocrx_word' id='word_1_3' title='bbox 100 300 300 340; x_wconf 88'>Ábra</span></span>
+            <span class='ocr_textfloat' id='line_1_4' title="bbox 100 360 900 400">
+            <span class='ocrx_word' id='word_1_4' title='bbox 100 360 300 400; x_wconf 85'>Melléklet</span></span>
+            </div></div>"#;
+
+        let lines: Vec<u32> = parse_hocr(hocr).iter().map(|w| w.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_missing_binary_is_an_error() {
+        let engine = TesseractEngine::with_binary(PathBuf::from("/nonexistent/tesseract"), TesseractOutput::Tsv);
+        let result = engine.recognize(Path::new("/tmp/none.png"), &["hu"]);
+        assert!(matches!(result, Err(OcrError::EngineUnavailable(_))));
+
+        // The probe result is kept for the next call
+        assert_eq!(engine.available.get(), Some(&false));
+    }
+}
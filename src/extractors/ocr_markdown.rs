@@ -0,0 +1,312 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Parser for the markdown files written by `mistral_ocr_processor/output_manager.py`:
+///
+/// ```text
+/// ---
+/// title: "szerzodes.pdf"
+/// source_file: "szerzodes.pdf"
+/// processed_date: "2025-01-12T10:31:02.123456"
+/// processor: "Mistral OCR"
+/// file_type: ".pdf"
+/// file_size_bytes: "482113"
+/// processor_used: "Tesseract OCR (Fallback)"
+/// ---
+///
+/// # szerzodes.pdf
+///
+/// *Document processed with Mistral OCR on 2025-01-12 10:31:02*
+///
+/// ---
+///
+/// <!-- page: 1 -->
+/// ...
+/// ```
+///
+/// Page boundaries are taken from `<!-- page: N -->` comments (Mistral OCR),
+/// `## Page N` headings (Tesseract fallback) or form feeds. Files without any
+/// marker become a single segment with an unknown page number.
+pub struct OcrMarkdownParser {
+    page_comment: Regex,
+    page_heading: Regex,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OcrMetadata {
+    pub title: Option<String>,
+    pub source_file: Option<String>,
+    pub processed_date: Option<String>,
+    pub processor: Option<String>,
+    pub processor_used: Option<String>,
+    pub file_type: Option<String>,
+    pub extra: BTreeMap<String, String>,
+}
+
+/// A page of the document body. Offsets are character offsets into `OcrMarkdownDocument::text`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageSegment {
+    pub page_number: Option<u32>,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrMarkdownDocument {
+    pub markdown_file: String,
+    pub metadata: OcrMetadata,
+    pub text: String,
+    pub pages: Vec<PageSegment>,
+}
+
+impl OcrMetadata {
+    /// The OCR method used when the primary Mistral OCR call failed, if any.
+    pub fn fallback_method(&self) -> Option<&str> {
+        self.processor_used.as_deref()
+            .filter(|p| p.to_lowercase().contains("fallback"))
+    }
+
+    /// Size of the original file in bytes. Older outputs only record
+    /// `file_size_mb`, rounded to two decimals, so that is an approximation.
+    pub fn file_size(&self) -> Option<i64> {
+        if let Some(bytes) = self.extra.get("file_size_bytes").and_then(|v| v.parse::<i64>().ok()) {
+            return Some(bytes);
+        }
+        self.extra.get("file_size_mb")
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|mb| (mb * 1024.0 * 1024.0).round() as i64)
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self.file_type.as_deref().map(|t| t.trim_start_matches('.').to_lowercase()).as_deref() {
+            Some("pdf") => "application/pdf",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("png") => "image/png",
+            Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            Some("odt") => "application/vnd.oasis.opendocument.text",
+            _ => "text/markdown",
+        }
+    }
+}
+
+impl OcrMarkdownDocument {
+    /// Original file name the OCR output was produced from.
+    pub fn source_name(&self) -> &str {
+        self.metadata.source_file.as_deref()
+            .or(self.metadata.title.as_deref())
+            .unwrap_or(&self.markdown_file)
+    }
+
+    /// Page number containing the given character offset.
+    pub fn page_at(&self, char_offset: usize) -> Option<u32> {
+        self.pages.iter()
+            .find(|p| char_offset >= p.start && char_offset < p.end)
+            .and_then(|p| p.page_number)
+    }
+
+    pub fn page_text(&self, page: &PageSegment) -> String {
+        self.text.chars().skip(page.start).take(page.end - page.start).collect()
+    }
+
+    /// Renders the document for prompts, keeping file and page provenance inline.
+    pub fn to_prompt_text(&self) -> String {
+        let mut out = format!("DOCUMENT: {} (source: {}", self.markdown_file, self.source_name());
+        if let Some(date) = &self.metadata.processed_date {
+            out.push_str(&format!(", processed: {}", date));
+        }
+        if let Some(method) = self.metadata.fallback_method() {
+            out.push_str(&format!(", OCR: {}", method));
+        }
+        out.push_str(")\n\n");
+
+        for page in &self.pages {
+            if let Some(number) = page.page_number {
+                out.push_str(&format!("[page {}]\n", number));
+            }
+            out.push_str(self.page_text(page).trim());
+            out.push_str("\n\n");
+        }
+
+        out
+    }
+}
+
+impl OcrMarkdownParser {
+    pub fn new() -> Self {
+        Self {
+            page_comment: Regex::new(r"(?i)^\s*<!--\s*page:?\s*(\d+)\s*-->\s*$").unwrap(),
+            page_heading: Regex::new(r"(?i)^\s*#{1,3}\s*(?:page|oldal)\s+(\d+)\s*$").unwrap(),
+        }
+    }
+
+    pub fn parse(&self, markdown_file: &str, content: &str) -> OcrMarkdownDocument {
+        let (metadata, body) = self.split_front_matter(content);
+        let body = self.strip_preamble(body, &metadata);
+
+        let mut text = String::new();
+        let mut text_chars = 0usize;
+        let mut pages: Vec<PageSegment> = Vec::new();
+        let mut current = PageSegment { page_number: None, start: 0, end: 0 };
+
+        for line in body.split_inclusive('\n') {
+            let marker = self.page_comment.captures(line)
+                .or_else(|| self.page_heading.captures(line))
+                .and_then(|c| c[1].parse::<u32>().ok());
+
+            let mut segments = line.split('\u{000C}').peekable();
+            if let Some(number) = marker {
+                close_page(&mut pages, &mut current, text_chars, Some(number));
+                continue;
+            }
+
+            while let Some(segment) = segments.next() {
+                text.push_str(segment);
+                text_chars += segment.chars().count();
+                if segments.peek().is_some() {
+                    // Form feed: the next page continues the numbering
+                    let next = current.page_number.map(|n| n + 1).or(Some(2));
+                    if current.page_number.is_none() && pages.is_empty() {
+                        current.page_number = Some(1);
+                    }
+                    close_page(&mut pages, &mut current, text_chars, next);
+                }
+            }
+        }
+        current.end = text_chars;
+        if current.end > current.start || pages.is_empty() {
+            pages.push(current);
+        }
+
+        OcrMarkdownDocument {
+            markdown_file: markdown_file.to_string(),
+            metadata,
+            text,
+            pages,
+        }
+    }
+
+    pub fn parse_file(&self, path: &Path) -> Result<OcrMarkdownDocument, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let name = path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+
+        Ok(self.parse(name, &content))
+    }
+
+    /// Parses every OCR output file in `dir`, skipping the processor's own summary report.
+    pub fn parse_directory(&self, dir: &Path) -> Result<Vec<OcrMarkdownDocument>, Box<dyn std::error::Error>> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("md") | Some("txt") | Some("rtf")))
+            .filter(|p| p.file_name().and_then(|n| n.to_str()) != Some("processing_summary.md"))
+            .collect();
+        paths.sort();
+
+        let mut documents = Vec::new();
+        for path in paths {
+            match self.parse_file(&path) {
+                Ok(document) => documents.push(document),
+                Err(e) => log::warn!("Skipping unreadable OCR output {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(documents)
+    }
+
+    fn split_front_matter<'a>(&self, content: &'a str) -> (OcrMetadata, &'a str) {
+        let mut metadata = OcrMetadata::default();
+        let Some(rest) = content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n")) else {
+            return (metadata, content);
+        };
+        let Some(end) = rest.find("\n---") else {
+            return (metadata, content);
+        };
+
+        for line in rest[..end].lines() {
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let key = key.trim();
+            let value = value.trim().trim_matches('"').to_string();
+            match key {
+                "title" => metadata.title = Some(value),
+                "source_file" => metadata.source_file = Some(value),
+                "processed_date" => metadata.processed_date = Some(value),
+                "processor" => metadata.processor = Some(value),
+                "processor_used" => metadata.processor_used = Some(value),
+                "file_type" => metadata.file_type = Some(value),
+                _ => {
+                    metadata.extra.insert(key.to_string(), value);
+                }
+            }
+        }
+
+        let body = &rest[end + 4..];
+        (metadata, body.trim_start_matches(['\r', '\n']))
+    }
+
+    /// Drops the "# filename", "*Document processed with ...*" and "---" lines
+    /// that the output manager puts before the OCR content.
+    fn strip_preamble<'a>(&self, body: &'a str, metadata: &OcrMetadata) -> &'a str {
+        let mut rest = body;
+        loop {
+            let trimmed = rest.trim_start_matches(['\r', '\n']);
+            let (line, remainder) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+            let line = line.trim();
+            let is_title = metadata.title.as_deref().is_some_and(|t| line == format!("# {}", t));
+            let is_note = line.starts_with("*Document processed with") && line.ends_with('*');
+
+            if is_title || is_note || line == "---" {
+                rest = remainder;
+            } else {
+                return trimmed;
+            }
+        }
+    }
+}
+
+fn close_page(pages: &mut Vec<PageSegment>, current: &mut PageSegment, offset: usize, next: Option<u32>) {
+    current.end = offset;
+    // Text before the first marker (usually nothing) is only kept if non-empty
+    if current.end > current.start || current.page_number.is_some() {
+        pages.push(current.clone());
+    }
+    *current = PageSegment { page_number: next, start: offset, end: offset };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "---\ntitle: \"szerzodes.pdf\"\nsource_file: \"szerzodes.pdf\"\nprocessed_date: \"2025-01-12T10:31:02\"\nprocessor: \"Mistral OCR\"\nfile_type: \".pdf\"\nfile_size_bytes: \"482113\"\nfile_size_mb: \"0.46\"\nprocessor_used: \"Tesseract OCR (Fallback)\"\n---\n\n# szerzodes.pdf\n\n*Document processed with Mistral OCR on 2025-01-12 10:31:02*\n\n---\n\n\n\n## Page 1\n\nKölcsönszerződés\n\n## Page 2\n\nÁrfolyamkockázat\n";
+
+    #[test]
+    fn test_parses_metadata_and_pages() {
+        let doc = OcrMarkdownParser::new().parse("szerzodes.md", SAMPLE);
+
+        assert_eq!(doc.source_name(), "szerzodes.pdf");
+        assert_eq!(doc.metadata.fallback_method(), Some("Tesseract OCR (Fallback)"));
+        assert_eq!(doc.metadata.content_type(), "application/pdf");
+        assert_eq!(doc.metadata.file_size(), Some(482113));
+        assert_eq!(doc.pages.len(), 2);
+        assert_eq!(doc.pages[1].page_number, Some(2));
+        assert_eq!(doc.page_text(&doc.pages[1]).trim(), "Árfolyamkockázat");
+
+        let offset = doc.text.chars().count() - 3;
+        assert_eq!(doc.page_at(offset), Some(2));
+    }
+
+    #[test]
+    fn test_unmarked_content_is_single_segment() {
+        let doc = OcrMarkdownParser::new().parse("notes.txt", "Egyszerű szöveg");
+        assert_eq!(doc.pages, vec![PageSegment { page_number: None, start: 0, end: 15 }]);
+        assert_eq!(doc.metadata.file_size(), None);
+
+        let mut legacy = OcrMetadata::default();
+        legacy.extra.insert("file_size_mb".to_string(), "0.5".to_string());
+        assert_eq!(legacy.file_size(), Some(524_288));
+    }
+}
@@ -0,0 +1,1126 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Text extraction for DOCX (WordprocessingML) and ODT (OpenDocument Text).
+///
+/// Blocks are returned in reading order: page headers, body (paragraphs,
+/// headings and tables), page footers, then footnotes. `to_text` renders them
+/// with markdown-style structural markers so the output looks like the
+/// Mistral OCR markdown and downstream code can rely on one format:
+///
+/// - headings: `#`, `##`, ... by outline level
+/// - list numbering: the rendered label ("5.2.", "b)") in front of the text,
+///   `-` for bullets
+/// - tables: `| cell | cell |` rows, with a `|---|` separator after the first row
+/// - page headers / footers: `[header] ...` / `[footer] ...`
+/// - footnotes: `[^id]` at the reference and `[^id]: text` at the end
+pub struct OfficeExtractor;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocumentBlock {
+    Heading { level: u8, text: String },
+    Paragraph { text: String },
+    Table { rows: Vec<Vec<String>> },
+    Header { text: String },
+    Footer { text: String },
+    Footnote { id: String, text: String },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OfficeDocument {
+    pub blocks: Vec<DocumentBlock>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OfficeFormat {
+    Docx,
+    Odt,
+}
+
+impl OfficeFormat {
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type {
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => Some(Self::Docx),
+            "application/vnd.oasis.opendocument.text" => Some(Self::Odt),
+            _ => None,
+        }
+    }
+}
+
+impl OfficeDocument {
+    pub fn to_text(&self) -> String {
+        let mut out: Vec<String> = Vec::new();
+
+        for block in &self.blocks {
+            match block {
+                DocumentBlock::Heading { level, text } => {
+                    out.push(format!("{} {}", "#".repeat((*level).clamp(1, 6) as usize), text));
+                }
+                DocumentBlock::Paragraph { text } => out.push(text.clone()),
+                DocumentBlock::Table { rows } => {
+                    let mut lines = Vec::new();
+                    for (i, row) in rows.iter().enumerate() {
+                        lines.push(format!("| {} |", row.join(" | ")));
+                        if i == 0 {
+                            lines.push(format!("|{}", "---|".repeat(row.len().max(1))));
+                        }
+                    }
+                    out.push(lines.join("\n"));
+                }
+                DocumentBlock::Header { text } => out.push(format!("[header] {}", text)),
+                DocumentBlock::Footer { text } => out.push(format!("[footer] {}", text)),
+                DocumentBlock::Footnote { id, text } => out.push(format!("[^{}]: {}", id, text)),
+            }
+        }
+
+        out.join("\n\n")
+    }
+}
+
+impl OfficeExtractor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn extract(&self, data: &[u8], format: OfficeFormat) -> Result<OfficeDocument, Box<dyn std::error::Error>> {
+        let mut archive = ZipArchive::new(Cursor::new(data))?;
+
+        match format {
+            OfficeFormat::Docx => self.extract_docx(&mut archive),
+            OfficeFormat::Odt => self.extract_odt(&mut archive),
+        }
+    }
+
+    fn extract_docx(&self, archive: &mut ZipArchive<Cursor<&[u8]>>) -> Result<OfficeDocument, Box<dyn std::error::Error>> {
+        let body = read_entry(archive, "word/document.xml")?
+            .ok_or("Not a DOCX file: word/document.xml is missing")?;
+        let styles = match read_entry(archive, "word/styles.xml")? {
+            Some(styles) => parse_docx_styles(&styles)?,
+            None => DocxStyles::default(),
+        };
+        let numbering = match read_entry(archive, "word/numbering.xml")? {
+            Some(numbering) => parse_docx_numbering(&numbering)?,
+            None => DocxNumbering::default(),
+        };
+
+        let mut part_names: Vec<String> = archive.file_names().map(|n| n.to_string()).collect();
+        part_names.sort();
+
+        let mut blocks = Vec::new();
+        let mut seen = HashSet::new();
+
+        // Page headers (first-page, even and default headers often repeat the same text)
+        for name in part_names.iter().filter(|n| is_docx_part(n, "header")) {
+            if let Some(xml) = read_entry(archive, name)? {
+                for text in parse_docx_paragraph_texts(&xml)? {
+                    if seen.insert(("header", text.clone())) {
+                        blocks.push(DocumentBlock::Header { text });
+                    }
+                }
+            }
+        }
+
+        blocks.extend(parse_docx_body(&body, &styles, &numbering)?);
+
+        for name in part_names.iter().filter(|n| is_docx_part(n, "footer")) {
+            if let Some(xml) = read_entry(archive, name)? {
+                for text in parse_docx_paragraph_texts(&xml)? {
+                    if seen.insert(("footer", text.clone())) {
+                        blocks.push(DocumentBlock::Footer { text });
+                    }
+                }
+            }
+        }
+
+        if let Some(footnotes) = read_entry(archive, "word/footnotes.xml")? {
+            blocks.extend(parse_docx_footnotes(&footnotes)?);
+        }
+
+        Ok(OfficeDocument { blocks })
+    }
+
+    fn extract_odt(&self, archive: &mut ZipArchive<Cursor<&[u8]>>) -> Result<OfficeDocument, Box<dyn std::error::Error>> {
+        let content = read_entry(archive, "content.xml")?
+            .ok_or("Not an ODT file: content.xml is missing")?;
+
+        let mut blocks = Vec::new();
+        let mut list_styles = OdtListStyles::default();
+        let (headers, footers) = match read_entry(archive, "styles.xml")? {
+            Some(styles) => {
+                list_styles.extend(parse_odt_list_styles(&styles)?);
+                parse_odt_page_furniture(&styles)?
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+        // Automatic list styles live in content.xml
+        list_styles.extend(parse_odt_list_styles(&content)?);
+
+        blocks.extend(headers.into_iter().map(|text| DocumentBlock::Header { text }));
+        let (body, footnotes) = parse_odt_body(&content, &list_styles)?;
+        blocks.extend(body);
+        blocks.extend(footers.into_iter().map(|text| DocumentBlock::Footer { text }));
+        blocks.extend(footnotes);
+
+        Ok(OfficeDocument { blocks })
+    }
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)?;
+    Ok(Some(xml))
+}
+
+fn is_docx_part(name: &str, kind: &str) -> bool {
+    name.strip_prefix("word/")
+        .and_then(|n| n.strip_prefix(kind))
+        .is_some_and(|rest| rest.ends_with(".xml") && !rest.contains('/'))
+}
+
+fn attr(e: &BytesStart, local: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == local)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn normalize_ws(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Paragraph styles that matter for extraction: styleId -> heading level,
+/// and styleId -> (numId, ilvl) for styles that carry list numbering (usually
+/// the headings of a multilevel "1. / 1.1." contract outline).
+#[derive(Debug, Default)]
+struct DocxStyles {
+    heading_levels: HashMap<String, u8>,
+    numbering: HashMap<String, (String, Option<usize>)>,
+}
+
+/// Reads heading levels from the built-in style names ("heading 1"), which
+/// stay in English even in localized (e.g. "Címsor 1") documents.
+fn parse_docx_styles(xml: &str) -> Result<DocxStyles, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut styles = DocxStyles::default();
+    let mut current: Option<String> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"style" => {
+                current = attr(&e, b"styleId");
+            }
+            Event::Empty(e) => {
+                let Some(style_id) = current.clone() else { continue };
+                match e.local_name().as_ref() {
+                    b"name" => {
+                        let name = attr(&e, b"val").unwrap_or_default().to_lowercase();
+                        if let Some(level) = name.strip_prefix("heading ").and_then(|l| l.trim().parse::<u8>().ok()) {
+                            styles.heading_levels.insert(style_id, level);
+                        } else if name == "title" {
+                            styles.heading_levels.insert(style_id, 1);
+                        }
+                    }
+                    b"outlineLvl" => {
+                        // Level 9 means "body text"
+                        if let Some(level) = attr(&e, b"val").and_then(|v| v.parse::<u8>().ok()).filter(|&l| l < 9) {
+                            styles.heading_levels.entry(style_id).or_insert(level + 1);
+                        }
+                    }
+                    b"numId" => {
+                        if let Some(num_id) = attr(&e, b"val") {
+                            styles.numbering.entry(style_id).or_insert((String::new(), None)).0 = num_id;
+                        }
+                    }
+                    b"ilvl" => {
+                        let ilvl = attr(&e, b"val").and_then(|v| v.parse::<usize>().ok());
+                        styles.numbering.entry(style_id).or_insert((String::new(), None)).1 = ilvl;
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"style" => current = None,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(styles)
+}
+
+/// How one level of a numbered list is counted and rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum NumberFormat {
+    #[default]
+    Decimal,
+    LowerLetter,
+    UpperLetter,
+    LowerRoman,
+    UpperRoman,
+    Bullet,
+    None,
+}
+
+impl NumberFormat {
+    fn from_docx(value: &str) -> Self {
+        match value {
+            "lowerLetter" => Self::LowerLetter,
+            "upperLetter" => Self::UpperLetter,
+            "lowerRoman" => Self::LowerRoman,
+            "upperRoman" => Self::UpperRoman,
+            "bullet" => Self::Bullet,
+            "none" => Self::None,
+            _ => Self::Decimal,
+        }
+    }
+
+    fn from_odt(value: &str) -> Self {
+        match value {
+            "a" => Self::LowerLetter,
+            "A" => Self::UpperLetter,
+            "i" => Self::LowerRoman,
+            "I" => Self::UpperRoman,
+            "" => Self::None,
+            _ => Self::Decimal,
+        }
+    }
+
+    fn render(&self, n: u32) -> String {
+        match self {
+            Self::LowerLetter | Self::UpperLetter if n > 0 => {
+                // a..z, then aa..zz as Word and LibreOffice do
+                let letter = (b'a' + ((n - 1) % 26) as u8) as char;
+                let text = letter.to_string().repeat(((n - 1) / 26 + 1) as usize);
+                if *self == Self::UpperLetter { text.to_uppercase() } else { text }
+            }
+            Self::LowerRoman | Self::UpperRoman if n > 0 => {
+                let text = roman(n);
+                if *self == Self::LowerRoman { text.to_lowercase() } else { text }
+            }
+            _ => n.to_string(),
+        }
+    }
+}
+
+fn roman(mut n: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"), (100, "C"), (90, "XC"),
+        (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut text = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            text.push_str(numeral);
+            n -= value;
+        }
+    }
+    text
+}
+
+/// One level of a list definition. `template` uses the DOCX `lvlText`
+/// notation, `%1`..`%9` standing for the counters of levels 1..9; ODT list
+/// styles are translated into it.
+#[derive(Debug, Clone)]
+struct ListLevel {
+    format: NumberFormat,
+    template: String,
+    start: u32,
+}
+
+impl Default for ListLevel {
+    fn default() -> Self {
+        Self { format: NumberFormat::Decimal, template: String::new(), start: 1 }
+    }
+}
+
+/// Counts the item at `level` (0-based): deeper counters restart, missing
+/// outer ones take their start value.
+fn advance_counters(counters: &mut Vec<u32>, levels: &[ListLevel], level: usize) {
+    let start = |i: usize| levels.get(i).map_or(1, |l| l.start);
+    if counters.len() > level {
+        counters[level] += 1;
+        counters.truncate(level + 1);
+    } else {
+        while counters.len() < level {
+            counters.push(start(counters.len()));
+        }
+        counters.push(start(level));
+    }
+}
+
+/// The rendered label of the item just counted at `level`, e.g. "5.2." or "b)".
+fn list_label(levels: &[ListLevel], counters: &[u32], level: usize) -> Option<String> {
+    let definition = levels.get(level)?;
+    match definition.format {
+        NumberFormat::None => return None,
+        NumberFormat::Bullet => return Some("-".to_string()),
+        _ => {}
+    }
+    let mut label = definition.template.clone();
+    for (i, counter) in counters.iter().enumerate().take(level + 1) {
+        let format = levels.get(i).map_or(NumberFormat::Decimal, |l| l.format);
+        label = label.replace(&format!("%{}", i + 1), &format.render(*counter));
+    }
+    let label = normalize_ws(&label);
+    if label.is_empty() { None } else { Some(label) }
+}
+
+/// `word/numbering.xml`: numId -> abstract list definition -> levels.
+#[derive(Debug, Default)]
+struct DocxNumbering {
+    definitions: HashMap<String, Vec<ListLevel>>,
+    instances: HashMap<String, String>,
+}
+
+impl DocxNumbering {
+    fn levels(&self, num_id: &str) -> Option<(&String, &Vec<ListLevel>)> {
+        let abstract_id = self.instances.get(num_id)?;
+        self.definitions.get(abstract_id).map(|levels| (abstract_id, levels))
+    }
+}
+
+fn parse_docx_numbering(xml: &str) -> Result<DocxNumbering, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut numbering = DocxNumbering::default();
+    let mut definition: Option<(String, Vec<ListLevel>)> = None;
+    let mut level: Option<(usize, ListLevel)> = None;
+    let mut instance: Option<String> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"abstractNum" => definition = attr(&e, b"abstractNumId").map(|id| (id, Vec::new())),
+                b"lvl" if definition.is_some() => {
+                    level = attr(&e, b"ilvl").and_then(|l| l.parse::<usize>().ok()).map(|l| (l, ListLevel::default()));
+                }
+                b"num" => instance = attr(&e, b"numId"),
+                _ => {}
+            },
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"abstractNumId" => {
+                    if let (Some(num_id), Some(abstract_id)) = (&instance, attr(&e, b"val")) {
+                        numbering.instances.insert(num_id.clone(), abstract_id);
+                    }
+                }
+                b"numFmt" | b"lvlText" | b"start" => {
+                    let (Some((_, level)), Some(value)) = (level.as_mut(), attr(&e, b"val")) else { continue };
+                    match e.local_name().as_ref() {
+                        b"numFmt" => level.format = NumberFormat::from_docx(&value),
+                        b"lvlText" => level.template = value,
+                        _ => level.start = value.parse().unwrap_or(1),
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"lvl" => {
+                    if let (Some((_, levels)), Some((ilvl, level))) = (definition.as_mut(), level.take()) {
+                        if levels.len() <= ilvl {
+                            levels.resize(ilvl + 1, ListLevel::default());
+                        }
+                        levels[ilvl] = level;
+                    }
+                }
+                b"abstractNum" => {
+                    if let Some((id, levels)) = definition.take() {
+                        numbering.definitions.insert(id, levels);
+                    }
+                }
+                b"num" => instance = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(numbering)
+}
+
+fn parse_docx_body(xml: &str, styles: &DocxStyles, numbering: &DocxNumbering) -> Result<Vec<DocumentBlock>, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut blocks = Vec::new();
+
+    let mut paragraph = String::new();
+    let mut level: Option<u8> = None;
+    // (numId, ilvl) set on the paragraph itself, and the one its style carries
+    let mut list: (Option<String>, Option<usize>) = (None, None);
+    let mut style_list: Option<&(String, Option<usize>)> = None;
+    // Counters are kept per abstract definition, so a list continues across numIds
+    let mut counters: HashMap<&String, Vec<u32>> = HashMap::new();
+    let mut in_text = false;
+    let mut table_depth = 0usize;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell = String::new();
+
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                b"p" => {
+                    paragraph.clear();
+                    level = None;
+                    list = (None, None);
+                    style_list = None;
+                }
+                b"pStyle" => {
+                    if let Some(style) = attr(e, b"val") {
+                        level = styles.heading_levels.get(&style).copied().or(level);
+                        style_list = styles.numbering.get(&style);
+                    }
+                }
+                b"numId" => list.0 = attr(e, b"val"),
+                b"ilvl" => list.1 = attr(e, b"val").and_then(|v| v.parse::<usize>().ok()),
+                b"outlineLvl" => {
+                    if let Some(l) = attr(e, b"val").and_then(|v| v.parse::<u8>().ok()) {
+                        // Level 9 means "body text"
+                        if l < 9 {
+                            level = Some(l + 1);
+                        }
+                    }
+                }
+                b"t" => in_text = matches!(event, Event::Start(_)),
+                b"tab" => paragraph.push(' '),
+                b"br" | b"cr" => paragraph.push(' '),
+                b"footnoteReference" => {
+                    if let Some(id) = attr(e, b"id") {
+                        paragraph.push_str(&format!("[^{}]", id));
+                    }
+                }
+                b"tbl" => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        rows.clear();
+                    }
+                }
+                b"tr" if table_depth == 1 => row.clear(),
+                b"tc" if table_depth == 1 => cell.clear(),
+                _ => {}
+            },
+            Event::Text(t) if in_text => paragraph.push_str(&t.unescape()?),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => {
+                    let mut text = normalize_ws(&paragraph);
+                    let num_id = list.0.as_deref().or(style_list.map(|(id, _)| id.as_str()));
+                    // numId 0 switches numbering off
+                    if let Some((abstract_id, levels)) = num_id.filter(|&id| id != "0").and_then(|id| numbering.levels(id)) {
+                        let ilvl = list.1.or(style_list.and_then(|(_, ilvl)| *ilvl)).unwrap_or(0);
+                        let counters = counters.entry(abstract_id).or_default();
+                        advance_counters(counters, levels, ilvl);
+                        if let Some(label) = list_label(levels, counters, ilvl).filter(|_| !text.is_empty()) {
+                            text = format!("{} {}", label, text);
+                        }
+                    }
+                    if table_depth > 0 {
+                        // Nested tables are flattened into the enclosing cell
+                        if !text.is_empty() {
+                            if !cell.is_empty() {
+                                cell.push(' ');
+                            }
+                            cell.push_str(&text);
+                        }
+                    } else if !text.is_empty() {
+                        match level {
+                            Some(level) => blocks.push(DocumentBlock::Heading { level, text }),
+                            None => blocks.push(DocumentBlock::Paragraph { text }),
+                        }
+                    }
+                    paragraph.clear();
+                }
+                b"tc" if table_depth == 1 => row.push(cell.replace('|', "/")),
+                b"tr" if table_depth == 1 && row.iter().any(|c| !c.is_empty()) => {
+                    rows.push(std::mem::take(&mut row));
+                }
+                b"tbl" => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && !rows.is_empty() {
+                        blocks.push(DocumentBlock::Table { rows: std::mem::take(&mut rows) });
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(blocks)
+}
+
+fn parse_docx_paragraph_texts(xml: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    Ok(parse_docx_body(xml, &DocxStyles::default(), &DocxNumbering::default())?
+        .into_iter()
+        .filter_map(|block| match block {
+            DocumentBlock::Paragraph { text } | DocumentBlock::Heading { text, .. } => Some(text),
+            DocumentBlock::Table { rows } => Some(rows.into_iter().map(|r| r.join(" ")).collect::<Vec<_>>().join(" ")),
+            _ => None,
+        })
+        .collect())
+}
+
+fn parse_docx_footnotes(xml: &str) -> Result<Vec<DocumentBlock>, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut footnotes = Vec::new();
+    let mut current: Option<String> = None;
+    let mut text = String::new();
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"footnote" => {
+                    // Separator and continuation notice entries carry a w:type
+                    current = if attr(&e, b"type").is_none() { attr(&e, b"id") } else { None };
+                    text.clear();
+                }
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Event::Empty(e) if matches!(e.local_name().as_ref(), b"tab" | b"br") => text.push(' '),
+            Event::Text(t) if in_text && current.is_some() => text.push_str(&t.unescape()?),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"t" => in_text = false,
+                b"p" => text.push(' '),
+                b"footnote" => {
+                    if let Some(id) = current.take() {
+                        let body = normalize_ws(&text);
+                        if !body.is_empty() {
+                            footnotes.push(DocumentBlock::Footnote { id, text: body });
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(footnotes)
+}
+
+/// List styles by name, and the chapter numbering of headings
+/// (`text:outline-style`).
+#[derive(Debug, Default)]
+struct OdtListStyles {
+    lists: HashMap<String, Vec<ListLevel>>,
+    outline: Vec<ListLevel>,
+}
+
+impl OdtListStyles {
+    fn extend(&mut self, other: OdtListStyles) {
+        self.lists.extend(other.lists);
+        if !other.outline.is_empty() {
+            self.outline = other.outline;
+        }
+    }
+}
+
+fn parse_odt_list_styles(xml: &str) -> Result<OdtListStyles, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut styles = OdtListStyles::default();
+    let mut current: Option<String> = None;
+    let mut in_style = false;
+    let mut in_outline = false;
+    let mut levels: Vec<ListLevel> = Vec::new();
+
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => match e.local_name().as_ref() {
+                name @ (b"list-style" | b"outline-style") if matches!(event, Event::Start(_)) => {
+                    // Headings numbered through a list refer to the outline style by name
+                    current = attr(e, b"name");
+                    in_style = true;
+                    in_outline = name == b"outline-style";
+                    levels.clear();
+                }
+                name @ (b"list-level-style-number" | b"outline-level-style" | b"list-level-style-bullet") if in_style => {
+                    let Some(level) = attr(e, b"level").and_then(|l| l.parse::<usize>().ok()).filter(|&l| l > 0) else { continue };
+                    let definition = if name == b"list-level-style-bullet" {
+                        ListLevel { format: NumberFormat::Bullet, ..ListLevel::default() }
+                    } else {
+                        let shown = attr(e, b"display-levels").and_then(|d| d.parse::<usize>().ok()).unwrap_or(1).clamp(1, level);
+                        let counters = (level - shown + 1..=level).map(|l| format!("%{}", l)).collect::<Vec<_>>().join(".");
+                        ListLevel {
+                            format: NumberFormat::from_odt(&attr(e, b"num-format").unwrap_or_default()),
+                            template: format!(
+                                "{}{}{}",
+                                attr(e, b"num-prefix").unwrap_or_default(),
+                                counters,
+                                attr(e, b"num-suffix").unwrap_or_default()
+                            ),
+                            start: attr(e, b"start-value").and_then(|s| s.parse().ok()).unwrap_or(1),
+                        }
+                    };
+                    if levels.len() < level {
+                        levels.resize(level, ListLevel { format: NumberFormat::None, ..ListLevel::default() });
+                    }
+                    levels[level - 1] = definition;
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"list-style" | b"outline-style" if in_style => {
+                    in_style = false;
+                    if in_outline {
+                        styles.outline = levels.clone();
+                    }
+                    if let Some(name) = current.take() {
+                        styles.lists.insert(name, std::mem::take(&mut levels));
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(styles)
+}
+
+/// A `text:h` or `text:p` being read; `label` is its list or chapter number.
+struct OdtParagraph {
+    level: Option<u8>,
+    label: Option<String>,
+    text: String,
+}
+
+/// A `text:list` being read. `pending` is set by each `text:list-item` until
+/// its first paragraph takes the number.
+struct OdtList {
+    style: Option<String>,
+    pending: bool,
+}
+
+/// Parses ODT `content.xml`. Footnote bodies are pulled out of the paragraph
+/// flow (they are inline in ODT) and returned separately.
+fn parse_odt_body(xml: &str, list_styles: &OdtListStyles) -> Result<(Vec<DocumentBlock>, Vec<DocumentBlock>), Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut blocks = Vec::new();
+    let mut footnotes = Vec::new();
+
+    let mut in_body = false;
+    // Stack of open text containers
+    let mut paragraphs: Vec<OdtParagraph> = Vec::new();
+    let mut lists: Vec<OdtList> = Vec::new();
+    // Counters per list style; a list continues its style's numbering only when asked to
+    let mut list_counters: HashMap<String, Vec<u32>> = HashMap::new();
+    let mut outline_counters: Vec<u32> = Vec::new();
+    // Inside `text:number`, the number as the writing application rendered it
+    let mut in_number = false;
+    let mut table_depth = 0usize;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell = String::new();
+    let mut note_id: Option<String> = None;
+    let mut note_text = String::new();
+    let mut in_note_body = false;
+    let mut in_citation = false;
+
+    loop {
+        let event = reader.read_event()?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let is_start = matches!(event, Event::Start(_));
+                match e.local_name().as_ref() {
+                    b"text" if is_start => in_body = true,
+                    b"list" if in_body && is_start => {
+                        let style = attr(e, b"style-name").or_else(|| lists.last().and_then(|l| l.style.clone()));
+                        if let Some(parent) = lists.last_mut() {
+                            parent.pending = false;
+                        } else if let Some(style) = &style {
+                            let continues = attr(e, b"continue-numbering").as_deref() == Some("true")
+                                || attr(e, b"continue-list").is_some();
+                            if !continues {
+                                list_counters.remove(style);
+                            }
+                        }
+                        lists.push(OdtList { style, pending: false });
+                    }
+                    b"list-item" if in_body && is_start => {
+                        if let Some(list) = lists.last_mut() {
+                            list.pending = true;
+                        }
+                    }
+                    b"h" | b"p" if in_body && is_start => {
+                        let level = if e.local_name().as_ref() == b"h" {
+                            Some(attr(e, b"outline-level").and_then(|l| l.parse::<u8>().ok()).unwrap_or(1))
+                        } else {
+                            None
+                        };
+
+                        let depth = lists.len().saturating_sub(1);
+                        let label = match lists.last_mut() {
+                            Some(list) if list.pending && !in_note_body => {
+                                list.pending = false;
+                                list.style.as_ref().and_then(|style| {
+                                    let levels = list_styles.lists.get(style)?;
+                                    let counters = list_counters.entry(style.clone()).or_default();
+                                    advance_counters(counters, levels, depth);
+                                    list_label(levels, counters, depth)
+                                })
+                            }
+                            Some(_) => None,
+                            None => level.filter(|_| !in_note_body && !list_styles.outline.is_empty()).and_then(|level| {
+                                let depth = usize::from(level.max(1)) - 1;
+                                advance_counters(&mut outline_counters, &list_styles.outline, depth);
+                                list_label(&list_styles.outline, &outline_counters, depth)
+                            }),
+                        };
+                        paragraphs.push(OdtParagraph { level, label, text: String::new() });
+                    }
+                    b"number" if in_body && is_start => {
+                        in_number = true;
+                        if let Some(paragraph) = paragraphs.last_mut() {
+                            paragraph.label = Some(String::new());
+                        }
+                    }
+                    b"s" => {
+                        let count = attr(e, b"c").and_then(|c| c.parse::<usize>().ok()).unwrap_or(1);
+                        push_odt_text(&mut paragraphs, &mut note_text, in_note_body, &" ".repeat(count));
+                    }
+                    b"tab" | b"line-break" => push_odt_text(&mut paragraphs, &mut note_text, in_note_body, " "),
+                    b"note" if is_start => {
+                        note_id = attr(e, b"id");
+                        note_text.clear();
+                    }
+                    b"note-citation" if is_start => in_citation = true,
+                    b"note-body" if is_start => in_note_body = true,
+                    b"table" if is_start => {
+                        table_depth += 1;
+                        if table_depth == 1 {
+                            rows.clear();
+                        }
+                    }
+                    b"table-row" if is_start && table_depth == 1 => row.clear(),
+                    b"table-cell" if table_depth == 1 => {
+                        cell.clear();
+                        if !is_start {
+                            row.push(String::new());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(t) if in_body => {
+                let text = t.unescape()?;
+                if in_number {
+                    if let Some(label) = paragraphs.last_mut().and_then(|p| p.label.as_mut()) {
+                        label.push_str(&text);
+                    }
+                } else if in_citation {
+                    // Replace the visible citation mark with a stable reference
+                    if let Some(id) = &note_id {
+                        let marker = format!("[^{}]", id);
+                        push_odt_text(&mut paragraphs, &mut note_text, false, &marker);
+                    }
+                } else {
+                    push_odt_text(&mut paragraphs, &mut note_text, in_note_body, &text);
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"text" => in_body = false,
+                b"list" if in_body => {
+                    // A nested list's numbering ends with it; top-level counters
+                    // are kept for a later list that continues them
+                    if let Some(OdtList { style: Some(style), .. }) = lists.pop().filter(|_| !lists.is_empty()) {
+                        if let Some(counters) = list_counters.get_mut(&style) {
+                            counters.truncate(lists.len());
+                        }
+                    }
+                }
+                b"number" => in_number = false,
+                b"note-citation" => in_citation = false,
+                b"note-body" => in_note_body = false,
+                b"note" => {
+                    if let Some(id) = note_id.take() {
+                        let body = normalize_ws(&note_text);
+                        if !body.is_empty() {
+                            footnotes.push(DocumentBlock::Footnote { id, text: body });
+                        }
+                    }
+                }
+                b"h" | b"p" if in_body => {
+                    if in_note_body {
+                        paragraphs.pop();
+                        note_text.push(' ');
+                        continue;
+                    }
+                    let Some(paragraph) = paragraphs.pop() else { continue };
+                    let mut text = normalize_ws(&paragraph.text);
+                    if text.is_empty() {
+                        continue;
+                    }
+                    if let Some(label) = paragraph.label.map(|l| normalize_ws(&l)).filter(|l| !l.is_empty()) {
+                        text = format!("{} {}", label, text);
+                    }
+                    if table_depth > 0 {
+                        if !cell.is_empty() {
+                            cell.push(' ');
+                        }
+                        cell.push_str(&text);
+                    } else {
+                        match paragraph.level {
+                            Some(level) => blocks.push(DocumentBlock::Heading { level, text }),
+                            None => blocks.push(DocumentBlock::Paragraph { text }),
+                        }
+                    }
+                }
+                b"table-cell" if table_depth == 1 => row.push(cell.replace('|', "/")),
+                b"table-row" if table_depth == 1 && row.iter().any(|c| !c.is_empty()) => {
+                    rows.push(std::mem::take(&mut row));
+                }
+                b"table" => {
+                    table_depth = table_depth.saturating_sub(1);
+                    if table_depth == 0 && !rows.is_empty() {
+                        blocks.push(DocumentBlock::Table { rows: std::mem::take(&mut rows) });
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok((blocks, footnotes))
+}
+
+fn push_odt_text(paragraphs: &mut [OdtParagraph], note_text: &mut String, in_note_body: bool, text: &str) {
+    if in_note_body {
+        note_text.push_str(text);
+    } else if let Some(paragraph) = paragraphs.last_mut() {
+        paragraph.text.push_str(text);
+    }
+}
+
+/// Collects page header and footer paragraphs from the master pages in ODT `styles.xml`.
+fn parse_odt_page_furniture(xml: &str) -> Result<(Vec<String>, Vec<String>), Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut headers = Vec::new();
+    let mut footers = Vec::new();
+    let mut section: Option<bool> = None; // Some(true) = header, Some(false) = footer
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"header" | b"header-first" | b"header-left" => section = Some(true),
+                b"footer" | b"footer-first" | b"footer-left" => section = Some(false),
+                b"p" | b"h" => text.clear(),
+                _ => {}
+            },
+            Event::Empty(e) if section.is_some() && matches!(e.local_name().as_ref(), b"s" | b"tab") => text.push(' '),
+            Event::Text(t) if section.is_some() => text.push_str(&t.unescape()?),
+            Event::End(e) => match e.local_name().as_ref() {
+                b"header" | b"header-first" | b"header-left" | b"footer" | b"footer-first" | b"footer-left" => section = None,
+                b"p" | b"h" => {
+                    let line = normalize_ws(&text);
+                    match section {
+                        Some(true) if !line.is_empty() && !headers.contains(&line) => headers.push(line),
+                        Some(false) if !line.is_empty() && !footers.contains(&line) => footers.push(line),
+                        _ => {}
+                    }
+                    text.clear();
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok((headers, footers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    fn archive(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    const DOCX_STYLES: &str = r#"<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+        <w:style w:type="paragraph" w:styleId="Cmsor1"><w:name w:val="heading 1"/>
+            <w:pPr><w:numPr><w:numId w:val="1"/></w:numPr><w:outlineLvl w:val="0"/></w:pPr></w:style>
+        <w:style w:type="paragraph" w:styleId="Cmsor2"><w:name w:val="heading 2"/>
+            <w:pPr><w:numPr><w:ilvl w:val="1"/><w:numId w:val="1"/></w:numPr><w:outlineLvl w:val="1"/></w:pPr></w:style>
+        <w:style w:type="paragraph" w:styleId="Szvegtrzs"><w:name w:val="Body Text"/>
+            <w:pPr><w:outlineLvl w:val="9"/></w:pPr></w:style>
+    </w:styles>"#;
+
+    const DOCX_NUMBERING: &str = r#"<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+        <w:abstractNum w:abstractNumId="10">
+            <w:lvl w:ilvl="0"><w:start w:val="1"/><w:numFmt w:val="decimal"/><w:lvlText w:val="%1."/></w:lvl>
+            <w:lvl w:ilvl="1"><w:start w:val="1"/><w:numFmt w:val="decimal"/><w:lvlText w:val="%1.%2."/></w:lvl>
+        </w:abstractNum>
+        <w:abstractNum w:abstractNumId="11">
+            <w:lvl w:ilvl="0"><w:start w:val="1"/><w:numFmt w:val="lowerLetter"/><w:lvlText w:val="%1)"/></w:lvl>
+            <w:lvl w:ilvl="1"><w:start w:val="1"/><w:numFmt w:val="bullet"/><w:lvlText w:val="&#61623;"/></w:lvl>
+        </w:abstractNum>
+        <w:num w:numId="1"><w:abstractNumId w:val="10"/></w:num>
+        <w:num w:numId="2"><w:abstractNumId w:val="11"/></w:num>
+    </w:numbering>"#;
+
+    fn docx_paragraph(properties: &str, text: &str) -> String {
+        format!("<w:p><w:pPr>{}</w:pPr><w:r><w:t xml:space=\"preserve\">{}</w:t></w:r></w:p>", properties, text)
+    }
+
+    fn docx_list_item(num_id: u32, ilvl: u32, text: &str) -> String {
+        docx_paragraph(&format!("<w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>", ilvl, num_id), text)
+    }
+
+    #[test]
+    fn test_docx_headings_numbering_and_tables() {
+        let body = [
+            docx_paragraph(r#"<w:pStyle w:val="Cmsor1"/>"#, "Kamat"),
+            docx_paragraph("", "Az ügyleti kamat változó."),
+            docx_paragraph(r#"<w:pStyle w:val="Cmsor2"/>"#, "Kamatváltoztatás"),
+            docx_list_item(2, 0, "a refinanszírozási költségek változása,"),
+            docx_list_item(2, 1, "a CHF LIBOR emelkedése,"),
+            docx_list_item(2, 0, "a jogszabályi környezet változása."),
+            r#"<w:tbl>
+                <w:tr><w:tc><w:p><w:r><w:t>Esedékesség</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Törlesztőrészlet</w:t></w:r></w:p></w:tc></w:tr>
+                <w:tr><w:tc><w:p><w:r><w:t>2008.01.05</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>612,40 CHF</w:t></w:r></w:p></w:tc></w:tr>
+            </w:tbl>"#.to_string(),
+            docx_paragraph(r#"<w:pStyle w:val="Cmsor1"/>"#, "Árfolyamkockázat"),
+            docx_paragraph(r#"<w:pStyle w:val="Szvegtrzs"/>"#, "Az árfolyamkockázatot az Adós viseli."),
+            docx_paragraph(r#"<w:pStyle w:val="Cmsor1"/><w:numPr><w:numId w:val="0"/></w:numPr>"#, "Záró rendelkezések"),
+        ].concat();
+        let document = format!(
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}</w:body></w:document>"#,
+            body
+        );
+        let docx = archive(&[
+            ("word/document.xml", &document),
+            ("word/styles.xml", DOCX_STYLES),
+            ("word/numbering.xml", DOCX_NUMBERING),
+        ]);
+
+        let document = OfficeExtractor::new().extract(&docx, OfficeFormat::Docx).unwrap();
+
+        assert_eq!(document.blocks, vec![
+            DocumentBlock::Heading { level: 1, text: "1. Kamat".to_string() },
+            DocumentBlock::Paragraph { text: "Az ügyleti kamat változó.".to_string() },
+            DocumentBlock::Heading { level: 2, text: "1.1. Kamatváltoztatás".to_string() },
+            DocumentBlock::Paragraph { text: "a) a refinanszírozási költségek változása,".to_string() },
+            DocumentBlock::Paragraph { text: "- a CHF LIBOR emelkedése,".to_string() },
+            DocumentBlock::Paragraph { text: "b) a jogszabályi környezet változása.".to_string() },
+            DocumentBlock::Table { rows: vec![
+                vec!["Esedékesség".to_string(), "Törlesztőrészlet".to_string()],
+                vec!["2008.01.05".to_string(), "612,40 CHF".to_string()],
+            ] },
+            DocumentBlock::Heading { level: 1, text: "2. Árfolyamkockázat".to_string() },
+            // outlineLvl 9 is body text, not a tenth heading level
+            DocumentBlock::Paragraph { text: "Az árfolyamkockázatot az Adós viseli.".to_string() },
+            DocumentBlock::Heading { level: 1, text: "Záró rendelkezések".to_string() },
+        ]);
+        assert!(document.to_text().contains("## 1.1. Kamatváltoztatás\n\n"));
+        assert!(document.to_text().contains("| Esedékesség | Törlesztőrészlet |\n|---|---|\n| 2008.01.05 | 612,40 CHF |"));
+    }
+
+    const ODT_STYLES: &str = r#"<office:document-styles
+        xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+        xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+        xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+        <office:styles>
+            <text:outline-style style:name="Outline">
+                <text:outline-level-style text:level="1" style:num-format="1" style:num-suffix="."/>
+                <text:outline-level-style text:level="2" style:num-format="1" style:num-suffix="." text:display-levels="2"/>
+                <text:outline-level-style text:level="3" style:num-format=""/>
+            </text:outline-style>
+        </office:styles>
+        <office:master-styles>
+            <style:master-page style:name="Standard">
+                <style:header><text:p>Kölcsönszerződés</text:p></style:header>
+            </style:master-page>
+        </office:master-styles>
+    </office:document-styles>"#;
+
+    const ODT_CONTENT: &str = r#"<office:document-content
+        xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+        xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+        xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+        xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+        <office:automatic-styles>
+            <text:list-style style:name="L1">
+                <text:list-level-style-number text:level="1" style:num-format="a" style:num-suffix=")"/>
+                <text:list-level-style-number text:level="2" style:num-format="i" style:num-suffix="."/>
+            </text:list-style>
+        </office:automatic-styles>
+        <office:body><office:text>
+            <text:h text:outline-level="1">Kamat</text:h>
+            <text:p>Az ügyleti kamat változó.</text:p>
+            <text:h text:outline-level="2">Kamatváltoztatás</text:h>
+            <text:list text:style-name="L1">
+                <text:list-item><text:p>a refinanszírozási költségek változása,</text:p>
+                    <text:list><text:list-item><text:p>a CHF LIBOR emelkedése,</text:p></text:list-item></text:list>
+                </text:list-item>
+                <text:list-item><text:p>a jogszabályi környezet változása.</text:p></text:list-item>
+            </text:list>
+            <table:table>
+                <table:table-row>
+                    <table:table-cell><text:p>Esedékesség</text:p></table:table-cell>
+                    <table:table-cell><text:p>Törlesztőrészlet</text:p></table:table-cell>
+                </table:table-row>
+                <table:table-row>
+                    <table:table-cell><text:p>2008.01.05</text:p></table:table-cell>
+                    <table:table-cell><text:p>612,40 CHF</text:p></table:table-cell>
+                </table:table-row>
+            </table:table>
+            <text:list text:style-name="L1" text:continue-numbering="true">
+                <text:list-item><text:p>a hatósági díjak változása.</text:p></text:list-item>
+            </text:list>
+            <text:h text:outline-level="1"><text:number>2.</text:number>Árfolyamkockázat</text:h>
+            <text:h text:outline-level="3">Tájékoztatás</text:h>
+            <text:h text:outline-level="1">Záró rendelkezések</text:h>
+        </office:text></office:body>
+    </office:document-content>"#;
+
+    #[test]
+    fn test_odt_headings_numbering_and_tables() {
+        let odt = archive(&[("content.xml", ODT_CONTENT), ("styles.xml", ODT_STYLES)]);
+
+        let document = OfficeExtractor::new().extract(&odt, OfficeFormat::Odt).unwrap();
+
+        assert_eq!(document.blocks, vec![
+            DocumentBlock::Header { text: "Kölcsönszerződés".to_string() },
+            DocumentBlock::Heading { level: 1, text: "1. Kamat".to_string() },
+            DocumentBlock::Paragraph { text: "Az ügyleti kamat változó.".to_string() },
+            DocumentBlock::Heading { level: 2, text: "1.1. Kamatváltoztatás".to_string() },
+            DocumentBlock::Paragraph { text: "a) a refinanszírozási költségek változása,".to_string() },
+            DocumentBlock::Paragraph { text: "i. a CHF LIBOR emelkedése,".to_string() },
+            DocumentBlock::Paragraph { text: "b) a jogszabályi környezet változása.".to_string() },
+            DocumentBlock::Table { rows: vec![
+                vec!["Esedékesség".to_string(), "Törlesztőrészlet".to_string()],
+                vec!["2008.01.05".to_string(), "612,40 CHF".to_string()],
+            ] },
+            DocumentBlock::Paragraph { text: "c) a hatósági díjak változása.".to_string() },
+            // The number the writing application cached is kept as written
+            DocumentBlock::Heading { level: 1, text: "2. Árfolyamkockázat".to_string() },
+            DocumentBlock::Heading { level: 3, text: "Tájékoztatás".to_string() },
+            DocumentBlock::Heading { level: 1, text: "3. Záró rendelkezések".to_string() },
+        ]);
+    }
+}
@@ -0,0 +1,356 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use uuid::Uuid;
+use crate::models::PaymentScheduleRow;
+
+/// Detects repayment schedule tables in extracted text and turns them into
+/// typed instalment rows. Handles markdown tables (`| a | b |`, as produced by
+/// the DOCX/ODT extractor and Mistral OCR) and whitespace-aligned columns (as
+/// produced by the PDF text layer, separated by tabs or two or more spaces).
+///
+/// A header row fixes the column roles; it stays in effect for the following
+/// rows so tables continued on the next page without a repeated header are
+/// still read. Rows without a parseable due date (totals, carried balances)
+/// are skipped.
+pub struct PaymentTableExtractor {
+    iso_date: Regex,
+    european_date: Regex,
+    currency_code: Regex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaymentColumn {
+    DueDate,
+    ExchangeRate,
+    OutstandingBalance,
+    Principal,
+    Interest,
+    HufDebit,
+    Instalment,
+}
+
+struct TableHeader {
+    columns: Vec<Option<PaymentColumn>>,
+    currency: Option<String>,
+}
+
+impl PaymentTableExtractor {
+    pub fn new() -> Self {
+        Self {
+            iso_date: Regex::new(r"(\d{4})\s*[.\-/]\s*(\d{1,2})\s*[.\-/]\s*(\d{1,2})").unwrap(),
+            european_date: Regex::new(r"(\d{1,2})\s*[.\-/]\s*(\d{1,2})\s*[.\-/]\s*(\d{4})").unwrap(),
+            currency_code: Regex::new(r"\b(CHF|EUR|JPY|USD|GBP)\b").unwrap(),
+        }
+    }
+
+    pub fn extract(&self, document_id: Uuid, text: &str) -> Vec<PaymentScheduleRow> {
+        let mut rows = Vec::new();
+        let mut header: Option<TableHeader> = None;
+        let mut char_offset = 0usize;
+
+        for line in text.split_inclusive(['\n', '\u{000C}']) {
+            let line_offset = char_offset;
+            char_offset += line.chars().count();
+
+            let Some(cells) = split_cells(line) else { continue };
+
+            if let Some(found) = self.parse_header(&cells) {
+                header = Some(found);
+                continue;
+            }
+
+            let Some(current) = &header else { continue };
+            if cells.len() != current.columns.len() {
+                continue;
+            }
+            if let Some(row) = self.parse_row(document_id, rows.len() as i32, current, &cells, line_offset, line.trim()) {
+                rows.push(row);
+            }
+        }
+
+        rows
+    }
+
+    fn parse_header(&self, cells: &[String]) -> Option<TableHeader> {
+        let columns: Vec<Option<PaymentColumn>> = cells.iter().map(|c| classify_header(c)).collect();
+        let has_date = columns.contains(&Some(PaymentColumn::DueDate));
+        let amounts = columns.iter()
+            .filter(|c| matches!(c, Some(col) if *col != PaymentColumn::DueDate))
+            .count();
+        // A data row never parses as a header: its date cell has no keyword
+        if !has_date || amounts == 0 {
+            return None;
+        }
+
+        let currency = cells.iter()
+            .zip(&columns)
+            .filter(|(_, col)| !matches!(col, Some(PaymentColumn::HufDebit) | Some(PaymentColumn::ExchangeRate)))
+            .find_map(|(cell, _)| self.currency_code.captures(&cell.to_uppercase()).map(|c| c[1].to_string()));
+
+        Some(TableHeader { columns, currency })
+    }
+
+    fn parse_row(
+        &self,
+        document_id: Uuid,
+        row_index: i32,
+        header: &TableHeader,
+        cells: &[String],
+        offset: usize,
+        source: &str,
+    ) -> Option<PaymentScheduleRow> {
+        let date_index = header.columns.iter().position(|c| *c == Some(PaymentColumn::DueDate))?;
+        let due_date = self.parse_date(&cells[date_index])?;
+
+        let mut row = PaymentScheduleRow::new(document_id, row_index, due_date, offset as i32, source.to_string());
+        let mut amounts = 0;
+
+        for (cell, column) in cells.iter().zip(&header.columns) {
+            let Some(column) = column else { continue };
+            if *column == PaymentColumn::DueDate {
+                continue;
+            }
+            let value = if *column == PaymentColumn::ExchangeRate { parse_rate(cell) } else { parse_amount(cell) };
+            let Some(value) = value else { continue };
+            amounts += 1;
+
+            match column {
+                PaymentColumn::ExchangeRate => row.exchange_rate = Some(value),
+                PaymentColumn::OutstandingBalance => row.outstanding_balance = Some(value),
+                PaymentColumn::Principal => row.principal = Some(value),
+                PaymentColumn::Interest => row.interest = Some(value),
+                PaymentColumn::HufDebit => row.huf_debit = Some(value),
+                PaymentColumn::Instalment => row.instalment_amount = Some(value),
+                PaymentColumn::DueDate => {}
+            }
+
+            if row.currency.is_none() && !matches!(column, PaymentColumn::HufDebit | PaymentColumn::ExchangeRate) {
+                row.currency = self.currency_code.captures(cell).map(|c| c[1].to_string());
+            }
+        }
+
+        if amounts == 0 {
+            return None;
+        }
+        if row.currency.is_none() {
+            row.currency = header.currency.clone();
+        }
+
+        Some(row)
+    }
+
+    /// Parses `2008.03.15.`, `2008. 03. 15.`, `2008-03-15` and day-first `15.03.2008` / `15/03/2008`.
+    pub fn parse_date(&self, cell: &str) -> Option<NaiveDate> {
+        if let Some(c) = self.iso_date.captures(cell) {
+            return NaiveDate::from_ymd_opt(c[1].parse().ok()?, c[2].parse().ok()?, c[3].parse().ok()?);
+        }
+        if let Some(c) = self.european_date.captures(cell) {
+            return NaiveDate::from_ymd_opt(c[3].parse().ok()?, c[2].parse().ok()?, c[1].parse().ok()?);
+        }
+        None
+    }
+}
+
+/// Splits a table line into cells, or `None` if the line is not tabular.
+fn split_cells(line: &str) -> Option<Vec<String>> {
+    let line = line.trim_matches(['\n', '\r', '\u{000C}']).trim();
+
+    if line.starts_with('|') {
+        let inner = line.trim_start_matches('|').trim_end_matches('|');
+        let cells: Vec<String> = inner.split('|').map(|c| c.trim().to_string()).collect();
+        // Markdown separator row |---|:---:|
+        if cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':' | ' '))) {
+            return None;
+        }
+        return (cells.len() >= 2).then_some(cells);
+    }
+
+    let mut cells = Vec::new();
+    for part in line.split('\t') {
+        let mut current = String::new();
+        let mut spaces = 0;
+        for ch in part.chars() {
+            if ch == ' ' {
+                spaces += 1;
+                continue;
+            }
+            if spaces >= 2 && !current.is_empty() {
+                cells.push(std::mem::take(&mut current));
+            } else if spaces == 1 && !current.is_empty() {
+                current.push(' ');
+            }
+            spaces = 0;
+            current.push(ch);
+        }
+        if !current.is_empty() {
+            cells.push(current);
+        }
+    }
+
+    (cells.len() >= 2).then_some(cells)
+}
+
+fn classify_header(cell: &str) -> Option<PaymentColumn> {
+    let lower = cell.to_lowercase();
+    let tokens: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).filter(|t| !t.is_empty()).collect();
+    let token = |words: &[&str]| tokens.iter().any(|t| words.contains(t));
+    // Hungarian compounds put the stem anywhere in a word ("Tőketartozás", "Törlesztőrészlet")
+    let stem = |stems: &[&str]| tokens.iter().any(|t| stems.iter().any(|s| t.contains(s)));
+
+    // Interest rates and other percentages are not amounts
+    if lower.contains('%')
+        || stem(&["kamatláb", "oprocentowanie"])
+        || token(&["thm", "apr"])
+        || (token(&["interest", "úroková"]) && token(&["rate", "sazba"]))
+    {
+        None
+    } else if stem(&["dátum", "esedékes", "időpont"]) || token(&["date", "napja", "data", "datum", "termin"]) {
+        Some(PaymentColumn::DueDate)
+    } else if stem(&["árfolyam"]) || token(&["exchange", "kurs", "kurz"]) {
+        Some(PaymentColumn::ExchangeRate)
+    } else if stem(&["tartozás", "egyenleg", "fennálló"]) || token(&["balance", "outstanding", "saldo", "zůstatek"]) {
+        Some(PaymentColumn::OutstandingBalance)
+    } else if stem(&["tőke"]) || token(&["principal", "kapitał", "jistina"]) {
+        Some(PaymentColumn::Principal)
+    } else if stem(&["kamat"]) || token(&["interest", "odsetki", "úrok"]) {
+        Some(PaymentColumn::Interest)
+    } else if token(&["huf", "ft", "debit"]) || stem(&["forint", "terhelt", "terhelés", "levont"]) {
+        Some(PaymentColumn::HufDebit)
+    } else if stem(&["részlet", "törlesztő"])
+        || token(&["instalment", "installment", "rata", "splátka", "payment"])
+        || token(&["chf", "eur", "jpy", "usd", "gbp"])
+    {
+        Some(PaymentColumn::Instalment)
+    } else {
+        None
+    }
+}
+
+/// Parses amounts in Hungarian and international notation: `1 234,56`,
+/// `1.234,56 Ft`, `1,234.56 CHF`, `-12,5`, `1.000,- Ft`. A lone comma is a
+/// decimal comma; a lone dot followed by exactly three digits separates
+/// thousands (`81.123 Ft`).
+pub fn parse_amount(cell: &str) -> Option<f64> {
+    parse_number(cell, true)
+}
+
+/// Parses an exchange rate like `parse_amount`, except that a lone dot is
+/// always a decimal point: rates are quoted to three or four decimals
+/// (`158.325`), as `rates::mnb_import::parse_number` reads them.
+pub fn parse_rate(cell: &str) -> Option<f64> {
+    parse_number(cell, false)
+}
+
+fn parse_number(cell: &str, dot_groups_thousands: bool) -> Option<f64> {
+    let mut cleaned: String = cell.chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+        .collect();
+    // Currency words and codes are dropped with the letters; a trailing "." from "Ft." must go too
+    cleaned = cleaned.trim_matches('.').to_string();
+    // "1.000,-": the dash stands in for zero fillér
+    if let Some(whole) = cleaned.strip_suffix(",-") {
+        cleaned = whole.to_string();
+    }
+    if !cleaned.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let last_dot = cleaned.rfind('.');
+    let last_comma = cleaned.rfind(',');
+    let normalized = match (last_dot, last_comma) {
+        (Some(d), Some(c)) if c > d => cleaned.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => cleaned.replace(',', ""),
+        (None, Some(_)) if cleaned.matches(',').count() > 1 => cleaned.replace(',', ""),
+        (None, Some(_)) => cleaned.replace(',', "."),
+        (Some(_), None) if cleaned.matches('.').count() > 1 => cleaned.replace('.', ""),
+        (Some(d), None) if dot_groups_thousands && cleaned.len() - d == 4 => cleaned.replace('.', ""),
+        _ => cleaned,
+    };
+
+    normalized.parse::<f64>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_schedule_rows() {
+        let text = "Törlesztési tábla\n\n\
+            | Esedékesség | Törlesztőrészlet (CHF) | Árfolyam | Terhelt összeg (Ft) | Tőke | Kamat |\n\
+            |---|---|---|---|---|---|\n\
+            | 2008.03.15. | 512,40 | 158,32 | 81 123 Ft | 301,10 | 211,30 |\n\
+            | 2008.04.15. | 512,40 | 161,05 | 82 522 Ft | 302,55 | 209,85 |\n\
+            | Összesen | 1 024,80 | | 163 645 Ft | | |\n";
+
+        let rows = PaymentTableExtractor::new().extract(Uuid::nil(), text);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].due_date, NaiveDate::from_ymd_opt(2008, 3, 15).unwrap());
+        assert_eq!(rows[0].currency.as_deref(), Some("CHF"));
+        assert_eq!(rows[0].instalment_amount, Some(512.40));
+        assert_eq!(rows[0].huf_debit, Some(81123.0));
+        assert_eq!(rows[1].exchange_rate, Some(161.05));
+        assert_eq!(rows[1].interest, Some(209.85));
+    }
+
+    #[test]
+    fn test_aligned_columns_continue_across_pages() {
+        let text = "Dátum        Részlet CHF    HUF terhelés    Tőketartozás\n\
+            15/01/2010   498.20         87,245.10       101,233.50\n\
+            \u{000C}Oldal 2\n\
+            15/02/2010   498.20         86,110.00       100,901.75\n";
+
+        let rows = PaymentTableExtractor::new().extract(Uuid::nil(), text);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].due_date, NaiveDate::from_ymd_opt(2010, 2, 15).unwrap());
+        assert_eq!(rows[1].huf_debit, Some(86110.0));
+        assert_eq!(rows[1].outstanding_balance, Some(100901.75));
+        assert!(rows[1].source_offset > rows[0].source_offset);
+    }
+
+    #[test]
+    fn test_parse_amount_notations() {
+        assert_eq!(parse_amount("1 234,56"), Some(1234.56));
+        assert_eq!(parse_amount("1.234,56 Ft"), Some(1234.56));
+        assert_eq!(parse_amount("1,234.56 CHF"), Some(1234.56));
+        assert_eq!(parse_amount("81.123 Ft"), Some(81123.0));
+        assert_eq!(parse_amount("1.000,- Ft"), Some(1000.0));
+        assert_eq!(parse_amount("12 500,- Ft"), Some(12500.0));
+        assert_eq!(parse_amount("158.32"), Some(158.32));
+        assert_eq!(parse_amount("-"), None);
+        assert_eq!(parse_rate("158.325"), Some(158.325));
+        assert_eq!(parse_rate("158,325"), Some(158.325));
+        assert_eq!(parse_rate("1.234,5678"), Some(1234.5678));
+    }
+
+    #[test]
+    fn test_three_decimal_rate_is_not_read_as_thousands() {
+        let text = "| Esedékesség | Törlesztőrészlet (CHF) | Árfolyam | Terhelt összeg (Ft) |\n\
+            |---|---|---|---|\n\
+            | 2008.03.15. | 512,40 | 158.325 | 81.125 Ft |\n";
+
+        let rows = PaymentTableExtractor::new().extract(Uuid::nil(), text);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].exchange_rate, Some(158.325));
+        assert_eq!(rows[0].huf_debit, Some(81125.0));
+    }
+
+    #[test]
+    fn test_interest_rate_columns_are_not_amounts() {
+        let text = "| Esedékesség | Kamatláb | Árfolyam | Törlesztőrészlet (CHF) | Kamat |\n\
+            |---|---|---|---|---|\n\
+            | 2008.03.15. | 4,5 | 158,32 | 512,40 | 211,30 |\n\
+            \n\
+            | Due date | Interest rate | Exchange rate | Instalment CHF |\n\
+            |---|---|---|---|\n\
+            | 2009.03.15. | 4.5 | 171.20 | 512.40 |\n";
+
+        let rows = PaymentTableExtractor::new().extract(Uuid::nil(), text);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].exchange_rate, Some(158.32));
+        assert_eq!(rows[0].interest, Some(211.30));
+        assert_eq!(rows[0].instalment_amount, Some(512.40));
+        assert_eq!(rows[1].exchange_rate, Some(171.20));
+        assert_eq!(rows[1].interest, None);
+    }
+}
@@ -0,0 +1,248 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Builds the section tree of a contract from its numbering: annexes
+/// ("1. számú melléklet", "Annex 2"), chapters ("I. fejezet"), numbered points
+/// ("5.", "5.2.", "§ 5") and lettered sub-points ("5.2.a)", "b)").
+///
+/// Every character offset of the text belongs to the innermost section that
+/// starts at or before it; text before the first heading belongs to no section.
+pub struct SectionParser {
+    annex: Regex,
+    chapter: Regex,
+    numbered: Regex,
+    lettered: Regex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SectionKind {
+    Annex,
+    Chapter,
+    Point,
+    SubPoint,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Section {
+    pub kind: SectionKind,
+    pub label: String, // "2" for an annex, "II" for a chapter, "5.2" or "5.2.a" for points
+    pub title: String,
+    pub level: usize,
+    pub start: usize, // character offsets into the parsed text
+    pub end: usize,
+    pub parent: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SectionTree {
+    pub sections: Vec<Section>, // document order
+}
+
+impl SectionTree {
+    /// Index of the innermost section containing `offset`.
+    pub fn section_index_at(&self, offset: usize) -> Option<usize> {
+        self.sections.iter()
+            .enumerate()
+            .filter(|(_, s)| offset >= s.start && offset < s.end)
+            .max_by_key(|(_, s)| s.level)
+            .map(|(i, _)| i)
+    }
+
+    pub fn section_at(&self, offset: usize) -> Option<&Section> {
+        self.section_index_at(offset).map(|i| &self.sections[i])
+    }
+
+    /// Sections from the outermost down to the one containing `offset`.
+    pub fn path_at(&self, offset: usize) -> Vec<&Section> {
+        let mut path = Vec::new();
+        let mut current = self.section_index_at(offset);
+        while let Some(i) = current {
+            path.push(&self.sections[i]);
+            current = self.sections[i].parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Human readable location such as "Section 7.3 of Annex 2" or "Section 5.2.a of Chapter II".
+    pub fn describe(&self, offset: usize) -> Option<String> {
+        let path = self.path_at(offset);
+        if path.is_empty() {
+            return None;
+        }
+
+        let mut parts = Vec::new();
+        if let Some(point) = path.iter().rev().find(|s| matches!(s.kind, SectionKind::Point | SectionKind::SubPoint)) {
+            parts.push(format!("Section {}", point.label));
+        }
+        if let Some(chapter) = path.iter().find(|s| s.kind == SectionKind::Chapter) {
+            parts.push(format!("Chapter {}", chapter.label));
+        }
+        if let Some(annex) = path.iter().find(|s| s.kind == SectionKind::Annex) {
+            parts.push(format!("Annex {}", annex.label));
+        }
+
+        Some(parts.join(" of "))
+    }
+
+    /// Character range of the innermost numbered point containing `offset`,
+    /// lettered sub-points included, so callers can work on whole points
+    /// instead of fixed-width windows.
+    pub fn point_range_at(&self, offset: usize) -> Option<(usize, usize)> {
+        self.path_at(offset).into_iter()
+            .rev()
+            .find(|s| s.kind == SectionKind::Point)
+            .map(|s| (s.start, s.end))
+    }
+
+    pub fn section_text(&self, text: &str, section: &Section) -> String {
+        text.chars().skip(section.start).take(section.end - section.start).collect()
+    }
+}
+
+impl SectionParser {
+    pub fn new() -> Self {
+        Self {
+            annex: Regex::new(
+                r"(?i)^(?:(\d+)\.?\s*(?:sz(?:ámú|\.)\s*)?melléklet|(?:melléklet|annex|appendix|schedule|załącznik|příloha|anexa|prilog)\s*(?:nr\.?|no\.?|č\.|sz\.)?\s*(\d+|(?-i:[IVXLC]+))\b)\s*[:.\-–]?\s*(.*)$"
+            ).unwrap(),
+            chapter: Regex::new(
+                r"(?i)^(?:((?-i:[IVXLC]+))\.\s*(?:fejezet|rész)|(?:chapter|part|rozdział|kapitola|capitolul)\s+((?-i:[IVXLC]+)|\d+)\b)\.?\s*[:.\-–]?\s*(.*)$"
+            ).unwrap(),
+            numbered: Regex::new(r"^(?:§\s*(\d{1,3})\.?|(\d{1,3}(?:\.\d{1,3})*)\.?)(?:\s+(.*))?$").unwrap(),
+            lettered: Regex::new(r"^(?:(\d{1,3}(?:\.\d{1,3})*)\.)?\(?([a-z])\)\s*(.*)$").unwrap(),
+        }
+    }
+
+    pub fn parse(&self, text: &str) -> SectionTree {
+        let mut tree = SectionTree::default();
+        let mut open: Vec<usize> = Vec::new();
+        let mut offset = 0usize;
+
+        for line in text.split_inclusive('\n') {
+            let line_start = offset;
+            offset += line.chars().count();
+
+            // Markdown headings from the office and OCR extractors carry the same numbering
+            let trimmed = line.trim().trim_start_matches('#').trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let Some((kind, label, title)) = self.classify(trimmed, &tree, &open) else { continue };
+            let level = match kind {
+                SectionKind::Annex => 0,
+                SectionKind::Chapter => 1,
+                // "5" -> 2, "5.2" -> 3, "5.2.a" and "5.2.1" -> 4
+                SectionKind::Point | SectionKind::SubPoint => 1 + label.split('.').count(),
+            };
+
+            while let Some(&last) = open.last() {
+                if tree.sections[last].level >= level {
+                    tree.sections[last].end = line_start;
+                    open.pop();
+                } else {
+                    break;
+                }
+            }
+
+            tree.sections.push(Section {
+                kind,
+                label,
+                title,
+                level,
+                start: line_start,
+                end: line_start,
+                parent: open.last().copied(),
+            });
+            open.push(tree.sections.len() - 1);
+        }
+
+        for i in open {
+            tree.sections[i].end = offset;
+        }
+
+        tree
+    }
+
+    fn classify(&self, line: &str, tree: &SectionTree, open: &[usize]) -> Option<(SectionKind, String, String)> {
+        if let Some(c) = self.annex.captures(line) {
+            let label = c.get(1).or_else(|| c.get(2))?.as_str().to_string();
+            return Some((SectionKind::Annex, label, c[3].trim().to_string()));
+        }
+
+        if let Some(c) = self.chapter.captures(line) {
+            let label = c.get(1).or_else(|| c.get(2))?.as_str().to_uppercase();
+            return Some((SectionKind::Chapter, label, c[3].trim().to_string()));
+        }
+
+        if let Some(c) = self.lettered.captures(line) {
+            // "a)" hangs off the innermost open point; "5.2.a)" names its point explicitly
+            let point = match c.get(1) {
+                Some(number) => number.as_str().to_string(),
+                None => open.iter().rev()
+                    .map(|&i| &tree.sections[i])
+                    .find(|s| s.kind == SectionKind::Point)?
+                    .label.clone(),
+            };
+            return Some((SectionKind::SubPoint, format!("{}.{}", point, &c[2]), c[3].trim().to_string()));
+        }
+
+        let c = self.numbered.captures(line)?;
+        let label = c.get(1).or_else(|| c.get(2))?.as_str().to_string();
+        let title = c.get(3).map_or("", |m| m.as_str()).trim();
+        // "12 000 Ft" or "3.5 százalék" is not a heading: without a closing dot or a §
+        // only multi-level numbers followed by a capitalised title count
+        let has_dot = line[c.get(2).or_else(|| c.get(1))?.end()..].starts_with('.');
+        if c.get(1).is_none() && !has_dot
+            && !(label.contains('.') && title.starts_with(char::is_uppercase))
+        {
+            return None;
+        }
+        if title.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return None;
+        }
+
+        Some((SectionKind::Point, label, title.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "KÖLCSÖNSZERZŐDÉS\n\
+        I. fejezet Általános rendelkezések\n\
+        5. Kamat\n\
+        5.1. A kamat mértéke évi 4,5%.\n\
+        5.2. A Bank jogosult a kamatot egyoldalúan módosítani, ha\n\
+        a) a refinanszírozási költségek változnak;\n\
+        b) a jogszabályi környezet változik.\n\
+        6. Árfolyamkockázat\n\
+        1. számú melléklet Törlesztési tábla\n\
+        7. Díjak\n\
+        7.3. A folyósítás vételi árfolyamon történik.\n";
+
+    #[test]
+    fn test_nested_points_and_annexes() {
+        let tree = SectionParser::new().parse(CONTRACT);
+
+        let offset_of = |needle: &str| CONTRACT[..CONTRACT.find(needle).unwrap()].chars().count();
+
+        assert_eq!(tree.describe(offset_of("refinanszírozási")).as_deref(), Some("Section 5.2.a of Chapter I"));
+        assert_eq!(tree.describe(offset_of("jogszabályi")).as_deref(), Some("Section 5.2.b of Chapter I"));
+        assert_eq!(tree.describe(offset_of("vételi árfolyamon")).as_deref(), Some("Section 7.3 of Annex 1"));
+        assert_eq!(tree.describe(0), None);
+
+        let (start, end) = tree.point_range_at(offset_of("refinanszírozási")).unwrap();
+        let point: String = CONTRACT.chars().skip(start).take(end - start).collect();
+        assert!(point.starts_with("5.2. A Bank"));
+        assert!(point.trim_end().ends_with("változik."));
+    }
+
+    #[test]
+    fn test_dates_and_amounts_are_not_headings() {
+        let tree = SectionParser::new().parse("2008. március 15.\n12 000 Ft\n3.5 százalék\n");
+        assert!(tree.sections.is_empty());
+    }
+}
@@ -1,9 +1,23 @@
 use base64::{Engine, engine::general_purpose};
-use std::io::Read;
+use serde::{Deserialize, Serialize};
 use tempfile::NamedTempFile;
-use std::fs;
+use std::path::Path;
+use super::language_detector::LanguageDetector;
+use super::ocr_engine::{OcrEngine, OcrError, OcrOutput, TesseractEngine, tesseract_language};
+use super::office_extractor::{OfficeExtractor, OfficeFormat};
+use super::section_parser::{SectionParser, SectionTree};
 
-pub struct TextProcessor;
+/// Page separator used when joining per-page text into `extracted_text`
+/// (form feed, the same convention as pdftotext).
+pub const PAGE_SEPARATOR: char = '\u{000C}';
+
+/// Pages with fewer visible characters than this are treated as image-only.
+const MIN_TEXT_LAYER_CHARS: usize = 16;
+
+pub struct TextProcessor {
+    ocr_engine: Box<dyn OcrEngine>,
+    language_detector: LanguageDetector,
+}
 
 #[derive(Debug)]
 pub struct ProcessingResult {
@@ -11,11 +25,36 @@ pub struct ProcessingResult {
     pub confidence: f32,
     pub processing_method: String,
     pub language_detected: Option<String>,
+    pub pages: Vec<PageText>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageText {
+    pub page_number: u32,
+    pub text: String,
+    pub needs_ocr: bool,
+}
+
+impl ProcessingResult {
+    /// Page numbers (1-based) that had no usable text layer.
+    pub fn pages_needing_ocr(&self) -> Vec<u32> {
+        self.pages.iter()
+            .filter(|p| p.needs_ocr)
+            .map(|p| p.page_number)
+            .collect()
+    }
 }
 
 impl TextProcessor {
     pub fn new() -> Self {
-        Self
+        Self::with_ocr_engine(Box::new(TesseractEngine::new()))
+    }
+
+    pub fn with_ocr_engine(ocr_engine: Box<dyn OcrEngine>) -> Self {
+        Self {
+            ocr_engine,
+            language_detector: LanguageDetector::new(),
+        }
     }
 
     pub async fn process_document(&self, file_data: &str, content_type: &str) -> Result<ProcessingResult, Box<dyn std::error::Error>> {
@@ -23,9 +62,11 @@ impl TextProcessor {
             "application/pdf" => self.process_pdf(file_data).await,
             "text/plain" => self.process_text(file_data),
             "image/jpeg" | "image/png" => self.process_image(file_data, content_type).await,
-            "application/msword" | "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => {
-                self.process_word_document(file_data).await
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document" |
+            "application/vnd.oasis.opendocument.text" => {
+                self.process_word_document(file_data, content_type).await
             },
+            "application/msword" => Err("Legacy .doc files are not supported - please convert to .docx or .odt".into()),
             _ => Err(format!("Unsupported content type: {}", content_type).into()),
         }
     }
@@ -39,6 +80,7 @@ impl TextProcessor {
             confidence: 1.0,
             processing_method: "direct_text".to_string(),
             language_detected: None,
+            pages: Vec::new(),
         })
     }
 
@@ -46,19 +88,34 @@ impl TextProcessor {
         // Decode base64 data
         let decoded = general_purpose::STANDARD.decode(file_data)?;
         
-        // Create temporary file
-        let mut temp_file = NamedTempFile::new()?;
-        std::io::Write::write_all(&mut temp_file, &decoded)?;
-        
-        // For now, we'll use a simple text extraction approach
-        // In production, you would integrate with a proper PDF library like pdf-extract or poppler
-        let extracted_text = self.extract_pdf_text_simple(&decoded)?;
+        // Extract the embedded text layer page by page
+        let pages = self.extract_pdf_pages(&decoded)?;
+        if pages.is_empty() {
+            return Err("PDF contains no pages".into());
+        }
+
+        let text_pages = pages.iter().filter(|p| !p.needs_ocr).count();
+        let extracted_text = pages.iter()
+            .map(|p| p.text.as_str())
+            .collect::<Vec<_>>()
+            .join(&PAGE_SEPARATOR.to_string());
+
+        // Confidence reflects how much of the document actually had a text layer
+        let confidence = 0.95 * text_pages as f32 / pages.len() as f32;
+        let processing_method = if text_pages == pages.len() {
+            "pdf_text_layer"
+        } else if text_pages == 0 {
+            "pdf_needs_ocr"
+        } else {
+            "pdf_text_layer_partial"
+        };
         
         Ok(ProcessingResult {
             extracted_text,
-            confidence: 0.8, // PDF extraction is generally reliable
-            processing_method: "pdf_extraction".to_string(),
+            confidence,
+            processing_method: processing_method.to_string(),
             language_detected: None,
+            pages,
         })
     }
 
@@ -77,81 +134,131 @@ impl TextProcessor {
         std::io::Write::write_all(&mut temp_file, &decoded)?;
         
         // Perform OCR
-        let extracted_text = self.perform_ocr(temp_file.path().to_str().unwrap()).await?;
+        let (ocr_output, language) = self.perform_ocr(temp_file.path()).await?;
+        let extracted_text = ocr_output.text();
         
         Ok(ProcessingResult {
+            confidence: ocr_output.mean_confidence(),
+            processing_method: format!("ocr_{}", ocr_output.engine),
+            language_detected: Some(language),
+            pages: vec![PageText {
+                page_number: 1,
+                text: extracted_text.clone(),
+                needs_ocr: false,
+            }],
             extracted_text,
-            confidence: 0.7, // OCR has variable accuracy
-            processing_method: "ocr".to_string(),
-            language_detected: None,
         })
     }
 
-    async fn process_word_document(&self, file_data: &str) -> Result<ProcessingResult, Box<dyn std::error::Error>> {
+    async fn process_word_document(&self, file_data: &str, content_type: &str) -> Result<ProcessingResult, Box<dyn std::error::Error>> {
         // Decode base64 data
         let decoded = general_purpose::STANDARD.decode(file_data)?;
         
-        // Create temporary file
-        let mut temp_file = NamedTempFile::with_suffix(".docx")?;
-        std::io::Write::write_all(&mut temp_file, &decoded)?;
-        
-        // Extract text from Word document
-        let extracted_text = self.extract_word_text(temp_file.path().to_str().unwrap())?;
+        let format = OfficeFormat::from_content_type(content_type)
+            .ok_or_else(|| format!("Unsupported office document type: {}", content_type))?;
+
+        // Extract body, tables, headers/footers and footnotes with structural markers
+        let extracted_text = self.extract_word_text(&decoded, format)?;
         
         Ok(ProcessingResult {
             extracted_text,
             confidence: 0.9, // Word extraction is generally very reliable
-            processing_method: "word_extraction".to_string(),
+            processing_method: match format {
+                OfficeFormat::Docx => "docx_extraction",
+                OfficeFormat::Odt => "odt_extraction",
+            }.to_string(),
             language_detected: None,
+            pages: Vec::new(),
         })
     }
 
-    fn extract_pdf_text_simple(&self, _pdf_data: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
-        // Placeholder for PDF text extraction
-        // In production, integrate with pdf-extract or similar library
-        
-        // For now, return a message indicating PDF processing is needed
-        Ok("PDF text extraction not implemented - please implement pdf-extract integration".to_string())
+    /// Extracts the embedded text layer of every page. Pages without a usable
+    /// text layer (scans) come back empty with `needs_ocr` set instead of failing.
+    pub fn extract_pdf_pages(&self, pdf_data: &[u8]) -> Result<Vec<PageText>, Box<dyn std::error::Error>> {
+        let document = lopdf::Document::load_mem(pdf_data)?;
+        let mut pages = Vec::new();
+
+        for (page_number, _) in document.get_pages() {
+            // A broken content stream on one page should not lose the rest of the document
+            let raw = document.extract_text(&[page_number]).unwrap_or_default();
+            let text = self.clean_pdf_page_text(&raw);
+            let visible_chars = text.chars().filter(|c| c.is_alphanumeric()).count();
+
+            if visible_chars < MIN_TEXT_LAYER_CHARS {
+                pages.push(PageText {
+                    page_number,
+                    text: String::new(),
+                    needs_ocr: true,
+                });
+            } else {
+                pages.push(PageText {
+                    page_number,
+                    text,
+                    needs_ocr: false,
+                });
+            }
+        }
+
+        Ok(pages)
     }
 
-    async fn perform_ocr(&self, image_path: &str) -> Result<String, Box<dyn std::error::Error>> {
-        // Placeholder for OCR functionality
-        // In production, integrate with tesseract-rs or call tesseract command
-        
-        // Check if tesseract is available
-        let output = std::process::Command::new("tesseract")
-            .arg("--version")
-            .output();
-            
-        match output {
-            Ok(_) => {
-                // Tesseract is available, perform OCR
-                let ocr_output = std::process::Command::new("tesseract")
-                    .arg(image_path)
-                    .arg("stdout")
-                    .arg("-l")
-                    .arg("hun+eng") // Hungarian and English languages
-                    .output()?;
-                
-                if ocr_output.status.success() {
-                    Ok(String::from_utf8_lossy(&ocr_output.stdout).to_string())
-                } else {
-                    let error = String::from_utf8_lossy(&ocr_output.stderr);
-                    Err(format!("OCR failed: {}", error).into())
+    fn clean_pdf_page_text(&self, raw: &str) -> String {
+        // lopdf emits one line per text operation; rejoin words broken at a
+        // hyphen + newline + lowercase letter and drop empty lines. Hyphens
+        // elsewhere ("CHF-alapú") are left alone, and a line-end hyphen after
+        // a capital or digit belongs to the compound ("CHF-\nalapú"), so it stays.
+        let mut text = String::with_capacity(raw.len());
+        let mut after_blank = false;
+        for line in raw.lines().map(|l| l.trim_end()) {
+            if line.trim().is_empty() {
+                after_blank = !text.is_empty();
+                continue;
+            }
+            let continues_word = !after_blank && text.ends_with('-') && line.starts_with(|c: char| c.is_lowercase());
+            if continues_word {
+                let before_hyphen = text[..text.len() - 1].chars().next_back();
+                if before_hyphen.is_some_and(|c| c.is_lowercase()) {
+                    text.pop();
                 }
-            },
-            Err(_) => {
-                // Tesseract not available, return placeholder
-                Ok("OCR not available - please install tesseract".to_string())
+            } else if !text.is_empty() {
+                text.push('\n');
             }
+            text.push_str(line);
+            after_blank = false;
         }
+        text
     }
 
-    fn extract_word_text(&self, doc_path: &str) -> Result<String, Box<dyn std::error::Error>> {
-        // Placeholder for Word document text extraction
-        // In production, integrate with a library like docx-rs or python-docx via subprocess
-        
-        Ok("Word document text extraction not implemented - please implement docx-rs integration".to_string())
+    /// Runs OCR with Hungarian + English first, then re-runs with the detected
+    /// language when the text turns out to be e.g. Czech or Romanian.
+    async fn perform_ocr(&self, image_path: &Path) -> Result<(OcrOutput, String), Box<dyn std::error::Error>> {
+        let first_pass = self.ocr_engine.recognize(image_path, &["hu", "en"])?;
+        let detection = self.language_detector.detect_language(&first_pass.text());
+
+        if matches!(detection.language.as_str(), "hu" | "en") || tesseract_language(&detection.language).is_none() {
+            return Ok((first_pass, detection.language));
+        }
+
+        match self.ocr_engine.recognize(image_path, &[detection.language.as_str(), "en"]) {
+            Ok(second_pass) if second_pass.mean_confidence() >= first_pass.mean_confidence() => {
+                Ok((second_pass, detection.language))
+            },
+            Ok(_) => Ok((first_pass, detection.language)),
+            Err(OcrError::LanguageUnavailable(lang)) => {
+                log::warn!("OCR language data for '{}' is not installed, keeping hun+eng result", lang);
+                Ok((first_pass, detection.language))
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn extract_word_text(&self, data: &[u8], format: OfficeFormat) -> Result<String, Box<dyn std::error::Error>> {
+        let document = OfficeExtractor::new().extract(data, format)?;
+        if document.blocks.is_empty() {
+            return Err("Office document contains no text".into());
+        }
+
+        Ok(document.to_text())
     }
 
     pub fn clean_text(&self, text: &str) -> String {
@@ -200,17 +307,39 @@ impl TextProcessor {
     pub fn detect_document_structure(&self, text: &str) -> DocumentStructure {
         let lines: Vec<&str> = text.lines().collect();
         let mut structure = DocumentStructure::new();
+        structure.sections = SectionParser::new().parse(text);
+
+        // Office documents and OCR markdown carry explicit heading markers;
+        // only fall back to guessing from capitals when there are none
+        let has_heading_markers = lines.iter().any(|l| Self::markdown_heading(l).is_some());
         
         for (i, line) in lines.iter().enumerate() {
             let trimmed = line.trim();
-            
-            // Detect headers (short lines in caps or with numbers)
-            if trimmed.len() < 50 && (
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if has_heading_markers {
+                if let Some((_, heading)) = Self::markdown_heading(trimmed) {
+                    structure.headers.push((i, heading.to_string()));
+                    continue;
+                }
+            } else if trimmed.len() < 50 && (
+                // Detect headers (short lines in caps or with numbers)
                 trimmed.chars().all(|c| c.is_uppercase() || c.is_whitespace() || c.is_numeric()) ||
                 trimmed.starts_with(char::is_numeric)
             ) {
                 structure.headers.push((i, trimmed.to_string()));
             }
+
+            // Page furniture, table rows and footnote bodies are not running text
+            let is_marker_line = trimmed.starts_with("[header]") ||
+                trimmed.starts_with("[footer]") ||
+                trimmed.starts_with('|') ||
+                trimmed.starts_with("[^");
+            if !is_marker_line && trimmed.len() > 20 {
+                structure.paragraphs.push((i, trimmed.to_string()));
+            }
             
             // Detect potential clauses (lines with legal language)
             if trimmed.len() > 50 && (
@@ -226,6 +355,17 @@ impl TextProcessor {
         
         structure
     }
+
+    /// Returns (level, title) for a markdown heading line such as "## 5. Kamat".
+    fn markdown_heading(line: &str) -> Option<(usize, &str)> {
+        let trimmed = line.trim_start();
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if level == 0 || level > 6 {
+            return None;
+        }
+        let title = trimmed[level..].strip_prefix(' ')?.trim();
+        if title.is_empty() { None } else { Some((level, title)) }
+    }
 }
 
 #[derive(Debug)]
@@ -233,6 +373,7 @@ pub struct DocumentStructure {
     pub headers: Vec<(usize, String)>,
     pub clauses: Vec<(usize, String)>,
     pub paragraphs: Vec<(usize, String)>,
+    pub sections: SectionTree,
 }
 
 impl DocumentStructure {
@@ -241,6 +382,104 @@ impl DocumentStructure {
             headers: Vec::new(),
             clauses: Vec::new(),
             paragraphs: Vec::new(),
+            sections: SectionTree::default(),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Document, Object, Stream};
+
+    /// A PDF with one page per entry; `None` is a page without a text layer,
+    /// like a scanned annex.
+    fn generated_pdf(pages: &[Option<&[&str]>]) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut kids = Vec::new();
+        for lines in pages {
+            let mut operations = Vec::new();
+            if let Some(lines) = lines {
+                operations.push(Operation::new("BT", vec![]));
+                operations.push(Operation::new("Tf", vec!["F1".into(), 12.into()]));
+                operations.push(Operation::new("TL", vec![14.into()]));
+                operations.push(Operation::new("Td", vec![72.into(), 720.into()]));
+                for line in *lines {
+                    operations.push(Operation::new("Tj", vec![Object::string_literal(*line)]));
+                    operations.push(Operation::new("T*", vec![]));
+                }
+                operations.push(Operation::new("ET", vec![]));
+            }
+            let content = Content { operations };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
         }
+
+        doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Count" => kids.len() as i64,
+            "Kids" => kids,
+            "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }));
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut data = Vec::new();
+        doc.save_to(&mut data).unwrap();
+        data
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_extract_pdf_pages_flags_pages_without_text_layer() {
+        let pdf = generated_pdf(&[
+            Some(&["The borrower bears the exchange", "rate risk of the loan in CHF."]),
+            None,
+            Some(&["Annex 2: repayment schedule of the", "foreign currency mortgage."]),
+        ]);
+
+        let pages = TextProcessor::new().extract_pdf_pages(&pdf).unwrap();
+
+        assert_eq!(pages.len(), 3);
+        assert!(!pages[0].needs_ocr);
+        assert!(pages[0].text.contains("exchange"));
+        assert!(pages[1].needs_ocr);
+        assert!(pages[1].text.is_empty());
+        assert!(!pages[2].needs_ocr);
+        assert_eq!(pages.iter().map(|p| p.page_number).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_clean_pdf_page_text_rejoins_hyphenated_lines() {
+        let processor = TextProcessor::new();
+        let text = processor.clean_pdf_page_text("Az árfolyam-\nkockázatot az Adós viseli.\n\n  \nCHF-\nAlapú kölcsön");
+
+        assert_eq!(text, "Az árfolyamkockázatot az Adós viseli.\nCHF-\nAlapú kölcsön");
+    }
+
+    #[test]
+    fn test_clean_pdf_page_text_keeps_compound_hyphens() {
+        let processor = TextProcessor::new();
+        let text = processor.clean_pdf_page_text("A CHF-alapú kölcsön 2008-ban, a deviza-\nalapú\nCHF-\nalapú törlesztés\nelszámolás-\n\nkövetkező");
+
+        assert_eq!(text, "A CHF-alapú kölcsön 2008-ban, a devizaalapú\nCHF-alapú törlesztés\nelszámolás-\nkövetkező");
+    }
+}
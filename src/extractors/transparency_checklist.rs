@@ -0,0 +1,322 @@
+use unicode_segmentation::UnicodeSegmentation;
+use uuid::Uuid;
+use crate::models::{ChecklistItem, ElementVerdict, EvidenceSpan, ExchangeSpreadTerms, ExtractedClause, TransparencyChecklist, TransparencyElement};
+use super::transparency_classifier::{Speaker, TransparencyAssessment, TransparencyClassifier};
+
+/// What the consumer pays or owes
+const PAYMENT_CUES: &[&str] = &[
+    "törlesztő", "részlet", "tartozás", "instalment", "installment", "repayment", "monthly payment", "debt",
+    "rata", "raty", "zadłużen", "splátk", "dluh", "rată", "datori",
+];
+
+const CHANGE_CUES: &[&str] = &[
+    "emelked", "növeked", "nőhet", "megnő", "increase", "rise", "grow",
+    "wzrost", "wzrośn", "wzros", "zvýš", "nárůst", "creșt", "crește",
+];
+
+const SIMULATION_CUES: &[&str] = &[
+    "példa", "példá", "szemléltet", "szimuláci", "example", "illustrat", "simulat", "scenario",
+    "przykład", "symulac", "příklad", "modelov", "exemplu", "simular",
+];
+
+const UNLIMITED_CUES: &[&str] = &[
+    "korlátlan", "felső határ nélkül", "nincs felső", "nehezen viselhető",
+    "unlimited", "no upper limit", "without limit", "no limit", "difficult to bear",
+    "nieograniczon", "bez limitu", "neomezen", "nelimitat",
+];
+
+const CONVERSION_CUES: &[&str] = &[
+    "átszámít", "forintra", "konvert", "conversion", "converted", "przelicz", "přepočt", "conversi", "convert",
+];
+
+const RATE_CUES: &[&str] = &["árfolyam", "exchange rate", "rate", "kurs", "kurz", "curs"];
+
+fn contains_any(text: &str, cues: &[&str]) -> bool {
+    cues.iter().any(|cue| text.contains(cue))
+}
+
+/// A text the checklist reads: an extracted clause or a letter in the client's file.
+pub struct ChecklistSource<'a> {
+    document_id: Uuid,
+    clause: Option<&'a ExtractedClause>,
+    offset: usize, // where `text` starts in the document
+    text: &'a str,
+}
+
+impl<'a> ChecklistSource<'a> {
+    pub fn clause(clause: &'a ExtractedClause) -> Self {
+        Self {
+            document_id: clause.document_id,
+            clause: Some(clause),
+            offset: clause.start_position.unwrap_or(0).max(0) as usize,
+            text: &clause.clause_text,
+        }
+    }
+
+    pub fn correspondence(document_id: Uuid, text: &'a str) -> Self {
+        Self { document_id, clause: None, offset: 0, text }
+    }
+
+    fn span(&self, byte_start: usize, sentence: &str) -> EvidenceSpan {
+        let trimmed = sentence.trim();
+        let leading = sentence.len() - sentence.trim_start().len();
+        let start = self.offset + self.text[..byte_start + leading].chars().count();
+        EvidenceSpan {
+            document_id: self.document_id,
+            clause_id: self.clause.map(|c| c.id),
+            source: if self.clause.is_some() { "clause" } else { "correspondence" }.to_string(),
+            start,
+            end: start + trimmed.chars().count(),
+            text: trimmed.to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Findings {
+    met: Vec<EvidenceSpan>,
+    unmet: Vec<EvidenceSpan>,
+    declarations: Vec<EvidenceSpan>, // borrower's pre-printed acknowledgements
+    mentions: Vec<EvidenceSpan>, // on the subject, but no warning by the bank
+    undefined_terms: Vec<EvidenceSpan>, // spread clauses that name neither a rate nor its source
+    incomplete_terms: Vec<EvidenceSpan>, // spread clauses that leave part of the mechanism open
+    missing_terms: Vec<&'static str>,
+}
+
+/// Evaluates the CJEU transparency test for foreign currency terms element by
+/// element over a document's clauses and the client's correspondence.
+///
+/// Each sentence that addresses an element is read with the
+/// `TransparencyClassifier`: a warning by the bank supports the element, a
+/// statement that the consumer was not informed defeats it, and a borrower's
+/// pre-printed declaration alone leaves it open (C-186/16 Andriciuc). Other
+/// sentences on the subject, such as a client's complaint that the instalment
+/// doubled, are kept as evidence but decide nothing. An element nothing
+/// addresses is unknown, not unmet.
+pub struct TransparencyChecklistEngine {
+    classifier: TransparencyClassifier,
+}
+
+impl TransparencyChecklistEngine {
+    pub fn new() -> Self {
+        Self { classifier: TransparencyClassifier::new() }
+    }
+
+    pub fn evaluate(&self, document_id: Uuid, sources: &[ChecklistSource]) -> TransparencyChecklist {
+        let items = TransparencyElement::ALL.iter()
+            .map(|element| self.evaluate_element(*element, sources))
+            .collect();
+        TransparencyChecklist::new(document_id, items)
+    }
+
+    fn evaluate_element(&self, element: TransparencyElement, sources: &[ChecklistSource]) -> ChecklistItem {
+        let mut findings = Findings::default();
+
+        for source in sources {
+            for (start, sentence) in source.text.split_sentence_bound_indices() {
+                let lower = sentence.to_lowercase();
+                if !addresses(element, &lower) {
+                    continue;
+                }
+                let span = source.span(start, sentence);
+                let classification = self.classifier.classify(sentence);
+                match classification.assessment {
+                    TransparencyAssessment::Negated => findings.unmet.push(span),
+                    TransparencyAssessment::BoilerplateAcknowledgement => findings.declarations.push(span),
+                    TransparencyAssessment::AdequateWarning if classification.speaker != Speaker::Borrower => findings.met.push(span),
+                    TransparencyAssessment::AdequateWarning | TransparencyAssessment::Absent => findings.mentions.push(span),
+                }
+            }
+        }
+
+        if element == TransparencyElement::ConversionMechanism {
+            conversion_terms(sources, &mut findings);
+        }
+
+        let defeated = !findings.unmet.is_empty() || !findings.undefined_terms.is_empty();
+        let (verdict, explanation) = match (findings.met.is_empty(), !defeated) {
+            (false, false) => (ElementVerdict::Unknown, "The contract and the correspondence conflict on this element".to_string()),
+            (false, true) => (ElementVerdict::Met, format!("Warned of in {} passage(s) stated by the bank", findings.met.len())),
+            (true, false) if !findings.unmet.is_empty() => (ElementVerdict::Unmet, "The documents state that the consumer was not informed".to_string()),
+            (true, false) => (
+                ElementVerdict::Unmet,
+                "The exchange spread clauses name neither the rates applied at disbursement and repayment nor their source".to_string(),
+            ),
+            (true, true) if !findings.incomplete_terms.is_empty() => (
+                ElementVerdict::Unknown,
+                format!(
+                    "The exchange spread clauses do not name {}; it may be set out in the bank's notice or business rules",
+                    findings.missing_terms.join(" or "),
+                ),
+            ),
+            (true, true) if !findings.declarations.is_empty() => (
+                ElementVerdict::Unknown,
+                "Only a pre-printed borrower declaration; whether the information was actually given needs other evidence".to_string(),
+            ),
+            (true, true) if !findings.mentions.is_empty() => (
+                ElementVerdict::Unknown,
+                format!("Mentioned in {} passage(s), none of them a warning by the bank", findings.mentions.len()),
+            ),
+            (true, true) => (ElementVerdict::Unknown, "No passage in the contract or the correspondence addresses this element".to_string()),
+        };
+
+        let mut evidence = findings.met;
+        evidence.extend(findings.unmet);
+        evidence.extend(findings.undefined_terms);
+        evidence.extend(findings.incomplete_terms);
+        evidence.extend(findings.declarations);
+        evidence.extend(findings.mentions);
+
+        ChecklistItem { element, verdict, authority: element.authority().to_string(), explanation, evidence }
+    }
+}
+
+fn addresses(element: TransparencyElement, lower: &str) -> bool {
+    match element {
+        TransparencyElement::EconomicConsequences => contains_any(lower, PAYMENT_CUES) && contains_any(lower, CHANGE_CUES),
+        TransparencyElement::RateSimulation => contains_any(lower, SIMULATION_CUES) && lower.chars().any(|c| c.is_ascii_digit()),
+        TransparencyElement::UnlimitedRisk => contains_any(lower, UNLIMITED_CUES),
+        TransparencyElement::ConversionMechanism => contains_any(lower, CONVERSION_CUES) && contains_any(lower, RATE_CUES),
+    }
+}
+
+/// Spread terms that name the rate at disbursement and at repayment and where
+/// they come from set out the mechanism (Kásler, para. 75). Contracts often
+/// name the two rates in separate points, so the terms of all the document's
+/// spread clauses are read together; terms that leave part of it open decide
+/// nothing, since the rest may be in the bank's notice.
+fn conversion_terms(sources: &[ChecklistSource], findings: &mut Findings) {
+    let spread_clauses: Vec<(&ChecklistSource, &ExchangeSpreadTerms)> = sources.iter()
+        .filter_map(|s| s.clause.and_then(|c| c.exchange_spread.as_ref()).map(|terms| (s, terms)))
+        .collect();
+    let Some(terms) = ExchangeSpreadTerms::merged(spread_clauses.iter().map(|(_, terms)| *terms)) else {
+        return;
+    };
+    let spans = spread_clauses.iter().map(|(source, _)| source.span(0, source.text));
+
+    let missing: Vec<&'static str> = [
+        (terms.disbursement_rate.is_none(), "the rate at disbursement"),
+        (terms.repayment_rate.is_none(), "the rate at repayment"),
+        (terms.rate_sources.is_empty(), "the source of the rates"),
+    ].into_iter().filter(|(missing, _)| *missing).map(|(_, label)| label).collect();
+
+    match missing.len() {
+        0 => findings.met.extend(spans),
+        3 => findings.undefined_terms.extend(spans),
+        _ => {
+            findings.incomplete_terms.extend(spans);
+            findings.missing_terms = missing;
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExchangeSpreadTerms;
+
+    fn clause(category: &str, text: &str, start: i32) -> ExtractedClause {
+        let mut clause = ExtractedClause::new(Uuid::nil(), category.to_string(), text.to_string(), "hu".to_string(), 0.8);
+        clause.start_position = Some(start);
+        clause.end_position = Some(start + text.chars().count() as i32);
+        clause
+    }
+
+    fn verdict(checklist: &TransparencyChecklist, element: TransparencyElement) -> &ChecklistItem {
+        checklist.items.iter().find(|i| i.element == element).unwrap()
+    }
+
+    #[test]
+    fn test_elements_cite_spans_and_paragraphs() {
+        let warning = clause(
+            "fx_risk",
+            "Szerződés. A Bank tájékoztatja az Adóst, hogy a CHF árfolyam emelkedésével a törlesztőrészlet korlátlanul emelkedhet.",
+            100,
+        );
+        let mut spread = clause("exchange_spread", "A folyósítás a Bank vételi árfolyamán történik.", 400);
+        spread.exchange_spread = Some(ExchangeSpreadTerms {
+            disbursement_rate: Some("buying".to_string()),
+            ..Default::default()
+        });
+        let letter = "Tisztelt Bank! A szerződéskötéskor nem tájékoztattak példával, hogy 20%-os árfolyamváltozás mit jelent.";
+
+        let sources = [
+            ChecklistSource::clause(&warning),
+            ChecklistSource::clause(&spread),
+            ChecklistSource::correspondence(Uuid::nil(), letter),
+        ];
+        let checklist = TransparencyChecklistEngine::new().evaluate(Uuid::nil(), &sources);
+
+        let consequences = verdict(&checklist, TransparencyElement::EconomicConsequences);
+        assert_eq!(consequences.verdict, ElementVerdict::Met);
+        assert_eq!(consequences.evidence[0].start, 111);
+        assert!(consequences.evidence[0].text.starts_with("A Bank tájékoztatja"));
+        assert!(consequences.authority.contains("Andriciuc, para. 45"));
+
+        assert_eq!(verdict(&checklist, TransparencyElement::UnlimitedRisk).verdict, ElementVerdict::Met);
+
+        let simulation = verdict(&checklist, TransparencyElement::RateSimulation);
+        assert_eq!(simulation.verdict, ElementVerdict::Unmet);
+        assert_eq!(simulation.evidence[0].source, "correspondence");
+
+        let mechanism = verdict(&checklist, TransparencyElement::ConversionMechanism);
+        assert_eq!(mechanism.verdict, ElementVerdict::Unknown);
+        assert!(mechanism.explanation.contains("the rate at repayment or the source of the rates"));
+        assert_eq!(mechanism.evidence[0].clause_id, Some(spread.id));
+
+        assert_eq!(checklist.overall, ElementVerdict::Unmet);
+    }
+
+    #[test]
+    fn test_conversion_rates_in_separate_points_are_read_together() {
+        let mut disbursement = clause("exchange_spread", "3.1. A kölcsön folyósítása a Bank deviza vételi árfolyamán történik.", 200);
+        disbursement.exchange_spread = Some(ExchangeSpreadTerms {
+            disbursement_rate: Some("buying".to_string()),
+            ..Default::default()
+        });
+        let mut repayment = clause(
+            "exchange_spread",
+            "6.1. A törlesztőrészletek a Bank Hirdetményében közzétett deviza eladási árfolyamon kerülnek átszámításra.",
+            900,
+        );
+        repayment.exchange_spread = Some(ExchangeSpreadTerms {
+            repayment_rate: Some("selling".to_string()),
+            rate_sources: vec!["bank's own quotation".to_string()],
+            ..Default::default()
+        });
+
+        let sources = [ChecklistSource::clause(&disbursement), ChecklistSource::clause(&repayment)];
+        let checklist = TransparencyChecklistEngine::new().evaluate(Uuid::nil(), &sources);
+
+        let mechanism = verdict(&checklist, TransparencyElement::ConversionMechanism);
+        assert_eq!(mechanism.verdict, ElementVerdict::Met);
+        assert!(mechanism.evidence.iter().any(|e| e.clause_id == Some(disbursement.id)));
+        assert!(mechanism.evidence.iter().any(|e| e.clause_id == Some(repayment.id)));
+        assert!(!mechanism.explanation.contains("not informed"));
+    }
+
+    #[test]
+    fn test_client_complaint_is_not_a_bank_warning() {
+        let letter = "A havi törlesztőrészletem 2010 óta a duplájára emelkedett, és erről a szerződéskötéskor senki nem beszélt.";
+        let checklist = TransparencyChecklistEngine::new()
+            .evaluate(Uuid::nil(), &[ChecklistSource::correspondence(Uuid::nil(), letter)]);
+
+        let consequences = verdict(&checklist, TransparencyElement::EconomicConsequences);
+        assert_eq!(consequences.verdict, ElementVerdict::Unknown);
+        assert_eq!(consequences.evidence.len(), 1);
+        assert_eq!(consequences.evidence[0].source, "correspondence");
+        assert_ne!(checklist.overall, ElementVerdict::Met);
+    }
+
+    #[test]
+    fn test_declarations_and_silence_stay_unknown() {
+        let declaration = clause("transparency", "Az Adós kijelenti, hogy az árfolyamkockázat korlátlan voltát tudomásul veszi.", 0);
+        let checklist = TransparencyChecklistEngine::new().evaluate(Uuid::nil(), &[ChecklistSource::clause(&declaration)]);
+
+        let unlimited = verdict(&checklist, TransparencyElement::UnlimitedRisk);
+        assert_eq!(unlimited.verdict, ElementVerdict::Unknown);
+        assert_eq!(unlimited.evidence.len(), 1);
+        assert!(verdict(&checklist, TransparencyElement::RateSimulation).evidence.is_empty());
+        assert_eq!(checklist.overall, ElementVerdict::Unknown);
+    }
+}
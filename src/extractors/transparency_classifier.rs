@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Words that introduce or report informing the consumer (matched as word stems)
+const INFORMATION_STEMS: &[&str] = &[
+    "tájékoztat", "figyelmeztet", "felvilágosít", "ismertet", "felhív",
+    "inform", "warn", "disclos", "explain", "advis",
+    "poučen", "poučil", "upozorn", "seznámen",
+    "poinformowa", "pouczon", "pouczy", "ostrzeż", "ostrzega",
+    "avertiz",
+];
+
+/// Negation cues that scope over the following words of the same clause
+const NEGATION_CUES: &[&str] = &[
+    "nem", "sem", "nincs", "nincsen", "soha",
+    "not", "no", "never", "without",
+    "nebyl", "nebyla", "nebyli", "bez",
+    "nie", "nu", "fără",
+];
+
+/// Negation cues that follow the word they negate ("tájékoztatás nélkül")
+const POSTPOSED_NEGATION_CUES: &[&str] = &["nélkül", "hiányában"];
+
+/// How many words a negation cue reaches forward
+const NEGATION_SCOPE: usize = 4;
+
+/// Standard borrower declarations acknowledging risk or receipt of information
+const ACKNOWLEDGEMENT_CUES: &[&str] = &[
+    "tudomásul vesz", "tudomásul veszi", "tudomásul veszem", "tudomásul vette", "tudomással bír",
+    "megértette", "megértettem", "kijelenti", "kijelentem", "nyilatkozik", "nyilatkozom", "elismeri",
+    "acknowledge", "has understood", "have understood", "i understand", "declares that", "confirms that",
+    "bere na vědomí", "beru na vědomí", "prohlašuje", "prohlašuji",
+    "przyjmuje do wiadomości", "przyjmuję do wiadomości", "oświadcza",
+    "declară", "am luat la cunoștință", "ia la cunoștință",
+];
+
+const BORROWER_CUES: &[&str] = &[
+    "adós", "adóstárs", "kölcsönfelvevő", "hitelfelvevő", "alulírott",
+    "borrower", "customer", "consumer",
+    "dlužník", "klient", "kredytobiorca", "pożyczkobiorca", "konsument",
+    "împrumutat", "consumator",
+];
+
+const BANK_CUES: &[&str] = &[
+    "bank", "hitelező", "pénzintézet", "lender", "creditor",
+    "věřitel", "kredytodawca", "pożyczkodawca", "banca", "finanszírozó",
+];
+
+/// Descriptions of what the risk means for the borrower: rising instalments,
+/// unlimited exposure, worked examples.
+const CONSEQUENCE_CUES: &[&str] = &[
+    "emelked", "növeked", "nőhet", "jelentős", "korlátlan", "többszörös", "példa", "szemléltet",
+    "increase", "rise", "significant", "unlimited", "example", "illustrat", "scenario",
+    "zvýš", "nárůst", "příklad",
+    "wzrost", "wzrosn", "przykład", "nieograniczon",
+    "creșt", "exemplu",
+];
+
+/// How a transparency clause informs the consumer about the risk, in the terms
+/// of C-186/16 Andriciuc: a warning by the bank, a pre-printed declaration by
+/// the borrower, nothing, or an explicit statement that no information was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransparencyAssessment {
+    AdequateWarning,
+    BoilerplateAcknowledgement,
+    Absent,
+    Negated,
+}
+
+impl TransparencyAssessment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AdequateWarning => "adequate_warning",
+            Self::BoilerplateAcknowledgement => "boilerplate_acknowledgement",
+            Self::Absent => "absent",
+            Self::Negated => "negated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Speaker {
+    Bank,
+    Borrower,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransparencyClassification {
+    pub assessment: TransparencyAssessment,
+    pub speaker: Speaker,
+    pub describes_consequences: bool,
+    pub evidence: Option<String>, // the sentence the assessment rests on
+}
+
+struct SentenceReading<'a> {
+    text: &'a str,
+    speaker: Speaker,
+    informs: bool,
+    negated: bool,
+    acknowledges: bool,
+}
+
+/// Labels transparency clauses by reading each sentence for who speaks (the
+/// bank or the borrower in a declaration), whether it mentions informing the
+/// consumer, and whether that mention sits in the scope of a negation.
+///
+/// A negated mention ("a Bank nem tájékoztatta") outweighs everything else; a
+/// bank warning outweighs borrower declarations, since a declaration such as
+/// "tudomásul veszem az árfolyamkockázatot" only shows the consumer signed
+/// boilerplate, not that they were informed. Informing is only a warning when
+/// the clause also spells out the economic consequences; "a Bank tájékoztatja
+/// az Adóst a díjakról" is a notice, not a warning.
+pub struct TransparencyClassifier;
+
+impl TransparencyClassifier {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn classify(&self, text: &str) -> TransparencyClassification {
+        let readings: Vec<SentenceReading> = text.split_sentence_bounds()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| self.read_sentence(s))
+            .collect();
+        let describes_consequences = contains_any(&text.to_lowercase(), CONSEQUENCE_CUES);
+
+        let decide = |assessment, reading: &SentenceReading| TransparencyClassification {
+            assessment,
+            speaker: reading.speaker,
+            describes_consequences,
+            evidence: Some(reading.text.trim().to_string()),
+        };
+
+        if let Some(r) = readings.iter().find(|r| r.negated) {
+            return decide(TransparencyAssessment::Negated, r);
+        }
+        if let Some(r) = readings.iter().find(|r| r.informs && !r.acknowledges && r.speaker != Speaker::Borrower)
+            .filter(|_| describes_consequences)
+        {
+            return decide(TransparencyAssessment::AdequateWarning, r);
+        }
+        if let Some(r) = readings.iter().find(|r| r.acknowledges || (r.informs && r.speaker == Speaker::Borrower)) {
+            return decide(TransparencyAssessment::BoilerplateAcknowledgement, r);
+        }
+
+        TransparencyClassification {
+            assessment: TransparencyAssessment::Absent,
+            speaker: readings.first().map_or(Speaker::Unknown, |r| r.speaker),
+            describes_consequences,
+            evidence: None,
+        }
+    }
+
+    fn read_sentence<'a>(&self, sentence: &'a str) -> SentenceReading<'a> {
+        let lower = sentence.to_lowercase();
+        let mut informs = false;
+        let mut negated = false;
+
+        // Negation does not reach across clause punctuation
+        for segment in lower.split([',', ';', ':']) {
+            let words: Vec<&str> = segment.unicode_words().collect();
+            for (i, word) in words.iter().enumerate() {
+                if !is_information_word(word) {
+                    continue;
+                }
+                informs = true;
+
+                let before = &words[i.saturating_sub(NEGATION_SCOPE)..i];
+                let after = &words[i + 1..(i + 3).min(words.len())];
+                if has_negative_prefix(word)
+                    || before.iter().any(|w| is_negation(w))
+                    || after.iter().any(|w| POSTPOSED_NEGATION_CUES.contains(w))
+                {
+                    negated = true;
+                }
+            }
+        }
+
+        let acknowledges = contains_any(&lower, ACKNOWLEDGEMENT_CUES);
+        // A declaration is the borrower's unless the sentence opens with the bank
+        let speaker = match first_party(&lower) {
+            Speaker::Bank => Speaker::Bank,
+            _ if acknowledges => Speaker::Borrower,
+            party => party,
+        };
+
+        SentenceReading { text: sentence, speaker, informs, negated, acknowledges }
+    }
+}
+
+fn contains_any(text: &str, cues: &[&str]) -> bool {
+    cues.iter().any(|cue| text.contains(cue))
+}
+
+fn is_information_word(word: &str) -> bool {
+    INFORMATION_STEMS.iter().any(|stem| word.contains(stem))
+}
+
+fn is_negation(word: &str) -> bool {
+    NEGATION_CUES.contains(&word) || word.ends_with("n't")
+}
+
+/// Czech and Polish negate the verb itself: "neinformoval", "nieostrzeżony".
+fn has_negative_prefix(word: &str) -> bool {
+    ["nie", "ne"].iter().any(|prefix| {
+        word.strip_prefix(prefix).is_some_and(|rest| INFORMATION_STEMS.iter().any(|stem| rest.starts_with(stem)))
+    })
+}
+
+/// The party named first is taken as the subject of the sentence.
+fn first_party(lower: &str) -> Speaker {
+    let position = |cues: &[&str]| cues.iter().filter_map(|cue| find_word(lower, cue)).min();
+    match (position(BANK_CUES), position(BORROWER_CUES)) {
+        (Some(bank), Some(borrower)) if bank < borrower => Speaker::Bank,
+        (Some(_), Some(_)) | (None, Some(_)) => Speaker::Borrower,
+        (Some(_), None) => Speaker::Bank,
+        (None, None) => Speaker::Unknown,
+    }
+}
+
+/// Byte position of `cue` at the start of a word.
+fn find_word(text: &str, cue: &str) -> Option<usize> {
+    text.match_indices(cue)
+        .map(|(i, _)| i)
+        .find(|&i| text[..i].chars().next_back().is_none_or(|c| !c.is_alphanumeric()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assessments() {
+        let classifier = TransparencyClassifier::new();
+        let assess = |text: &str| classifier.classify(text).assessment;
+
+        assert_eq!(
+            assess("A Bank felhívja az Adós figyelmét, hogy az árfolyam változásával a törlesztőrészlet jelentősen emelkedhet."),
+            TransparencyAssessment::AdequateWarning
+        );
+        assert_eq!(
+            assess("Az Adós kijelenti, hogy a Bank az árfolyamkockázatról tájékoztatta, és azt tudomásul veszi."),
+            TransparencyAssessment::BoilerplateAcknowledgement
+        );
+        assert_eq!(assess("Tudomásul veszem az árfolyamkockázatot."), TransparencyAssessment::BoilerplateAcknowledgement);
+        assert_eq!(
+            assess("A Bank az Adóst az árfolyamkockázatról nem tájékoztatta."),
+            TransparencyAssessment::Negated
+        );
+        assert_eq!(assess("The borrower was not informed of the exchange rate risk."), TransparencyAssessment::Negated);
+        assert_eq!(assess("Kredytobiorca nie został poinformowany o ryzyku."), TransparencyAssessment::Negated);
+        assert_eq!(assess("Az árfolyamkockázatot az Adós viseli."), TransparencyAssessment::Absent);
+    }
+
+    #[test]
+    fn test_notice_without_consequences_is_not_a_warning() {
+        let classifier = TransparencyClassifier::new();
+        let assess = |text: &str| classifier.classify(text).assessment;
+
+        assert_eq!(assess("A Bank tájékoztatja az Adóst a szerződés díjairól."), TransparencyAssessment::Absent);
+        assert_eq!(assess("The Bank informs the Borrower of the applicable fees."), TransparencyAssessment::Absent);
+        assert_eq!(
+            assess("The Bank informs the Borrower that the instalments may increase significantly if the forint weakens."),
+            TransparencyAssessment::AdequateWarning
+        );
+    }
+
+    #[test]
+    fn test_negation_does_not_cross_clause_boundaries() {
+        let result = TransparencyClassifier::new()
+            .classify("A Bank nem vállal felelősséget az árfolyamért, de tájékoztatja az Adóst a kockázat jelentős mértékéről.");
+        assert_eq!(result.assessment, TransparencyAssessment::AdequateWarning);
+        assert_eq!(result.speaker, Speaker::Bank);
+        assert!(result.describes_consequences);
+    }
+}
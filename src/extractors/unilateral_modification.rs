@@ -0,0 +1,207 @@
+use regex::Regex;
+use crate::models::{DhPrinciple, PrincipleCheck, UnilateralModificationAnalysis};
+
+/// Catch-all wording that leaves the bank room to decide what the clause means
+const VAGUE_TERMS: &[&str] = &[
+    "belátása szerint", "saját döntés", "saját megítélés", "bármely", "stb.", "egyéb ok", "egyéb körülmény",
+    "at its discretion", "at its sole", "any other", "other circumstances", "etc.",
+];
+
+/// Markers that a list of reasons is only illustrative
+const OPEN_LIST_TERMS: &[&str] = &[
+    "különösen", "többek között", "például", "így különösen",
+    "in particular", "including", "such as", "among others", "for example",
+];
+
+/// External, verifiable benchmarks
+const OBJECTIVE_TERMS: &[&str] = &[
+    "jegybanki alapkamat", "alapkamat", "bubor", "libor", "euribor", "refinanszírozási kamat", "fogyasztói árindex",
+    "ksh", "jogszabály", "central bank base rate", "reference rate", "consumer price index", "statutory",
+];
+
+/// Reasons left to the bank's own assessment
+const SUBJECTIVE_TERMS: &[&str] = &[
+    "üzletpolitik", "kockázati megítélés", "ügyfél-minősítés", "piaci körülmények", "forrásköltség",
+    "business policy", "risk assessment", "market conditions", "funding costs",
+];
+
+const PROPORTIONALITY_TERMS: &[&str] = &[
+    "arányos", "mértékével", "mértékében", "mértékéig", "legfeljebb",
+    "in proportion", "proportionate", "to the extent", "not exceeding", "at most",
+];
+
+const NOTICE_TERMS: &[&str] = &["értesít", "hirdetmény", "előzetesen", "közzétesz", "notify", "notice", "in advance", "publish"];
+
+const TERMINATION_TERMS: &[&str] = &["felmond", "terminat"];
+
+/// Tests clauses that let the bank unilaterally change interest, costs or
+/// fees against the principles of Act XXXVIII of 2014 (the first DH act).
+/// Each principle is checked on the clause wording alone; a principle met only
+/// in another part of the contract will show as failed and needs review.
+pub struct UnilateralModificationAnalyzer {
+    modification: Regex,
+    enumerated_reason: Regex,
+    price_decrease: Regex,
+    consumer_party: Regex,
+    bank_party: Regex,
+}
+
+impl UnilateralModificationAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            modification: Regex::new(
+                r"(?is)(?:egyoldalú\w*.{0,80}(?:módosít|emel|megváltoztat)|jogosult.{0,80}(?:kamat|díj|költség|jutalék).{0,80}(?:módosít|emel|megváltoztat)|unilateral\w*.{0,80}(?:amend|modif|chang|increas)|(?:right|entitled)\s+to\s+(?:amend|modify|change|increase).{0,60}(?:interest|fee|charge|cost))"
+            ).unwrap(),
+            enumerated_reason: Regex::new(r"(?m)(?:^|\s)(?:[a-z]\)|\(?[ivx]+\)|\d+\.\d*\.?\s)").unwrap(),
+            // The decrease has to act on the price itself, not on the collateral or income
+            price_decrease: Regex::new(
+                r"(?i)(?:kamat|díj|költség|jutalék|törlesztő)\w*(?:\s+\w+){0,2}?\s*(?:csökken|mérsékl)|(?:csökkent|mérsékel|mérsékl)\w*(?:\s+\w+){0,2}\s+(?:kamat|díj|költség|jutalék|törlesztő)|(?:decreas|reduc|lower)\w*(?:\s+\w+){0,2}\s+(?:interest|fees?|charges?|costs?|rates?)\b|(?:interest|fees?|charges?|costs?|rates?)(?:\s+\w+){0,2}\s+(?:decreas|reduc|lower)"
+            ).unwrap(),
+            // Nominative forms only: "az Adóssal kötött szerződést" names the
+            // consumer without making them the one who terminates
+            consumer_party: Regex::new(r"\b(?:adós|adóstárs|ügyfél|fogyasztó|kölcsönvevő|aki|borrower|customer|consumer)\b").unwrap(),
+            bank_party: Regex::new(r"\b(?:bank|hitelező|lender)\b").unwrap(),
+        }
+    }
+
+    pub fn is_unilateral_modification(&self, text: &str) -> bool {
+        self.modification.is_match(text)
+    }
+
+    /// `None` when the text is not a unilateral modification clause.
+    pub fn analyze(&self, text: &str) -> Option<UnilateralModificationAnalysis> {
+        if !self.is_unilateral_modification(text) {
+            return None;
+        }
+
+        let lower = text.to_lowercase();
+        let checks = DhPrinciple::ALL.iter()
+            .map(|&principle| {
+                let (satisfied, finding) = self.check(principle, &lower);
+                PrincipleCheck { principle, satisfied, finding: finding.to_string() }
+            })
+            .collect();
+
+        Some(UnilateralModificationAnalysis { checks })
+    }
+
+    /// A termination verb whose nearest preceding party in the sentence is the
+    /// consumer. "A Bank jogosult a szerződést felmondani" is the bank's right.
+    fn consumer_may_terminate(&self, lower: &str) -> bool {
+        TERMINATION_TERMS.iter()
+            .flat_map(|term| lower.match_indices(term))
+            .any(|(at, _)| {
+                let start = lower[..at].rfind(['.', ';', '\n']).map_or(0, |i| i + 1);
+                let sentence = &lower[start..at];
+                let consumer = self.consumer_party.find_iter(sentence).last().map(|m| m.start());
+                let bank = self.bank_party.find_iter(sentence).last().map(|m| m.start());
+                consumer > bank
+            })
+    }
+
+    fn check(&self, principle: DhPrinciple, lower: &str) -> (bool, &'static str) {
+        let has = |terms: &[&str]| terms.iter().any(|t| lower.contains(t));
+        let listed_reasons = self.enumerated_reason.find_iter(lower).count() >= 2;
+
+        match principle {
+            DhPrinciple::ClearWording if has(VAGUE_TERMS) =>
+                (false, "Open-ended wording leaves the scope of the modification to the bank"),
+            DhPrinciple::ClearWording =>
+                (true, "No open-ended wording found"),
+            DhPrinciple::Transparency if has(NOTICE_TERMS) =>
+                (true, "The clause provides for notice of the modification"),
+            DhPrinciple::Transparency =>
+                (false, "No advance notice or publication of the modification is provided"),
+            DhPrinciple::ExhaustiveReasons if listed_reasons && !has(OPEN_LIST_TERMS) =>
+                (true, "The reasons for modification are listed exhaustively"),
+            DhPrinciple::ExhaustiveReasons if listed_reasons =>
+                (false, "The list of reasons is only illustrative"),
+            DhPrinciple::ExhaustiveReasons =>
+                (false, "The reasons for modification are not listed"),
+            DhPrinciple::Objectivity if has(SUBJECTIVE_TERMS) =>
+                (false, "Modification depends on the bank's own assessment or business policy"),
+            DhPrinciple::Objectivity if has(OBJECTIVE_TERMS) =>
+                (true, "Modification is tied to external, verifiable circumstances"),
+            DhPrinciple::Objectivity =>
+                (false, "No objective, verifiable circumstance is named"),
+            DhPrinciple::Proportionality if has(PROPORTIONALITY_TERMS) =>
+                (true, "The extent of the modification is limited to the change in circumstances"),
+            DhPrinciple::Proportionality =>
+                (false, "The extent of the modification is not tied to the change in circumstances"),
+            DhPrinciple::Symmetry if self.price_decrease.is_match(lower) =>
+                (true, "Favourable changes must be passed on to the consumer"),
+            DhPrinciple::Symmetry =>
+                (false, "Only increases are provided for; decreases are not passed on"),
+            DhPrinciple::TerminationRight if self.consumer_may_terminate(lower) =>
+                (true, "The consumer may terminate the contract in response"),
+            DhPrinciple::TerminationRight =>
+                (false, "No termination right is granted to the consumer"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_business_policy_clause_fails_most_principles() {
+        let text = "A Bank jogosult a kamatot egyoldalúan módosítani, különösen üzletpolitikai okokból \
+            vagy a piaci körülmények változása esetén.";
+
+        let analysis = UnilateralModificationAnalyzer::new().analyze(text).unwrap();
+        let failed: Vec<DhPrinciple> = analysis.failed().map(|c| c.principle).collect();
+
+        assert!(failed.contains(&DhPrinciple::Objectivity));
+        assert!(failed.contains(&DhPrinciple::ExhaustiveReasons));
+        assert!(failed.contains(&DhPrinciple::Symmetry));
+        assert!(failed.contains(&DhPrinciple::TerminationRight));
+        assert!(!analysis.is_compliant());
+    }
+
+    #[test]
+    fn test_compliant_clause() {
+        let text = "A Bank a kamatot egyoldalúan kizárólag az alábbi okokból módosíthatja:\n\
+            a) a jegybanki alapkamat változása,\n\
+            b) a BUBOR változása.\n\
+            A módosítás mértéke a változás mértékével arányos; a feltételek javulása esetén a Bank a kamatot csökkenti. \
+            A Bank a módosításról 30 nappal előzetesen értesíti az Adóst, aki a szerződést díjmentesen felmondhatja.";
+
+        let analysis = UnilateralModificationAnalyzer::new().analyze(text).unwrap();
+        assert!(analysis.is_compliant(), "{:?}", analysis.failed().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_bank_termination_and_collateral_decrease_do_not_count() {
+        let text = "A Bank jogosult a kamatot egyoldalúan emelni az alábbi okokból:\n\
+            a) a fedezet értékének csökkenése,\n\
+            b) egyéb jogszabályi változás.\n\
+            Ha az Adós a módosítást nem fogadja el, a Bank jogosult a szerződést felmondani.";
+
+        let analysis = UnilateralModificationAnalyzer::new().analyze(text).unwrap();
+        let failed: Vec<DhPrinciple> = analysis.failed().map(|c| c.principle).collect();
+
+        assert!(failed.contains(&DhPrinciple::Symmetry));
+        assert!(failed.contains(&DhPrinciple::TerminationRight));
+        // "egyéb jogszabályi változás" is a listed reason, not an open list
+        assert!(!failed.contains(&DhPrinciple::ExhaustiveReasons));
+    }
+
+    #[test]
+    fn test_english_consumer_termination_and_rate_decrease() {
+        let text = "The Bank is entitled to unilaterally increase the interest rate if the reference rate rises. \
+            The Bank shall reduce the interest rate if the reference rate falls. \
+            The Borrower may terminate the agreement free of charge.";
+
+        let analysis = UnilateralModificationAnalyzer::new().analyze(text).unwrap();
+        let failed: Vec<DhPrinciple> = analysis.failed().map(|c| c.principle).collect();
+
+        assert!(!failed.contains(&DhPrinciple::Symmetry));
+        assert!(!failed.contains(&DhPrinciple::TerminationRight));
+    }
+
+    #[test]
+    fn test_other_clauses_are_not_analyzed() {
+        assert!(UnilateralModificationAnalyzer::new().analyze("A kamat mértéke évi 4,5%.").is_none());
+    }
+}
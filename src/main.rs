@@ -6,7 +6,7 @@ use log::info;
 use sqlx::SqlitePool;
 use std::env;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 
 mod api;
 mod db;
@@ -14,13 +14,18 @@ mod extractors;
 mod matching;
 mod models;
 mod ai;
+mod translation;
+mod damages;
+mod rates;
 
 use api::{
-    documents::{upload_document, get_documents, get_document},
+    documents::{upload_document, get_documents, get_document, import_ocr_output, get_document_pages, get_document_payments},
     cases::{get_cases, get_case, search_cases},
-    research::{extract_clauses, match_precedents, generate_draft, get_research_sheet},
+    research::{extract_clauses, translate_clauses, transparency_checklist, match_precedents, generate_draft, get_research_sheet},
     health::health_check,
-    case_analysis::{SharedAnalysis, analyze_case, get_analysis_status, generate_documents, apply_user_override, get_current_analysis},
+    case_analysis::{SharedAnalysis, analyze_case, get_analysis_status, generate_documents, apply_user_override, get_current_analysis, reconstruct_schedules, counterfactual_damages, compare_remedies},
+    clause_patterns::{SharedClauseExtractor, load_clause_extractor, get_clause_patterns, get_clause_pattern, create_clause_pattern, update_clause_pattern, delete_clause_pattern, reload_clause_patterns, evaluate_clause_patterns},
+    rates::{get_rate_series, lookup_rate, import_rates},
 };
 
 #[actix_web::main]
@@ -54,6 +59,14 @@ async fn main() -> std::io::Result<()> {
         Err(e) => info!("Precedent data already exists or seeding failed: {}", e),
     }
 
+    match db::seed_clause_patterns(&pool).await {
+        Ok(inserted) => info!("Seeded {} default clause patterns", inserted),
+        Err(e) => info!("Clause pattern seeding failed: {}", e),
+    }
+
+    // Clause extractor built from the active patterns, reloaded when they change
+    let clause_extractor: SharedClauseExtractor = Arc::new(RwLock::new(load_clause_extractor(&pool).await));
+
     // Initialize shared analysis state for AI features
     let shared_analysis: SharedAnalysis = Arc::new(Mutex::new(None));
 
@@ -72,6 +85,7 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(shared_analysis.clone()))
+            .app_data(web::Data::new(clause_extractor.clone()))
             .wrap(cors)
             .wrap(Logger::default())
             // Health check
@@ -83,20 +97,40 @@ async fn main() -> std::io::Result<()> {
             .route("/api/analysis", web::get().to(get_current_analysis))
             .route("/api/override", web::post().to(apply_user_override))
             .route("/api/generate-documents", web::post().to(generate_documents))
+            .route("/api/analysis/schedules", web::post().to(reconstruct_schedules))
+            .route("/api/analysis/counterfactual", web::post().to(counterfactual_damages))
+            .route("/api/analysis/remedies", web::post().to(compare_remedies))
             
             // Document management (legacy)
             .route("/api/documents", web::post().to(upload_document))
             .route("/api/documents", web::get().to(get_documents))
+            .route("/api/documents/import-ocr", web::post().to(import_ocr_output))
             .route("/api/documents/{id}", web::get().to(get_document))
+            .route("/api/documents/{id}/pages", web::get().to(get_document_pages))
+            .route("/api/documents/{id}/payments", web::get().to(get_document_payments))
             // Legal cases and precedents (legacy)
             .route("/api/cases", web::get().to(get_cases))
             .route("/api/cases/{id}", web::get().to(get_case))
             .route("/api/cases/search", web::post().to(search_cases))
             // Research workflow (legacy)
             .route("/api/research/extract-clauses", web::post().to(extract_clauses))
+            .route("/api/research/translate-clauses", web::post().to(translate_clauses))
+            .route("/api/research/transparency-checklist", web::post().to(transparency_checklist))
             .route("/api/research/match-precedents", web::post().to(match_precedents))
             .route("/api/research/generate-draft", web::post().to(generate_draft))
             .route("/api/research/sheet/{document_id}", web::get().to(get_research_sheet))
+            // Clause pattern management
+            .route("/api/clause-patterns", web::get().to(get_clause_patterns))
+            .route("/api/clause-patterns", web::post().to(create_clause_pattern))
+            .route("/api/clause-patterns/reload", web::post().to(reload_clause_patterns))
+            .route("/api/clause-patterns/evaluation", web::get().to(evaluate_clause_patterns))
+            .route("/api/clause-patterns/{id}", web::get().to(get_clause_pattern))
+            .route("/api/clause-patterns/{id}", web::put().to(update_clause_pattern))
+            .route("/api/clause-patterns/{id}", web::delete().to(delete_clause_pattern))
+            // Historical exchange and base rates
+            .route("/api/rates", web::get().to(get_rate_series))
+            .route("/api/rates/lookup", web::get().to(lookup_rate))
+            .route("/api/rates/import", web::post().to(import_rates))
             // Static files and UI
             .service(fs::Files::new("/static", "./static/").index_file("index.html"))
             .route("/", web::get().to(|| async { 
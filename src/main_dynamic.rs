@@ -11,6 +11,9 @@ mod models;
 mod db;
 mod extractors;
 mod matching;
+mod translation;
+mod damages;
+mod rates;
 
 use api::case_analysis::{
     SharedAnalysis, analyze_case, get_analysis_status, generate_documents, 
@@ -157,6 +157,13 @@ impl PrecedentMatcher {
                     similarity += 0.9;
                 }
             },
+            "exchange_spread" => {
+                if case.case_number == "C-26/13" { // Kásler - buying/selling rate spread
+                    similarity += 0.9;
+                } else if case.is_foreign_currency_case() {
+                    similarity += 0.5;
+                }
+            },
             "interest_rate" => {
                 if case.key_ruling.to_lowercase().contains("interest") {
                     similarity += 0.7;
@@ -220,6 +227,10 @@ impl PrecedentMatcher {
         let clause_type_explanation = match clause.clause_type.as_str() {
             "fx_risk" => "foreign currency risk allocation",
             "transparency" => "information disclosure requirements", 
+            "exchange_spread" => "exchange rate spread",
+            "early_repayment" => "early repayment fee",
+            "insurance_tie_in" => "tied insurance",
+            "collateral_acceleration" => "collateral-based acceleration",
             "interest_rate" => "interest rate modification terms",
             "penalty" => "penalty and fee provisions",
             _ => "contractual terms",
@@ -3,6 +3,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+/// Pattern severities, least to most serious. The API rejects any other value
+/// and the extractor maps each one to a base confidence and a risk-level floor.
+pub const PATTERN_SEVERITIES: [&str; 4] = ["informational", "medium", "warning", "critical"];
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ClausePattern {
     pub id: Uuid,
@@ -10,8 +14,8 @@ pub struct ClausePattern {
     pub pattern_type: String, // regex, keyword, semantic
     pub pattern_text: String,
     pub language: String,
-    pub clause_category: String, // fx_risk, interest_rate, penalty, etc.
-    pub severity: String, // informational, warning, critical
+    pub clause_category: String, // fx_risk, exchange_spread, interest_rate, penalty, early_repayment, etc.
+    pub severity: String, // one of PATTERN_SEVERITIES
     pub description: String,
     pub legal_basis: Option<String>,
     pub is_active: bool,
@@ -49,6 +53,8 @@ pub struct ClauseAnalysis {
     pub eu_compliance_issues: Vec<String>,
     pub hungarian_law_issues: Vec<String>,
     pub suggested_challenges: Vec<String>,
+    #[serde(default)]
+    pub breakdown: crate::models::ClauseScores, // factors behind the three scores
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -68,75 +74,353 @@ pub struct PrecedentMatch {
     pub applicability_assessment: String,
 }
 
+/// Terms of an exchange-rate spread clause: which of the bank's rates applies
+/// at disbursement and at repayment, and any spread or rate source it names.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeSpreadTerms {
+    pub disbursement_rate: Option<String>, // buying, selling, middle
+    pub repayment_rate: Option<String>,
+    pub spread_percent: Option<f64>,
+    pub rate_sources: Vec<String>, // e.g. "MNB official rate", "bank's own quotation"
+}
+
+impl ExchangeSpreadTerms {
+    /// Combines the terms of all spread clauses of one document. Contracts often
+    /// name the disbursement and the repayment rate in separate points; where
+    /// two clauses name different rates for the same event, the first wins.
+    pub fn merged<'a>(terms: impl IntoIterator<Item = &'a ExchangeSpreadTerms>) -> Option<Self> {
+        terms.into_iter().fold(None, |merged: Option<Self>, next| {
+            let Some(mut merged) = merged else { return Some(next.clone()) };
+            if merged.disbursement_rate.is_none() {
+                merged.disbursement_rate = next.disbursement_rate.clone();
+            }
+            if merged.repayment_rate.is_none() {
+                merged.repayment_rate = next.repayment_rate.clone();
+            }
+            merged.spread_percent = merged.spread_percent.or(next.spread_percent);
+            for source in &next.rate_sources {
+                if !merged.rate_sources.contains(source) {
+                    merged.rate_sources.push(source.clone());
+                }
+            }
+            Some(merged)
+        })
+    }
+
+    /// Disbursement and repayment converted at different rates, the pattern
+    /// struck down in C-26/13 Kásler and by Act XXXVIII of 2014.
+    pub fn has_asymmetric_rates(&self) -> bool {
+        matches!((&self.disbursement_rate, &self.repayment_rate), (Some(d), Some(r)) if d != r)
+    }
+
+    pub fn is_quantified(&self) -> bool {
+        self.spread_percent.is_some() || self.has_asymmetric_rates()
+    }
+}
+
+/// A point of the Directive 93/13/EEC Annex (indicative list of unfair terms)
+/// that an extracted clause falls under, with the text that triggered it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnnexMatch {
+    pub point: String, // "1(j)"
+    pub title: String,
+    pub evidence: String,
+}
+
+/// Principles Act XXXVIII of 2014 (following Kúria 2/2014 PJE) applies to
+/// clauses letting the bank unilaterally raise interest, costs or fees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DhPrinciple {
+    ClearWording,      // egyértelmű és érthető megfogalmazás
+    Transparency,      // átláthatóság
+    ExhaustiveReasons, // tételes meghatározás
+    Objectivity,       // ténylegesség
+    Proportionality,   // arányosság
+    Symmetry,          // szimmetria
+    TerminationRight,  // felmondhatóság
+}
+
+impl DhPrinciple {
+    pub const ALL: [DhPrinciple; 7] = [
+        Self::ClearWording,
+        Self::Transparency,
+        Self::ExhaustiveReasons,
+        Self::Objectivity,
+        Self::Proportionality,
+        Self::Symmetry,
+        Self::TerminationRight,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ClearWording => "clear and intelligible wording",
+            Self::Transparency => "transparency",
+            Self::ExhaustiveReasons => "exhaustive list of reasons",
+            Self::Objectivity => "objectivity",
+            Self::Proportionality => "proportionality",
+            Self::Symmetry => "symmetry",
+            Self::TerminationRight => "termination right",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrincipleCheck {
+    pub principle: DhPrinciple,
+    pub satisfied: bool,
+    pub finding: String,
+}
+
+/// Result of testing one unilateral modification clause against every `DhPrinciple`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnilateralModificationAnalysis {
+    pub checks: Vec<PrincipleCheck>,
+}
+
+impl UnilateralModificationAnalysis {
+    pub fn failed(&self) -> impl Iterator<Item = &PrincipleCheck> {
+        self.checks.iter().filter(|c| !c.satisfied)
+    }
+
+    pub fn is_compliant(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClausePatternRequest {
+    pub name: String,
+    pub pattern_type: String, // regex, keyword
+    pub pattern_text: String,
+    pub language: String, // ISO 639-1 code or "multilingual"
+    pub clause_category: String,
+    pub severity: Option<String>,
+    pub description: String,
+    pub legal_basis: Option<String>,
+    pub is_active: Option<bool>,
+}
+
 impl ClausePattern {
-    pub fn new_fx_risk_pattern() -> Self {
+    pub fn new(request: ClausePatternRequest) -> Self {
+        let now = Utc::now();
         Self {
             id: Uuid::new_v4(),
-            name: "Foreign Currency Risk Clause".to_string(),
-            pattern_type: "regex".to_string(),
-            pattern_text: r"(?i)(deviza|foreign\s+currency|árfolyam|exchange\s+rate|currency\s+risk|CHF|švýcarský\s+frank)".to_string(),
-            language: "multilingual".to_string(),
-            clause_category: "fx_risk".to_string(),
-            severity: "critical".to_string(),
-            description: "Clauses relating to foreign currency exchange rate risk".to_string(),
-            legal_basis: Some("EU Directive 93/13/EEC on unfair terms".to_string()),
-            is_active: true,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+            name: request.name,
+            pattern_type: request.pattern_type,
+            pattern_text: request.pattern_text,
+            language: request.language,
+            clause_category: request.clause_category,
+            severity: request.severity.unwrap_or_else(|| "warning".to_string()),
+            description: request.description,
+            legal_basis: request.legal_basis,
+            is_active: request.is_active.unwrap_or(true),
+            created_at: now,
+            updated_at: now,
         }
     }
 
-    pub fn new_transparency_pattern() -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            name: "Information Disclosure Requirements".to_string(),
-            pattern_type: "keyword".to_string(),
-            pattern_text: "tájékoztatás|information|disclosure|warning|risk|figyelmeztetés".to_string(),
-            language: "multilingual".to_string(),
-            clause_category: "transparency".to_string(),
-            severity: "warning".to_string(),
-            description: "Clauses related to information disclosure and transparency requirements".to_string(),
-            legal_basis: Some("CJEU Andriciuc v. Banca Românească".to_string()),
-            is_active: true,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+    /// Applies an edit from the API, keeping id and creation time.
+    pub fn update(&mut self, request: ClausePatternRequest) {
+        self.name = request.name;
+        self.pattern_type = request.pattern_type;
+        self.pattern_text = request.pattern_text;
+        self.language = request.language;
+        self.clause_category = request.clause_category;
+        if let Some(severity) = request.severity {
+            self.severity = severity;
         }
+        self.description = request.description;
+        self.legal_basis = request.legal_basis;
+        if let Some(is_active) = request.is_active {
+            self.is_active = is_active;
+        }
+        self.updated_at = Utc::now();
+    }
+
+    fn default_pattern(
+        name: &str,
+        pattern_text: &str,
+        language: &str,
+        clause_category: &str,
+        severity: &str,
+        description: &str,
+        legal_basis: &str,
+    ) -> Self {
+        Self::new(ClausePatternRequest {
+            name: name.to_string(),
+            pattern_type: "regex".to_string(),
+            pattern_text: pattern_text.to_string(),
+            language: language.to_string(),
+            clause_category: clause_category.to_string(),
+            severity: Some(severity.to_string()),
+            description: description.to_string(),
+            legal_basis: Some(legal_basis.to_string()),
+            is_active: Some(true),
+        })
     }
 
+    /// Patterns seeded into `clause_patterns`. Names are unique: seeding skips
+    /// names that already exist so edits made through the API are kept.
     pub fn get_default_patterns() -> Vec<Self> {
+        const DIRECTIVE: &str = "EU Directive 93/13/EEC on unfair terms";
+        const ANDRICIUC: &str = "CJEU C-186/16 Andriciuc v Banca Românească";
+        const FX: &str = "Clauses relating to foreign currency exchange rate risk";
+        const INFO: &str = "Clauses related to information disclosure and transparency requirements";
+        const INTEREST: &str = "Clauses allowing unilateral interest rate changes";
+        const PENALTY: &str = "Clauses imposing penalties or additional fees";
+        const UNILATERAL: &str = "Terms giving the bank unilateral discretion over the contract";
+        const SPREAD: &str = "Disbursement and repayment at different exchange rates (buying/selling rate spread)";
+        const KASLER: &str = "CJEU C-26/13 Kásler v OTP Jelzálogbank";
+        const KASLER_DH: &str = "CJEU C-26/13 Kásler v OTP Jelzálogbank; 2014. évi XXXVIII. törvény";
+        const EARLY_REPAYMENT: &str = "Fees or conditions for repaying the loan before maturity";
+        const INSURANCE: &str = "Compulsory life or property insurance tied to the loan";
+        const ACCELERATION: &str = "Calling the whole loan due or demanding more collateral when the collateral value or exchange rate moves";
+        const MCD_EARLY: &str = "Directive 2014/17/EU Art. 25 (early repayment)";
+        const MCD_TYING: &str = "Directive 2014/17/EU Art. 12 (tying and bundling practices)";
+        const AZIZ: &str = "CJEU C-415/11 Aziz (acceleration clauses); EU Directive 93/13/EEC";
+
         vec![
-            Self::new_fx_risk_pattern(),
-            Self::new_transparency_pattern(),
-            // Interest rate clauses
-            Self {
-                id: Uuid::new_v4(),
-                name: "Interest Rate Variation Clause".to_string(),
-                pattern_type: "regex".to_string(),
-                pattern_text: r"(?i)(kamat|interest\s+rate|úrok|změna\s+úroku|rate\s+change)".to_string(),
-                language: "multilingual".to_string(),
-                clause_category: "interest_rate".to_string(),
-                severity: "warning".to_string(),
-                description: "Clauses allowing unilateral interest rate changes".to_string(),
-                legal_basis: Some("EU consumer protection directives".to_string()),
-                is_active: true,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-            },
-            // Penalty clauses
-            Self {
-                id: Uuid::new_v4(),
-                name: "Penalty and Fee Clauses".to_string(),
-                pattern_type: "keyword".to_string(),
-                pattern_text: "penalty|fee|költség|díj|sankce|poplatek|fine".to_string(),
-                language: "multilingual".to_string(),
-                clause_category: "penalty".to_string(),
-                severity: "medium".to_string(),
-                description: "Clauses imposing penalties or additional fees".to_string(),
-                legal_basis: Some("Unfair Terms Directive".to_string()),
-                is_active: true,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-            },
+            // Foreign currency risk
+            Self::default_pattern("FX risk (hu)",
+                r"(?i)(?:devizaalapú\s+hitel|árfolyamkockázat|deviza.*kockázat|svájci\s+frank|CHF.*alapú|devizában\s+denominált|árfolyam.*változás|deviza.*kamat)",
+                "hu", "fx_risk", "critical", FX, DIRECTIVE),
+            Self::default_pattern("FX risk (en)",
+                r"(?i)(?:foreign\s+currency\s+loan|exchange\s+rate\s+risk|currency\s+fluctuation|Swiss\s+franc|CHF\s+loan|foreign\s+exchange|currency\s+exposure|FX\s+risk)",
+                "en", "fx_risk", "critical", FX, DIRECTIVE),
+            Self::default_pattern("FX risk (cs)",
+                r"(?i)(?:úvěr\w*\s+v\s+cizí\s+měně|kurzov\w*\s+rizik\w*|měnov\w*\s+rizik\w*|švýcarsk\w*\s+frank\w*|změn\w*\s+(?:směnného\s+)?kurzu)",
+                "cs", "fx_risk", "critical", FX, DIRECTIVE),
+            Self::default_pattern("FX risk (pl)",
+                r"(?i)(?:kredyt\w*\s+(?:indeksowan|denominowan)\w*|ryzyk\w*\s+kursow\w*|ryzyk\w*\s+walutow\w*|frank\w*\s+szwajcarsk\w*|kurs\w*\s+(?:wymiany|waluty)|tabel\w*\s+kursów)",
+                "pl", "fx_risk", "critical", FX, DIRECTIVE),
+            Self::default_pattern("FX risk (ro)",
+                r"(?i)(?:credit\w*\s+în\s+valută|risc\w*\s+valutar|risc\w*\s+de\s+curs|franc\w*\s+elvețian\w*|curs\w*\s+de\s+schimb|fluctuați\w*\s+cursului)",
+                "ro", "fx_risk", "critical", FX, DIRECTIVE),
+
+            // Information disclosure
+            Self::default_pattern("Risk disclosure (hu)",
+                r"(?i)(?:tudomásul\s+vesz\w*|tájékoztat\w*.{0,60}kockázat\w*|kockázat\w*.{0,60}tájékoztat\w*|felhívj?\w*\s+.{0,20}figyelm\w*|figyelmeztet\w*|kockázat.*ismertetés|információ.*nyújtás|kockázat.*felvilágosítás)",
+                "hu", "transparency", "warning", INFO, ANDRICIUC),
+            Self::default_pattern("Risk disclosure (en)",
+                r"(?i)(?:risk\s+disclosure|information\s+provided|warning.*risk|disclosure.*currency|informed.*decision)",
+                "en", "transparency", "warning", INFO, ANDRICIUC),
+            Self::default_pattern("Risk disclosure (cs)",
+                r"(?i)(?:poučen\w*\s+o\s+rizi\w*|upozorněn\w*\s+na\s+rizik\w*|informac\w*\s+o\s+rizi\w*|byl\w*\s+seznámen\w*)",
+                "cs", "transparency", "warning", INFO, ANDRICIUC),
+            Self::default_pattern("Risk disclosure (pl)",
+                r"(?i)(?:pouczeni\w*\s+o\s+ryzyk\w*|oświadczeni\w*\s+o\s+ryzyk\w*|informacj\w*\s+o\s+ryzyk\w*|został\w*\s+poinformowan\w*)",
+                "pl", "transparency", "warning", INFO, ANDRICIUC),
+            Self::default_pattern("Risk disclosure (ro)",
+                r"(?i)(?:informa\w*\s+(?:privind|despre)\s+risc\w*|avertiz\w*|a\s+fost\s+informat\w*|declar\w*\s+că\s+a\s+înțeles)",
+                "ro", "transparency", "warning", INFO, ANDRICIUC),
+
+            // Exchange-rate spread
+            Self::default_pattern("Exchange spread (hu)",
+                r"(?i)(?:árfolyamrés\w*|vételi\s+árfolyam\w*|eladási\s+árfolyam\w*|deviza[\s-]*közép\w*|középárfolyam\w*)",
+                "hu", "exchange_spread", "critical", SPREAD, KASLER_DH),
+            Self::default_pattern("Exchange spread (en)",
+                r"(?i)(?:exchange\s+rate\s+spread|(?:buying|selling|bid|offer)\s+(?:exchange\s+)?rate|mid(?:dle)?[\s-]+(?:market\s+)?rate)",
+                "en", "exchange_spread", "critical", SPREAD, KASLER),
+            Self::default_pattern("Exchange spread (cs)",
+                r"(?i)(?:kurz\w*\s+(?:devizy\s+)?(?:nákup|prodej)\w*|střed\w*\s+kurz\w*|kurzov\w*\s+rozpětí)",
+                "cs", "exchange_spread", "critical", SPREAD, KASLER),
+            Self::default_pattern("Exchange spread (pl)",
+                r"(?i)(?:spread\w*(?:\s+walutow\w*)?|kurs\w*\s+(?:kupna|sprzedaży)|kurs\w*\s+średni\w*)",
+                "pl", "exchange_spread", "critical", SPREAD, KASLER),
+            Self::default_pattern("Exchange spread (ro)",
+                r"(?i)(?:curs\w*\s+de\s+(?:cumpărare|vânzare)|curs\w*\s+mediu|marj\w*\s+de\s+curs)",
+                "ro", "exchange_spread", "critical", SPREAD, KASLER),
+
+            // Interest rate variation
+            Self::default_pattern("Interest rate variation (hu)",
+                r"(?i)(?:kamat.*változás|kamatláb.*módosítás|kamat.*emelés|változó\s+kamat|kamat.*feltétel)",
+                "hu", "interest_rate", "warning", INTEREST, DIRECTIVE),
+            Self::default_pattern("Interest rate variation (en)",
+                r"(?i)(?:interest\s+rate\s+change|variable\s+interest|rate\s+adjustment|interest.*modification|rate\s+variation)",
+                "en", "interest_rate", "warning", INTEREST, DIRECTIVE),
+            Self::default_pattern("Interest rate variation (cs)",
+                r"(?i)(?:změn\w*\s+úrok\w*|pohybliv\w*\s+úrok\w*|úrokov\w*\s+sazb\w*)",
+                "cs", "interest_rate", "warning", INTEREST, DIRECTIVE),
+            Self::default_pattern("Interest rate variation (pl)",
+                r"(?i)(?:zmienn\w*\s+oprocentowani\w*|oprocentowani\w*.{0,30}\s+zmienn\w*|zmian\w*\s+oprocentowania|stop\w*\s+procentow\w*)",
+                "pl", "interest_rate", "warning", INTEREST, DIRECTIVE),
+            Self::default_pattern("Interest rate variation (ro)",
+                r"(?i)(?:dobând\w*\s+variabil\w*|modific\w*\s+(?:a\s+)?dobânzii|rata\s+dobânzii)",
+                "ro", "interest_rate", "warning", INTEREST, DIRECTIVE),
+
+            // Penalties and fees
+            Self::default_pattern("Penalties and fees (hu)",
+                r"(?i)(?:késedelmi\s+kamat|díj.*felszámítás|költség.*visel|bírság|pótlék.*fizetés)",
+                "hu", "penalty", "medium", PENALTY, DIRECTIVE),
+            Self::default_pattern("Penalties and fees (en)",
+                r"(?i)(?:penalty.*fee|additional\s+charges|late\s+payment|default\s+interest|administrative\s+fee)",
+                "en", "penalty", "medium", PENALTY, DIRECTIVE),
+            Self::default_pattern("Penalties and fees (cs)",
+                r"(?i)(?:smluvní\s+pokut\w*|úrok\w*\s+z\s+prodlení|sankč\w*\s+poplat\w*)",
+                "cs", "penalty", "medium", PENALTY, DIRECTIVE),
+            Self::default_pattern("Penalties and fees (pl)",
+                r"(?i)(?:kar\w*\s+umown\w*|odsetk\w*\s+karn\w*|odsetk\w*\s+za\s+opóźnienie|opłat\w*\s+windykacyjn\w*)",
+                "pl", "penalty", "medium", PENALTY, DIRECTIVE),
+            Self::default_pattern("Penalties and fees (ro)",
+                r"(?i)(?:penalit\w*\s+de\s+întârziere|dobând\w*\s+penalizatoare|comision\w*\s+de\s+administrare)",
+                "ro", "penalty", "medium", PENALTY, DIRECTIVE),
+
+            // Early repayment
+            Self::default_pattern("Early repayment (hu)",
+                r"(?i)(?:előtörleszt\w*|végtörleszt\w*|lejárat\s+előtti\s+(?:visszafizet|törleszt)\w*|idő\s+előtti\s+visszafizet\w*)",
+                "hu", "early_repayment", "warning", EARLY_REPAYMENT, "2009. évi CLXII. törvény 24. §; Directive 2014/17/EU Art. 25"),
+            Self::default_pattern("Early repayment (en)",
+                r"(?i)(?:early\s+(?:repayment|redemption)|prepayment\s+(?:fee|charge|penalty|compensation)|repay\w*\s+(?:the\s+loan\s+)?before\s+(?:maturity|the\s+end))",
+                "en", "early_repayment", "warning", EARLY_REPAYMENT, MCD_EARLY),
+            Self::default_pattern("Early repayment (pl)",
+                r"(?i)(?:wcześniejsz\w*\s+spłat\w*|przedterminow\w*\s+spłat\w*|prowizj\w*\s+za\s+wcześniejsz\w*)",
+                "pl", "early_repayment", "warning", EARLY_REPAYMENT, MCD_EARLY),
+            Self::default_pattern("Early repayment (cs)",
+                r"(?i)(?:předčasn\w*\s+splace\w*|mimořádn\w*\s+splátk\w*|poplat\w*\s+za\s+předčasn\w*)",
+                "cs", "early_repayment", "warning", EARLY_REPAYMENT, MCD_EARLY),
+
+            // Insurance tied to the loan
+            Self::default_pattern("Insurance tie-in (hu)",
+                r"(?i)(?:(?:élet|vagyon|lakás|ingatlan|hitelfedezeti)biztosítás\w*|biztosítás\w*.{0,60}(?:kötelez|feltétel|engedményez|kedvezményez)\w*|kedvezményezett\w*.{0,40}bank\w*)",
+                "hu", "insurance_tie_in", "warning", INSURANCE, MCD_TYING),
+            Self::default_pattern("Insurance tie-in (en)",
+                r"(?i)(?:(?:life|property|home|buildings|payment\s+protection)\s+insurance|insurance\s+polic\w*.{0,60}(?:assign|pledg|condition|required|mandatory)\w*|(?:bank|lender)\s+(?:as|is\s+the)\s+(?:loss\s+payee|beneficiary))",
+                "en", "insurance_tie_in", "warning", INSURANCE, MCD_TYING),
+            Self::default_pattern("Insurance tie-in (pl)",
+                r"(?i)(?:ubezpieczeni\w*\s+(?:na\s+życie|nieruchomości|niskiego\s+wkładu|pomostow\w*)|cesj\w*\s+(?:praw\s+z\s+)?(?:umowy\s+)?ubezpieczeni\w*)",
+                "pl", "insurance_tie_in", "warning", INSURANCE, MCD_TYING),
+            Self::default_pattern("Insurance tie-in (cs)",
+                r"(?i)(?:pojištění\w*\s+(?:nemovitost\w*|schopnosti\s+splácet|život\w*)|životní\w*\s+pojištění|vinkulac\w*\s+pojistn\w*)",
+                "cs", "insurance_tie_in", "warning", INSURANCE, MCD_TYING),
+
+            // Acceleration on collateral value
+            Self::default_pattern("Collateral acceleration (hu)",
+                r"(?i)(?:fedezet\w*.{0,80}(?:csökken|elégtelen|érték\w*\s+változ)\w*|felmond\w*.{0,80}(?:egy\s+összegben|lejárttá|esedékessé)|kiegészítő\s+fedezet\w*|pótfedezet\w*|hitelfedezeti\s+mutató\w*)",
+                "hu", "collateral_acceleration", "critical", ACCELERATION, AZIZ),
+            Self::default_pattern("Collateral acceleration (en)",
+                r"(?i)(?:(?:loan[\s-]to[\s-]value|ltv)\s+(?:ratio\s+)?(?:exceed|breach)\w*|(?:additional|further)\s+(?:collateral|security)|(?:collateral|security)\s+(?:value\s+)?(?:falls|drops|decreases|is\s+insufficient)|(?:entire|whole|outstanding)\s+(?:loan|debt|amount)\s+(?:shall\s+)?(?:become\s+)?(?:immediately\s+)?(?:due|payable)|accelerat\w*)",
+                "en", "collateral_acceleration", "critical", ACCELERATION, AZIZ),
+            Self::default_pattern("Collateral acceleration (pl)",
+                r"(?i)(?:dodatkow\w*\s+zabezpiecz\w*|spad\w*\s+wartości\s+(?:zabezpieczenia|nieruchomości)|natychmiastow\w*\s+wymagaln\w*|postawieni\w*\s+(?:całego\s+)?kredytu\s+w\s+stan\s+wymagalności)",
+                "pl", "collateral_acceleration", "critical", ACCELERATION, AZIZ),
+            Self::default_pattern("Collateral acceleration (cs)",
+                r"(?i)(?:dodatečn\w*\s+zajištění|pokles\w*\s+hodnot\w*\s+zajištění|okamžit\w*\s+splatn\w*|zesplatně\w*)",
+                "cs", "collateral_acceleration", "critical", ACCELERATION, AZIZ),
+
+            // Unilateral discretion of the bank
+            Self::default_pattern("Unilateral bank discretion",
+                r"(?i)(?:(?:bank|hitelező).*jogosult.*egyoldalú|bank.*right.*unilateral|szerződés.*módosítás.*bank|bank\w*\s+.{0,40}módosíthat\w*|contract.*modification.*bank|kizárólag.*bank.*dönt|solely.*bank.*discretion)",
+                "multilingual", "unfair_term", "warning", UNILATERAL, DIRECTIVE),
+            Self::default_pattern("Unilateral bank discretion (cs)",
+                r"(?i)(?:banka\s+je\s+oprávněna\s+jednostranně|jednostrann\w*\s+změn\w*)",
+                "cs", "unfair_term", "warning", UNILATERAL, DIRECTIVE),
+            Self::default_pattern("Unilateral bank discretion (pl)",
+                r"(?i)(?:bank\s+(?:jest\s+)?uprawniony\s+do\s+jednostronn\w*|jednostronn\w*\s+zmian\w*|według\s+(?:decyzji|uznania)\s+(?:zarządu\s+)?bank\w*)",
+                "pl", "unfair_term", "warning", UNILATERAL, DIRECTIVE),
+            Self::default_pattern("Unilateral bank discretion (ro)",
+                r"(?i)(?:banca\s+(?:are\s+dreptul|poate)\s+.*unilateral|modific\w*\s+unilateral\w*)",
+                "ro", "unfair_term", "warning", UNILATERAL, DIRECTIVE),
         ]
     }
 }
@@ -150,23 +434,32 @@ impl ClauseAnalysis {
             eu_compliance_issues: Vec::new(),
             hungarian_law_issues: Vec::new(),
             suggested_challenges: Vec::new(),
+            breakdown: crate::models::ClauseScores::default(),
         }
     }
 
-    pub fn calculate_scores(&mut self, clause: &crate::models::ExtractedClause) {
-        // Calculate unfairness based on FX risk and lack of transparency
+    /// Scores any clause type with `ClauseScorer` and lists the issues behind
+    /// the scores. Use `ScoringContext::for_document` so that spread terms are
+    /// read across all of the document's spread clauses.
+    pub fn calculate_scores(&mut self, clause: &crate::models::ExtractedClause, context: &crate::models::ScoringContext) {
+        let scores = crate::models::ClauseScorer::new().score(clause, context);
+        self.unfairness_score = scores.unfairness.score;
+        self.transparency_score = scores.transparency.score;
+        self.consumer_detriment_score = scores.consumer_detriment.score;
+        self.breakdown = scores;
+
         if clause.is_fx_risk_clause() {
-            self.unfairness_score = 0.9; // Very high for FX risk clauses
-            self.consumer_detriment_score = 0.95;
-            
-            // Check for transparency issues
-            let transparency_indicators = ["warning", "risk", "tájékoztatás", "figyelmeztetés"];
-            let has_warnings = transparency_indicators.iter().any(|&indicator| {
-                clause.clause_text.to_lowercase().contains(&indicator.to_lowercase())
-            });
-            
-            self.transparency_score = if has_warnings { 0.6 } else { 0.1 };
-            
+            // Check for transparency issues; a borrower's signed acknowledgement is not a warning
+            let has_warnings = match clause.transparency_assessment.as_deref() {
+                Some(assessment) => assessment == "adequate_warning",
+                None => {
+                    let transparency_indicators = ["warning", "risk", "tájékoztatás", "figyelmeztetés"];
+                    transparency_indicators.iter().any(|&indicator| {
+                        clause.clause_text.to_lowercase().contains(&indicator.to_lowercase())
+                    })
+                }
+            };
+
             // Add compliance issues
             if !has_warnings {
                 self.eu_compliance_issues.push("Insufficient warning about currency risk (Andriciuc v. Banca Românească)".to_string());
@@ -175,5 +468,32 @@ impl ClauseAnalysis {
                 self.suggested_challenges.push("Cite CJEU precedents on FX risk disclosure requirements".to_string());
             }
         }
+
+        for annex in &clause.annex_points {
+            self.eu_compliance_issues.push(format!(
+                "Directive 93/13/EEC Annex point {} ({}): \"{}\"",
+                annex.point, annex.title, annex.evidence
+            ));
+        }
+
+        if let Some(modification) = &clause.unilateral_modification {
+            let failed = modification.failed().count();
+            if failed > 0 {
+                self.suggested_challenges.push("Challenge the unilateral modification clause under Act XXXVIII of 2014".to_string());
+            }
+            for check in modification.failed() {
+                self.hungarian_law_issues.push(format!(
+                    "Unilateral modification clause fails the principle of {} (Act XXXVIII of 2014): {}",
+                    check.principle.label(),
+                    check.finding
+                ));
+            }
+        }
+
+        // Only spread terms shown to convert at different rates are the term DH1 voided
+        if clause.has_asymmetric_spread(context.document_spread.as_ref()) {
+            self.hungarian_law_issues.push("Exchange rate spread clause deemed unfair under Act XXXVIII of 2014 (DH1)".to_string());
+            self.eu_compliance_issues.push("Buying/selling rate spread not justified by any service to the consumer (Kásler, C-26/13)".to_string());
+        }
     }
 }
\ No newline at end of file
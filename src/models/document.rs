@@ -49,13 +49,63 @@ pub struct ExtractedClause {
     pub clause_text: String,
     pub original_language: String,
     pub english_translation: Option<String>,
-    pub start_position: Option<i32>,
+    pub start_position: Option<i32>, // character offsets of clause_text in the document text
     pub end_position: Option<i32>,
     pub confidence_score: f32,
     pub risk_level: String, // low, medium, high, critical
+    pub section_path: Option<String>, // e.g. "Section 7.3 of Annex 2"
+    #[sqlx(json)]
+    pub categories: Vec<String>, // every category matched on this span, clause_type first
+    #[sqlx(json)]
+    pub matched_triggers: Vec<String>, // text of the pattern matches merged into this clause
+    pub transparency_assessment: Option<String>, // adequate_warning, boilerplate_acknowledgement, absent, negated
+    #[sqlx(json(nullable))]
+    pub exchange_spread: Option<crate::models::ExchangeSpreadTerms>, // exchange_spread clauses only
+    #[sqlx(json(nullable))]
+    pub unilateral_modification: Option<crate::models::UnilateralModificationAnalysis>,
+    #[sqlx(json)]
+    pub annex_points: Vec<crate::models::AnnexMatch>, // Directive 93/13/EEC Annex points the clause falls under
+    #[sqlx(json(nullable))]
+    pub translation_record: Option<TranslationRecord>, // how and when english_translation was produced
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationRecord {
+    pub method: String, // glossary, llm:<model>
+    pub translated_at: DateTime<Utc>,
+    pub coverage: f32, // share of the clause the method rendered in English
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DocumentPage {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub page_number: Option<i32>,
+    pub start_offset: i32, // character offset into extracted_text
+    pub end_offset: i32,
+    pub text: String,
+    pub needs_ocr: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DocumentSource {
+    pub document_id: Uuid,
+    pub source_file: String,
+    pub markdown_file: String,
+    pub processor: Option<String>,
+    pub fallback_method: Option<String>,
+    pub processed_date: Option<String>,
+    pub metadata: serde_json::Value,
+    pub imported_at: DateTime<Utc>,
+}
+
+/// Byte length of a base64 payload, without decoding it
+fn decoded_size(base64: &str) -> i64 {
+    (base64.trim_end_matches('=').len() * 3 / 4) as i64
+}
+
 impl Document {
     pub fn new(request: DocumentUploadRequest) -> Self {
         let now = Utc::now();
@@ -63,7 +113,7 @@ impl Document {
             id: Uuid::new_v4(),
             filename: request.filename,
             content_type: request.content_type,
-            file_size: 0, // Will be calculated after processing
+            file_size: decoded_size(&request.file_data),
             original_text: None,
             extracted_text: None,
             document_type: request.document_type,
@@ -97,6 +147,7 @@ impl ExtractedClause {
         Self {
             id: Uuid::new_v4(),
             document_id,
+            categories: vec![clause_type.clone()],
             clause_type,
             clause_text,
             original_language: language,
@@ -105,6 +156,13 @@ impl ExtractedClause {
             end_position: None,
             confidence_score: confidence,
             risk_level: "medium".to_string(),
+            section_path: None,
+            matched_triggers: Vec::new(),
+            transparency_assessment: None,
+            exchange_spread: None,
+            unilateral_modification: None,
+            annex_points: Vec::new(),
+            translation_record: None,
             created_at: Utc::now(),
         }
     }
@@ -131,4 +189,36 @@ impl ExtractedClause {
             self.risk_level = "low".to_string();
         }
     }
-}
\ No newline at end of file
+
+    /// True if the clause was classified as `category`, as primary type or as a merged category.
+    pub fn has_category(&self, category: &str) -> bool {
+        self.clause_type == category || self.categories.iter().any(|c| c == category)
+    }
+
+    /// True if this is a spread clause and the spread terms convert disbursement
+    /// and repayment at different rates. The two rates often sit in separate
+    /// points, so the document's merged terms are checked when given, else the
+    /// clause's own. Scoring and the issue list both rest on this check.
+    pub fn has_asymmetric_spread(&self, document_terms: Option<&crate::models::ExchangeSpreadTerms>) -> bool {
+        self.exchange_spread.as_ref().is_some_and(|own| document_terms.unwrap_or(own).has_asymmetric_rates())
+    }
+}
+
+impl DocumentPage {
+    pub fn new(document_id: Uuid, page_number: Option<i32>, start_offset: i32, end_offset: i32, text: String, needs_ocr: bool) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            document_id,
+            page_number,
+            start_offset,
+            end_offset,
+            text,
+            needs_ocr,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn contains_offset(&self, offset: i32) -> bool {
+        offset >= self.start_offset && offset < self.end_offset
+    }
+}
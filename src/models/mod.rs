@@ -2,8 +2,14 @@ pub mod case;
 pub mod document;
 pub mod clause;
 pub mod research;
+pub mod payment;
+pub mod scoring;
+pub mod rate;
 
 pub use case::*;
 pub use document::*;
 pub use clause::*;
-pub use research::*;
\ No newline at end of file
+pub use research::*;
+pub use payment::*;
+pub use scoring::*;
+pub use rate::*;
\ No newline at end of file
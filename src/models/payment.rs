@@ -0,0 +1,55 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// One instalment row of a repayment schedule (törlesztési tábla) or bank statement.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaymentScheduleRow {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub page_number: Option<i32>,
+    pub row_index: i32, // position of the row within the document's schedule
+    pub due_date: NaiveDate,
+    pub currency: Option<String>, // loan currency of instalment/principal/interest: CHF, EUR, JPY, ...
+    pub instalment_amount: Option<f64>,
+    pub huf_debit: Option<f64>,
+    pub exchange_rate: Option<f64>,
+    pub principal: Option<f64>,
+    pub interest: Option<f64>,
+    pub outstanding_balance: Option<f64>,
+    pub source_offset: i32, // character offset of the row in the document text
+    pub source_text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PaymentScheduleRow {
+    pub fn new(document_id: Uuid, row_index: i32, due_date: NaiveDate, source_offset: i32, source_text: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            document_id,
+            page_number: None,
+            row_index,
+            due_date,
+            currency: None,
+            instalment_amount: None,
+            huf_debit: None,
+            exchange_rate: None,
+            principal: None,
+            interest: None,
+            outstanding_balance: None,
+            source_offset,
+            source_text,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Exchange rate implied by the HUF debit and the foreign currency instalment,
+    /// used when the statement does not print the applied rate.
+    pub fn implied_exchange_rate(&self) -> Option<f64> {
+        match (self.huf_debit, self.instalment_amount) {
+            (Some(huf), Some(fx)) if fx > 0.0 => Some(huf / fx),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Longest run of days without a published exchange rate (weekends plus
+/// holidays such as Christmas) that a lookup bridges
+pub const MAX_PUBLICATION_GAP_DAYS: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateKind {
+    Official,
+    BankBuy,
+    BankSell,
+    BaseRate,
+    Bubor, // 3-month BUBOR, the reference rate of variable-rate HUF loans
+}
+
+impl RateKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Official => "official",
+            Self::BankBuy => "bank_buy",
+            Self::BankSell => "bank_sell",
+            Self::BaseRate => "base_rate",
+            Self::Bubor => "bubor",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "official" => Some(Self::Official),
+            "bank_buy" => Some(Self::BankBuy),
+            "bank_sell" => Some(Self::BankSell),
+            "base_rate" => Some(Self::BaseRate),
+            "bubor" => Some(Self::Bubor),
+            _ => None,
+        }
+    }
+
+    /// How far back a lookup may reach for a day without its own value.
+    /// Exchange rates are published on banking days only, and the last rate
+    /// before a weekend or holiday applies to it; the base rate stays in force
+    /// from one decision to the next, so a change of any age applies.
+    pub fn max_fallback_days(&self) -> Option<i64> {
+        match self {
+            Self::BaseRate => None,
+            _ => Some(MAX_PUBLICATION_GAP_DAYS),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RateSeries {
+    pub id: Uuid,
+    pub source: String, // MNB or the quoting bank
+    pub kind: String, // official, bank_buy, bank_sell, base_rate, bubor
+    pub currency: Option<String>, // None for the base rate and BUBOR
+    pub created_at: DateTime<Utc>,
+}
+
+impl RateSeries {
+    pub fn new(source: &str, kind: RateKind, currency: Option<&str>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            source: source.to_string(),
+            kind: kind.as_str().to_string(),
+            currency: currency.map(|c| c.to_uppercase()),
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn rate_kind(&self) -> Option<RateKind> {
+        RateKind::parse(&self.kind)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RateObservation {
+    pub series_id: Uuid,
+    pub rate_date: NaiveDate,
+    pub value: f64, // HUF per 1 unit of currency; percent per annum for the base rate and BUBOR
+    pub source_file: Option<String>,
+    pub imported_at: DateTime<Utc>,
+}
+
+/// The value that applies on `requested_date` and the date it was published
+/// or took effect, which differs on weekends and holidays.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLookup {
+    pub requested_date: NaiveDate,
+    pub rate_date: NaiveDate,
+    pub value: f64,
+}
+
+impl RateLookup {
+    /// Applies the fallback rule of `kind` to the latest observation on or
+    /// before the requested date.
+    pub fn resolve(kind: RateKind, requested_date: NaiveDate, latest: Option<(NaiveDate, f64)>) -> Option<Self> {
+        let (rate_date, value) = latest?;
+        if rate_date > requested_date {
+            return None;
+        }
+        if let Some(max_days) = kind.max_fallback_days() {
+            if (requested_date - rate_date).num_days() > max_days {
+                return None;
+            }
+        }
+        Some(Self { requested_date, rate_date, value })
+    }
+
+    pub fn is_fallback(&self) -> bool {
+        self.rate_date != self.requested_date
+    }
+}
@@ -34,6 +34,100 @@ pub struct LegalFindings {
     pub applicable_precedents: Vec<ApplicablePrecedent>,
     pub legal_arguments: Vec<LegalArgument>,
     pub evidence_requirements: Vec<String>,
+    #[serde(default)]
+    pub transparency_checklist: Option<TransparencyChecklist>,
+}
+
+/// Elements of the CJEU transparency test for foreign currency terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransparencyElement {
+    EconomicConsequences,
+    RateSimulation,
+    UnlimitedRisk,
+    ConversionMechanism,
+}
+
+impl TransparencyElement {
+    pub const ALL: [TransparencyElement; 4] = [
+        Self::EconomicConsequences,
+        Self::RateSimulation,
+        Self::UnlimitedRisk,
+        Self::ConversionMechanism,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::EconomicConsequences => "The consumer could assess the economic consequences of the term",
+            Self::RateSimulation => "The consumer was shown simulations of exchange rate changes",
+            Self::UnlimitedRisk => "The consumer was made aware that the exchange rate risk is unlimited",
+            Self::ConversionMechanism => "The disbursement and repayment conversion mechanism was explained",
+        }
+    }
+
+    /// The CJEU paragraph the element derives from.
+    pub fn authority(&self) -> &'static str {
+        match self {
+            Self::EconomicConsequences => "C-186/16 Andriciuc, para. 45; C-26/13 Kásler, para. 75",
+            Self::RateSimulation => "C-186/16 Andriciuc, para. 49",
+            Self::UnlimitedRisk => "C-186/16 Andriciuc, para. 50",
+            Self::ConversionMechanism => "C-26/13 Kásler, para. 75",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElementVerdict {
+    Met,
+    Unmet,
+    Unknown,
+}
+
+/// A passage of a contract clause or of the client's correspondence that a
+/// verdict rests on. Offsets are character offsets into the document text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceSpan {
+    pub document_id: Uuid,
+    pub clause_id: Option<Uuid>, // None for correspondence
+    pub source: String, // clause, correspondence
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub element: TransparencyElement,
+    pub verdict: ElementVerdict,
+    pub authority: String,
+    pub explanation: String,
+    pub evidence: Vec<EvidenceSpan>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransparencyChecklist {
+    pub document_id: Uuid,
+    pub items: Vec<ChecklistItem>,
+    pub overall: ElementVerdict, // unmet if any element is unmet, met only if all are met
+    pub evaluated_at: DateTime<Utc>,
+}
+
+impl TransparencyChecklist {
+    pub fn new(document_id: Uuid, items: Vec<ChecklistItem>) -> Self {
+        let overall = if items.iter().any(|i| i.verdict == ElementVerdict::Unmet) {
+            ElementVerdict::Unmet
+        } else if !items.is_empty() && items.iter().all(|i| i.verdict == ElementVerdict::Met) {
+            ElementVerdict::Met
+        } else {
+            ElementVerdict::Unknown
+        };
+        Self { document_id, items, overall, evaluated_at: Utc::now() }
+    }
+
+    pub fn unmet(&self) -> impl Iterator<Item = &ChecklistItem> {
+        self.items.iter().filter(|i| i.verdict == ElementVerdict::Unmet)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -141,6 +235,7 @@ impl LegalFindings {
             applicable_precedents: Vec::new(),
             legal_arguments: Vec::new(),
             evidence_requirements: Vec::new(),
+            transparency_checklist: None,
         }
     }
 
@@ -209,6 +304,16 @@ impl GeneratedPleading {
             content.push_str(&format!("{}. {}\n", i + 1, issue.description));
             content.push_str(&format!("Legal Basis: {}\n\n", issue.legal_basis.join("; ")));
         }
+
+        if let Some(checklist) = &findings.transparency_checklist {
+            for item in checklist.unmet() {
+                content.push_str(&format!("Not met: {}. {}\n", item.element.label(), item.explanation));
+                for evidence in &item.evidence {
+                    content.push_str(&format!("Evidence: \"{}\"\n", evidence.text));
+                }
+                content.push_str(&format!("Legal Basis: {}\n\n", item.authority));
+            }
+        }
         
         content.push_str("III. SUPPORTING PRECEDENTS\n\n");
         
@@ -0,0 +1,530 @@
+use serde::{Deserialize, Serialize};
+use crate::models::{ExchangeSpreadTerms, ExtractedClause};
+
+/// Transparency at or above this counts as "plain, intelligible language" for Art. 4(2)
+const PLAIN_AND_INTELLIGIBLE: f32 = 0.7;
+
+/// Highest unfairness score for a transparent main-subject-matter term
+const MAIN_SUBJECT_MATTER_CAP: f32 = 0.3;
+
+/// Highest unfairness score for an individually negotiated term
+const NEGOTIATED_TERM_CAP: f32 = 0.1;
+
+/// Average sentence length (words) at which plainness starts to drop, and where it reaches zero
+const PLAIN_SENTENCE_WORDS: f32 = 25.0;
+const OPAQUE_SENTENCE_WORDS: f32 = 60.0;
+
+/// References to documents outside the clause the consumer must read to understand it
+const EXTERNAL_REFERENCES: &[&str] = &[
+    "üzletszabályzat", "hirdetmény", "általános szerződési feltétel", "ászf",
+    "terms and conditions", "business rules", "tariff", "price list",
+    "regulamin", "tabel", "obchodní podmínky", "sazebník", "condiții generale",
+];
+
+const CONSEQUENCE_TERMS: &[&str] = &[
+    "emelked", "növeked", "nőhet", "increase", "rise", "wzrost", "wzrośn", "zvýš", "nárůst", "creșt",
+];
+
+const EXAMPLE_TERMS: &[&str] = &[
+    "példa", "szemléltet", "számítás", "example", "illustrat", "simulat", "scenario",
+    "przykład", "symulac", "příklad", "modelov", "exemplu", "simular",
+];
+
+const UNLIMITED_TERMS: &[&str] = &[
+    "korlátlan", "unlimited", "uncapped", "without limit", "nieograniczon", "neomezen", "nelimitat",
+];
+
+/// Matched as whole words: "capital" and "limited" are no cap
+const CAP_WORDS: &[&str] = &["legfeljebb", "cap", "caps", "capped", "limit", "limits", "nejvýše"];
+
+/// Word beginnings: maximum, maximális, maksymalnie
+const CAP_STEMS: &[&str] = &["maxim", "maksymaln"];
+
+const NEGOTIATED_TERMS: &[&str] = &[
+    "egyedileg megtárgyalt", "egyedi megállapodás", "individually negotiated", "indywidualnie uzgodn",
+    "individuálně sjednan", "negociat individual",
+];
+
+/// Negations that turn a negotiated-term statement around when they come
+/// shortly before it: "nem egyedileg megtárgyalt", "not individually negotiated"
+const NEGOTIATION_NEGATIONS: &[&str] = &["nem", "not", "never", "nie", "není", "nebyl", "nebyla", "nebylo", "nu"];
+
+const DISCRETION_TERMS: &[&str] = &[
+    "belátása szerint", "egyoldalú", "unilateral", "at its discretion", "sole discretion",
+    "jednostronn", "jednostrann", "według decyzji",
+];
+
+const MULTIPLIER_TERMS: &[&str] = &["kétszeres", "háromszoros", "double", "twice", "triple", "dwukrotn", "czterokrotn", "dvojnásob", "dublul"];
+
+/// One input to a score: `value` is how strongly the factor points towards a
+/// high score (0 to 1), `weight` its share of the weighted mean.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreFactor {
+    pub name: String,
+    pub value: f32,
+    pub weight: f32,
+    pub explanation: String,
+}
+
+/// Ceiling applied after weighting, with its legal ground.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreCap {
+    pub max: f32,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub score: f32,
+    pub factors: Vec<ScoreFactor>,
+    pub cap: Option<ScoreCap>,
+}
+
+impl ScoreBreakdown {
+    fn weighted(factors: Vec<ScoreFactor>) -> Self {
+        let total_weight: f32 = factors.iter().map(|f| f.weight).sum();
+        let score = if total_weight == 0.0 {
+            0.0
+        } else {
+            factors.iter().map(|f| f.value * f.weight).sum::<f32>() / total_weight
+        };
+        Self { score: score.clamp(0.0, 1.0), factors, cap: None }
+    }
+
+    fn capped(mut self, max: f32, reason: &str) -> Self {
+        self.score = self.score.min(max);
+        self.cap = Some(ScoreCap { max, reason: reason.to_string() });
+        self
+    }
+
+    pub fn factor(&self, name: &str) -> Option<&ScoreFactor> {
+        self.factors.iter().find(|f| f.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClauseScores {
+    pub unfairness: ScoreBreakdown,
+    pub transparency: ScoreBreakdown, // higher is more transparent
+    pub consumer_detriment: ScoreBreakdown,
+}
+
+/// Facts about the contract that the clause text itself cannot show.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoringContext {
+    pub individually_negotiated: Option<bool>, // None: presumed not negotiated (Art. 3(2))
+    pub document_spread: Option<ExchangeSpreadTerms>, // merged terms of all the document's spread clauses
+}
+
+impl ScoringContext {
+    /// Context for the clauses of one document, presuming standard terms.
+    pub fn for_document(clauses: &[ExtractedClause]) -> Self {
+        Self {
+            document_spread: ExchangeSpreadTerms::merged(clauses.iter().filter_map(|c| c.exchange_spread.as_ref())),
+            ..Self::default()
+        }
+    }
+}
+
+fn factor(name: &str, value: f32, weight: f32, explanation: impl Into<String>) -> ScoreFactor {
+    ScoreFactor { name: name.to_string(), value: value.clamp(0.0, 1.0), weight, explanation: explanation.into() }
+}
+
+fn contains_any(text: &str, terms: &[&str]) -> bool {
+    terms.iter().any(|t| text.contains(t))
+}
+
+/// `Some(true)` when the text says the term was individually negotiated,
+/// `Some(false)` when it only says so under a negation, `None` when it is silent.
+fn negotiation_statement(text: &str) -> Option<bool> {
+    let mut negated = false;
+    for term in NEGOTIATED_TERMS {
+        for (at, _) in text.match_indices(term) {
+            let before: Vec<&str> = text[..at].split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+            if before.iter().rev().take(3).any(|w| NEGOTIATION_NEGATIONS.contains(w)) {
+                negated = true;
+            } else {
+                return Some(true);
+            }
+        }
+    }
+    negated.then_some(false)
+}
+
+fn names_cap(text: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric())
+        .any(|w| CAP_WORDS.contains(&w) || CAP_STEMS.iter().any(|s| w.starts_with(s)))
+}
+
+/// Scores clauses of every type under Directive 93/13/EEC from explicit
+/// factors: transparency (Art. 4(2), 5), unfairness (Art. 3) and the
+/// consumer's financial exposure. Every score carries its factor breakdown.
+pub struct ClauseScorer;
+
+impl ClauseScorer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn score(&self, clause: &ExtractedClause, context: &ScoringContext) -> ClauseScores {
+        let lower = clause.clause_text.to_lowercase();
+        let transparency = self.transparency(clause, &lower);
+        let unfairness = self.unfairness(clause, &lower, context, transparency.score);
+        let consumer_detriment = self.consumer_detriment(clause, &lower);
+
+        ClauseScores { unfairness, transparency, consumer_detriment }
+    }
+
+    fn transparency(&self, clause: &ExtractedClause, lower: &str) -> ScoreBreakdown {
+        let mut factors = Vec::new();
+
+        let sentences: Vec<&str> = lower.split(['.', ';', '!', '?']).filter(|s| !s.trim().is_empty()).collect();
+        let words = lower.split_whitespace().count() as f32;
+        let average = if sentences.is_empty() { 0.0 } else { words / sentences.len() as f32 };
+        let length_score = 1.0 - ((average - PLAIN_SENTENCE_WORDS) / (OPAQUE_SENTENCE_WORDS - PLAIN_SENTENCE_WORDS)).clamp(0.0, 1.0);
+        let references = EXTERNAL_REFERENCES.iter().filter(|r| lower.contains(*r)).count();
+        factors.push(factor(
+            "plain_language",
+            length_score - 0.15 * references.min(3) as f32,
+            0.3,
+            format!("{:.0} words per sentence; {} reference(s) to other documents", average, references),
+        ));
+
+        let mut comprehensible = 0.2;
+        let mut shown = Vec::new();
+        for (terms, weight, label) in [
+            (CONSEQUENCE_TERMS, 0.3, "describes how payments change"),
+            (EXAMPLE_TERMS, 0.3, "gives a worked example or simulation"),
+            (UNLIMITED_TERMS, 0.2, "states that the risk is unlimited"),
+        ] {
+            if contains_any(lower, terms) {
+                comprehensible += weight;
+                shown.push(label);
+            }
+        }
+        factors.push(factor(
+            "economic_consequences_comprehensible",
+            comprehensible,
+            0.3,
+            if shown.is_empty() { "The economic consequences are not described".to_string() } else { shown.join("; ") },
+        ));
+
+        if let Some(assessment) = clause.transparency_assessment.as_deref() {
+            let (value, explanation) = match assessment {
+                "adequate_warning" => (0.9, "The bank warns the consumer of the risk"),
+                "boilerplate_acknowledgement" => (0.3, "Only a pre-printed acknowledgement by the consumer (Andriciuc, C-186/16)"),
+                "negated" => (0.0, "The text states that no information was given"),
+                _ => (0.1, "No information about the risk"),
+            };
+            factors.push(factor("risk_information", value, 0.4, explanation));
+        }
+
+        ScoreBreakdown::weighted(factors)
+    }
+
+    fn unfairness(&self, clause: &ExtractedClause, lower: &str, context: &ScoringContext, transparency: f32) -> ScoreBreakdown {
+        let mut factors = Vec::new();
+
+        let (negotiated, explanation) = match context.individually_negotiated {
+            Some(true) => (true, "Stated to be individually negotiated"),
+            Some(false) => (false, "Not individually negotiated"),
+            None => match negotiation_statement(lower) {
+                Some(true) => (true, "The clause says it was individually negotiated"),
+                Some(false) => (false, "The clause says it was not individually negotiated"),
+                None => (false, "Pre-formulated standard term, presumed not individually negotiated (Art. 3(2))"),
+            },
+        };
+        factors.push(factor("not_individually_negotiated", if negotiated { 0.0 } else { 1.0 }, 0.15, explanation));
+
+        factors.push(factor(
+            "lack_of_transparency",
+            1.0 - transparency,
+            0.25,
+            format!("Transparency score {:.2}", transparency),
+        ));
+
+        let (value, explanation) = match &clause.unilateral_modification {
+            Some(analysis) => {
+                let failed = analysis.failed().count();
+                (failed as f32 / analysis.checks.len().max(1) as f32,
+                 format!("{} of {} Act XXXVIII of 2014 principles failed", failed, analysis.checks.len()))
+            },
+            None if contains_any(lower, DISCRETION_TERMS) => (0.7, "The bank may act at its own discretion".to_string()),
+            None => (0.0, "No unilateral discretion of the bank".to_string()),
+        };
+        factors.push(factor("bank_discretion", value, 0.2, explanation));
+
+        let points = &clause.annex_points;
+        factors.push(factor(
+            "annex_indicative_list",
+            if points.is_empty() { 0.0 } else { 0.5 + 0.25 * (points.len() - 1) as f32 },
+            0.2,
+            if points.is_empty() {
+                "No Directive 93/13/EEC Annex point applies".to_string()
+            } else {
+                format!("Annex point(s) {}", points.iter().map(|p| p.point.as_str()).collect::<Vec<_>>().join(", "))
+            },
+        ));
+
+        let (value, explanation) = imbalance(clause, context);
+        factors.push(factor("significant_imbalance", value, 0.2, explanation));
+
+        let breakdown = ScoreBreakdown::weighted(factors);
+        if negotiated {
+            breakdown.capped(
+                NEGOTIATED_TERM_CAP,
+                "Individually negotiated; Art. 3(1) assesses only terms that were not individually negotiated",
+            )
+        } else if defines_main_subject_matter(clause) && transparency >= PLAIN_AND_INTELLIGIBLE {
+            breakdown.capped(
+                MAIN_SUBJECT_MATTER_CAP,
+                "Defines the main subject matter in plain, intelligible language; excluded from the unfairness assessment (Art. 4(2))",
+            )
+        } else {
+            breakdown
+        }
+    }
+
+    fn consumer_detriment(&self, clause: &ExtractedClause, lower: &str) -> ScoreBreakdown {
+        let mut factors = Vec::new();
+
+        let (value, category) = clause.categories.iter()
+            .chain(std::iter::once(&clause.clause_type))
+            .map(|c| (exposure(c), c.as_str()))
+            .fold((0.0, "other"), |best, next| if next.0 > best.0 { next } else { best });
+        factors.push(factor("exposure", value, 0.5, format!("Typical financial exposure of a {} clause", category)));
+
+        let fx = clause.has_category("fx_risk");
+        let (value, explanation) = if contains_any(lower, UNLIMITED_TERMS) {
+            (1.0, "The clause itself calls the risk unlimited")
+        } else if fx && !names_cap(lower) {
+            (1.0, "Exchange rate risk without any cap")
+        } else {
+            (0.0, "The exposure is bounded")
+        };
+        factors.push(factor("unlimited_risk", value, 0.25, explanation));
+
+        let (value, explanation) = match largest_percent(lower) {
+            _ if contains_any(lower, MULTIPLIER_TERMS) => (1.0, "Charge set as a multiple of the contractual rate".to_string()),
+            Some(percent) => ((percent / 10.0) as f32, format!("Largest stated rate or charge {}%", percent)),
+            None => (0.0, "No rate or charge stated".to_string()),
+        };
+        factors.push(factor("quantified_cost", value, 0.25, explanation));
+
+        ScoreBreakdown::weighted(factors)
+    }
+}
+
+/// Art. 4(2) covers the terms defining the main subject matter and the price:
+/// the currency of the loan and its risk (Andriciuc, C-186/16, para. 38) and the interest rate.
+fn defines_main_subject_matter(clause: &ExtractedClause) -> bool {
+    clause.has_category("fx_risk") || clause.has_category("interest_rate")
+}
+
+fn imbalance(clause: &ExtractedClause, context: &ScoringContext) -> (f32, String) {
+    if clause.has_asymmetric_spread(context.document_spread.as_ref()) {
+        return (0.9, "Disbursement and repayment at different rates, a spread with no service in return (Kásler, C-26/13)".to_string());
+    }
+
+    let (value, category) = clause.categories.iter()
+        .chain(std::iter::once(&clause.clause_type))
+        .map(|c| (match c.as_str() {
+            "exchange_spread" => 0.8,
+            "fx_risk" | "collateral_acceleration" => 0.7,
+            "penalty" | "unfair_term" => 0.6,
+            "early_repayment" | "insurance_tie_in" | "interest_rate" => 0.5,
+            "transparency" => 0.4,
+            _ => 0.3,
+        }, c.as_str()))
+        .fold((0.0, "other"), |best, next| if next.0 > best.0 { next } else { best });
+
+    (value, format!("Imbalance typical of a {} clause", category))
+}
+
+fn exposure(category: &str) -> f32 {
+    match category {
+        "fx_risk" => 0.9,
+        "collateral_acceleration" => 0.85,
+        "exchange_spread" | "interest_rate" => 0.6,
+        "unfair_term" | "penalty" | "transparency" => 0.5,
+        "early_repayment" | "insurance_tie_in" => 0.4,
+        _ => 0.3,
+    }
+}
+
+/// Largest "12%" / "12 százalék" style figure in the text.
+fn largest_percent(lower: &str) -> Option<f64> {
+    let chars: Vec<char> = lower.chars().collect();
+    let mut best: Option<f64> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && (chars[i].is_ascii_digit() || ((chars[i] == ',' || chars[i] == '.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()))) {
+            i += 1;
+        }
+        let number: String = chars[start..i].iter().collect();
+        let rest: String = chars[i..].iter().take(12).collect();
+        let rest = rest.trim_start();
+        if rest.starts_with('%') || rest.starts_with("százalék") || rest.starts_with("per cent") || rest.starts_with("procent") {
+            if let Ok(value) = number.replace(',', ".").parse::<f64>() {
+                best = Some(best.map_or(value, |b: f64| b.max(value)));
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn clause(category: &str, text: &str) -> ExtractedClause {
+        ExtractedClause::new(Uuid::nil(), category.to_string(), text.to_string(), "hu".to_string(), 0.8)
+    }
+
+    #[test]
+    fn test_every_clause_type_gets_scores_with_factors() {
+        let scorer = ClauseScorer::new();
+        for (category, text) in [
+            ("penalty", "Késedelem esetén az Adós az ügyleti kamat kétszeresének megfelelő késedelmi kamatot fizet."),
+            ("early_repayment", "Előtörlesztés esetén az Adós az előtörlesztett összeg 3%-ának megfelelő díjat fizet."),
+            ("interest_rate", "A Bank jogosult a kamatot egyoldalúan módosítani."),
+        ] {
+            let scores = scorer.score(&clause(category, text), &ScoringContext::default());
+            assert!(scores.unfairness.score > 0.0, "{category}");
+            assert!(scores.consumer_detriment.score > 0.0, "{category}");
+            assert!(!scores.unfairness.factors.is_empty());
+            assert!(scores.unfairness.factor("not_individually_negotiated").unwrap().value == 1.0);
+        }
+    }
+
+    #[test]
+    fn test_transparent_main_subject_matter_is_capped() {
+        let mut fx = clause(
+            "fx_risk",
+            "A Bank tájékoztatja az Adóst, hogy a törlesztőrészlet az árfolyam változásával korlátlanul emelkedhet. \
+             Példa: ha a frank 20%-kal erősödik, a részlet is 20%-kal nő.",
+        );
+        fx.transparency_assessment = Some("adequate_warning".to_string());
+
+        let scores = ClauseScorer::new().score(&fx, &ScoringContext::default());
+        assert!(scores.transparency.score >= PLAIN_AND_INTELLIGIBLE);
+        assert_eq!(scores.unfairness.cap.as_ref().unwrap().max, MAIN_SUBJECT_MATTER_CAP);
+        assert!(scores.unfairness.score <= MAIN_SUBJECT_MATTER_CAP);
+
+        fx.transparency_assessment = Some("boilerplate_acknowledgement".to_string());
+        fx.clause_text = "Az Adós tudomásul veszi az árfolyamkockázatot, amelyet a Hirdetmény és az Üzletszabályzat szerint visel.".to_string();
+        let scores = ClauseScorer::new().score(&fx, &ScoringContext::default());
+        assert!(scores.unfairness.cap.is_none());
+        assert!(scores.unfairness.score > MAIN_SUBJECT_MATTER_CAP);
+    }
+
+    #[test]
+    fn test_spread_score_and_issues_rest_on_the_same_check() {
+        let mut spread = clause("exchange_spread", "A folyósítás és a törlesztés a Bank által jegyzett árfolyamon történik.");
+        spread.exchange_spread = Some(ExchangeSpreadTerms::default());
+
+        let mut analysis = crate::models::ClauseAnalysis::new();
+        analysis.calculate_scores(&spread, &ScoringContext::default());
+        assert!(analysis.breakdown.unfairness.factor("significant_imbalance").unwrap().value < 0.9);
+        assert!(analysis.hungarian_law_issues.iter().all(|i| !i.contains("DH1")));
+
+        spread.exchange_spread = Some(ExchangeSpreadTerms {
+            disbursement_rate: Some("buying".to_string()),
+            repayment_rate: Some("selling".to_string()),
+            ..ExchangeSpreadTerms::default()
+        });
+        let mut analysis = crate::models::ClauseAnalysis::new();
+        analysis.calculate_scores(&spread, &ScoringContext::default());
+        assert_eq!(analysis.breakdown.unfairness.factor("significant_imbalance").unwrap().value, 0.9);
+        assert!(analysis.hungarian_law_issues.iter().any(|i| i.contains("DH1")));
+    }
+
+    #[test]
+    fn test_spread_rates_in_separate_points_are_asymmetric() {
+        let mut disbursement = clause("exchange_spread", "3.1. A kölcsön folyósítása a Bank deviza vételi árfolyamán történik.");
+        disbursement.exchange_spread = Some(ExchangeSpreadTerms {
+            disbursement_rate: Some("buying".to_string()),
+            ..ExchangeSpreadTerms::default()
+        });
+        let mut repayment = clause("exchange_spread", "6.1. A törlesztőrészletek a Bank deviza eladási árfolyamon fizetendők.");
+        repayment.exchange_spread = Some(ExchangeSpreadTerms {
+            repayment_rate: Some("selling".to_string()),
+            ..ExchangeSpreadTerms::default()
+        });
+        let interest = clause("interest_rate", "A Bank jogosult a kamatot egyoldalúan módosítani.");
+        let clauses = [disbursement, repayment, interest];
+        let context = ScoringContext::for_document(&clauses);
+
+        for spread in &clauses[..2] {
+            let mut analysis = crate::models::ClauseAnalysis::new();
+            analysis.calculate_scores(spread, &context);
+            assert!(analysis.hungarian_law_issues.iter().any(|i| i.contains("DH1")));
+            assert_eq!(analysis.breakdown.unfairness.factor("significant_imbalance").unwrap().value, 0.9);
+        }
+        let mut analysis = crate::models::ClauseAnalysis::new();
+        analysis.calculate_scores(&clauses[2], &context);
+        assert!(analysis.hungarian_law_issues.iter().all(|i| !i.contains("DH1")));
+    }
+
+    #[test]
+    fn test_cap_words_are_matched_whole() {
+        let scorer = ClauseScorer::new();
+        let risk = |text: &str| scorer.score(&clause("fx_risk", text), &ScoringContext::default())
+            .consumer_detriment.factor("unlimited_risk").unwrap().value;
+
+        assert_eq!(risk("The borrower bears the exchange rate risk on the capital of the loan granted by the Bank Limited."), 1.0);
+        assert_eq!(risk("The exchange rate applied to the instalments is capped at 20% above the disbursement rate."), 0.0);
+        assert_eq!(risk("A törlesztőrészlet árfolyamkockázata legfeljebb 15%-os emelkedésig terheli az Adóst."), 0.0);
+    }
+
+    #[test]
+    fn test_negated_negotiation_statement_is_not_negotiated() {
+        let scorer = ClauseScorer::new();
+        let negotiated = |text: &str| scorer.score(&clause("unfair_term", text), &ScoringContext::default())
+            .unfairness.factor("not_individually_negotiated").unwrap().value;
+
+        assert_eq!(negotiated("A jelen feltétel nem egyedileg megtárgyalt szerződési feltétel."), 1.0);
+        assert_eq!(negotiated("This term was not individually negotiated with the Borrower."), 1.0);
+        assert_eq!(negotiated("Postanowienie nie zostało indywidualnie uzgodnione z konsumentem."), 1.0);
+        assert_eq!(negotiated("A felek a kamatfeltételt egyedileg megtárgyalt módon rögzítik."), 0.0);
+    }
+
+    #[test]
+    fn test_negotiated_context_lowers_unfairness() {
+        let text = "A Bank jogosult a kezelési költséget belátása szerint módosítani.";
+        let scorer = ClauseScorer::new();
+        let standard = scorer.score(&clause("unfair_term", text), &ScoringContext::default());
+        let negotiated = scorer.score(&clause("unfair_term", text), &ScoringContext { individually_negotiated: Some(true), ..ScoringContext::default() });
+        assert!(negotiated.unfairness.score < standard.unfairness.score);
+    }
+
+    #[test]
+    fn test_negotiated_term_is_outside_the_assessment() {
+        let mut term = clause(
+            "unfair_term",
+            "A Bank jogosult a kezelési költséget az Üzletszabályzat és a Hirdetmény szerint, belátása szerint egyoldalúan módosítani.",
+        );
+        term.annex_points.push(crate::models::AnnexMatch {
+            point: "1(j)".to_string(),
+            title: "Unilateral alteration of terms".to_string(),
+            evidence: "egyoldalúan módosítani".to_string(),
+        });
+        let scorer = ClauseScorer::new();
+        assert!(scorer.score(&term, &ScoringContext::default()).unfairness.score > 0.5);
+
+        let negotiated = scorer.score(&term, &ScoringContext { individually_negotiated: Some(true), ..ScoringContext::default() });
+        assert_eq!(negotiated.unfairness.cap.as_ref().unwrap().max, NEGOTIATED_TERM_CAP);
+        assert!(negotiated.unfairness.cap.as_ref().unwrap().reason.contains("Art. 3(1)"));
+        assert!(negotiated.unfairness.score <= NEGOTIATED_TERM_CAP);
+
+        term.clause_text.push_str(" A felek ezt a feltételt egyedileg megtárgyalt módon rögzítik.");
+        let stated = scorer.score(&term, &ScoringContext::default());
+        assert!(stated.unfairness.score <= NEGOTIATED_TERM_CAP);
+    }
+}
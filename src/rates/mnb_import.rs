@@ -0,0 +1,366 @@
+use chrono::{Duration, NaiveDate};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use zip::ZipArchive;
+use crate::models::RateKind;
+
+/// First day of Excel's 1900 date system, counting its phantom 1900-02-29
+const EXCEL_EPOCH: (i32, u32, u32) = (1899, 12, 30);
+
+const BUY_WORDS: &[&str] = &["vételi", "vétel", "buy", "bid"];
+const SELL_WORDS: &[&str] = &["eladási", "eladás", "sell", "ask", "offer"];
+const BASE_RATE_WORDS: &[&str] = &["alapkamat", "base rate", "policy rate"];
+const BUBOR_WORDS: &[&str] = &["bubor"];
+const UNIT_WORDS: &[&str] = &["egység", "unit"];
+
+/// One column of an export: what it measures and its dated values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSeries {
+    pub kind: RateKind,
+    pub currency: Option<String>,
+    pub values: Vec<(NaiveDate, f64)>,
+}
+
+/// Reads the rate tables the MNB publishes (Árfolyam-lekérdezés and the base
+/// rate history) as CSV or XLSX, and bank rate sheets laid out the same way.
+///
+/// The layout is one row per date and one column per series. The header names
+/// the currency by ISO code (CHF, EUR, JPY, USD or GBP), with "vételi"/"buy" or "eladási"/"sell" for bank
+/// quotes, or says "alapkamat"/"base rate" or "BUBOR"; of the BUBOR tenors
+/// only the 3-month one is read. An optional "Egység"/"Unit" row
+/// below the header gives how many units a quote is for (JPY is quoted per
+/// 100); values are stored per 1 unit. Dates may be "2010.01.04.",
+/// "2010-01-04" or Excel serial numbers, and values may use a decimal comma.
+pub struct MnbRateImporter {
+    currency_code: Regex,
+    tenor: Regex,
+}
+
+impl MnbRateImporter {
+    pub fn new() -> Self {
+        Self {
+            // Only the loan currencies: "OTP CHF vételi" and "MNB EUR" name a source too
+            currency_code: Regex::new(r"\b(?:CHF|EUR|JPY|USD|GBP)\b").unwrap(),
+            tenor: Regex::new(r"(?i)o/n|\b(\d+)\s*(hét|hetes|w|week|hó|hónap|m|month)").unwrap(),
+        }
+    }
+
+    pub fn import_file(&self, path: &Path) -> Result<Vec<ImportedSeries>, Box<dyn std::error::Error>> {
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+        let rows = match extension.as_str() {
+            "csv" | "txt" => read_csv(&std::fs::read(path)?)?,
+            "xlsx" => read_xlsx(&std::fs::read(path)?)?,
+            _ => return Err(format!("{}: unsupported rate file format", path.display()).into()),
+        };
+        self.parse_table(&rows)
+    }
+
+    pub fn parse_table(&self, rows: &[Vec<String>]) -> Result<Vec<ImportedSeries>, Box<dyn std::error::Error>> {
+        let header_index = rows.iter()
+            .position(|row| row.iter().skip(1).any(|cell| self.column_series(cell).is_some()))
+            .ok_or("no rate columns found in the header")?;
+        let header = &rows[header_index];
+
+        let mut columns: Vec<(usize, ImportedSeries, f64)> = header.iter().enumerate().skip(1)
+            .filter_map(|(i, cell)| {
+                let (kind, currency) = self.column_series(cell)?;
+                Some((i, ImportedSeries { kind, currency, values: Vec::new() }, 1.0))
+            })
+            .collect();
+
+        for row in &rows[header_index + 1..] {
+            let first = row.first().map(|c| c.trim().to_lowercase()).unwrap_or_default();
+            if UNIT_WORDS.iter().any(|w| first.starts_with(w)) {
+                for (i, _, unit) in &mut columns {
+                    if let Some(value) = row.get(*i).and_then(|c| parse_number(c)).filter(|u| *u > 0.0) {
+                        *unit = value;
+                    }
+                }
+                continue;
+            }
+
+            let Some(date) = row.first().and_then(|c| parse_date(c)) else {
+                continue;
+            };
+            for (i, series, unit) in &mut columns {
+                if let Some(value) = row.get(*i).and_then(|c| parse_number(c)) {
+                    series.values.push((date, value / *unit));
+                }
+            }
+        }
+
+        let imported: Vec<ImportedSeries> = columns.into_iter()
+            .map(|(_, mut series, _)| {
+                series.values.sort_by_key(|(date, _)| *date);
+                series.values.dedup_by_key(|(date, _)| *date);
+                series
+            })
+            .filter(|s| !s.values.is_empty())
+            .collect();
+        if imported.is_empty() {
+            return Err("no dated values found".into());
+        }
+        Ok(imported)
+    }
+
+    fn column_series(&self, cell: &str) -> Option<(RateKind, Option<String>)> {
+        let lower = cell.to_lowercase();
+        if BASE_RATE_WORDS.iter().any(|w| lower.contains(w)) {
+            return Some((RateKind::BaseRate, None));
+        }
+        if BUBOR_WORDS.iter().any(|w| lower.contains(w)) {
+            let three_months = match self.tenor.captures(&lower) {
+                Some(tenor) => tenor.get(1).is_some_and(|n| n.as_str() == "3")
+                    && tenor.get(2).is_some_and(|unit| ["hó", "hónap", "m", "month"].contains(&unit.as_str())),
+                None => true,
+            };
+            return three_months.then_some((RateKind::Bubor, None));
+        }
+
+        let currency = self.currency_code.find(cell)?.as_str().to_string();
+        let kind = if BUY_WORDS.iter().any(|w| lower.contains(w)) {
+            RateKind::BankBuy
+        } else if SELL_WORDS.iter().any(|w| lower.contains(w)) {
+            RateKind::BankSell
+        } else {
+            RateKind::Official
+        };
+        Some((kind, Some(currency)))
+    }
+}
+
+/// CSV with `;`, tab or `,` as the separator, whichever the first line uses.
+fn read_csv(data: &[u8]) -> Result<Vec<Vec<String>>, Box<dyn std::error::Error>> {
+    let text = String::from_utf8_lossy(data);
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or_default();
+    let delimiter = [b';', b'\t', b','].into_iter()
+        .max_by_key(|d| first_line.bytes().filter(|b| b == d).count())
+        .unwrap_or(b';');
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        rows.push(record?.iter().map(|c| c.trim().to_string()).collect());
+    }
+    Ok(rows)
+}
+
+/// Cell values of the first worksheet, with shared strings resolved.
+fn read_xlsx(data: &[u8]) -> Result<Vec<Vec<String>>, Box<dyn std::error::Error>> {
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let shared_strings = match read_entry(&mut archive, "xl/sharedStrings.xml")? {
+        Some(xml) => parse_shared_strings(&xml)?,
+        None => Vec::new(),
+    };
+    let sheet = read_entry(&mut archive, "xl/worksheets/sheet1.xml")?
+        .ok_or("workbook has no first worksheet")?;
+    parse_sheet(&sheet, &shared_strings)
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)?;
+    Ok(Some(xml))
+}
+
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"si" => current.clear(),
+            Event::Start(e) if e.local_name().as_ref() == b"t" => in_text = true,
+            Event::End(e) if e.local_name().as_ref() == b"t" => in_text = false,
+            Event::End(e) if e.local_name().as_ref() == b"si" => strings.push(current.clone()),
+            Event::Text(t) if in_text => current.push_str(&t.unescape()?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+fn parse_sheet(xml: &str, shared_strings: &[String]) -> Result<Vec<Vec<String>>, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut column = 0;
+    let mut cell_type = None;
+    let mut value = String::new();
+    let mut in_value = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.local_name().as_ref() == b"row" => rows.push(Vec::new()),
+            Event::Start(e) if e.local_name().as_ref() == b"c" => {
+                let mut reference = None;
+                cell_type = None;
+                for attribute in e.attributes().flatten() {
+                    match attribute.key.local_name().as_ref() {
+                        b"r" => reference = Some(attribute.unescape_value()?.into_owned()),
+                        b"t" => cell_type = Some(attribute.unescape_value()?.into_owned()),
+                        _ => {}
+                    }
+                }
+                column = reference.as_deref().map_or(rows.last().map_or(0, |r| r.len()), column_index);
+                value.clear();
+            },
+            Event::Start(e) if matches!(e.local_name().as_ref(), b"v" | b"t") => in_value = true,
+            Event::End(e) if matches!(e.local_name().as_ref(), b"v" | b"t") => in_value = false,
+            Event::Text(t) if in_value => value.push_str(&t.unescape()?),
+            Event::End(e) if e.local_name().as_ref() == b"c" => {
+                let text = match cell_type.as_deref() {
+                    Some("s") => value.trim().parse::<usize>().ok()
+                        .and_then(|i| shared_strings.get(i).cloned())
+                        .unwrap_or_default(),
+                    _ => value.clone(),
+                };
+                if let Some(row) = rows.last_mut() {
+                    if row.len() <= column {
+                        row.resize(column + 1, String::new());
+                    }
+                    row[column] = text;
+                }
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+/// Zero-based column of a cell reference such as "AB12".
+fn column_index(reference: &str) -> usize {
+    reference.chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .fold(0, |index, c| index * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1))
+        .saturating_sub(1)
+}
+
+pub fn parse_date(cell: &str) -> Option<NaiveDate> {
+    let cell = cell.trim().trim_end_matches('.');
+    for format in ["%Y.%m.%d", "%Y-%m-%d", "%Y. %m. %d", "%Y/%m/%d", "%d.%m.%Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(cell, format) {
+            return Some(date);
+        }
+    }
+
+    // Excel serial date, as in XLSX exports without a text date column
+    let serial: f64 = cell.parse().ok()?;
+    if !(1.0..=2_958_465.0).contains(&serial) || serial.fract() != 0.0 {
+        return None;
+    }
+    let (y, m, d) = EXCEL_EPOCH;
+    NaiveDate::from_ymd_opt(y, m, d)?.checked_add_signed(Duration::days(serial as i64))
+}
+
+/// "139,52", "1 234,5", "1.234,5", "6.50%" or "0.0325"; empty cells and "-" are no value.
+pub fn parse_number(cell: &str) -> Option<f64> {
+    let cleaned: String = cell.trim()
+        .trim_end_matches('%')
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{a0}')
+        .collect();
+    if cleaned.is_empty() || cleaned == "-" {
+        return None;
+    }
+    // With both separators present, the last one is the decimal mark
+    let normalized = match (cleaned.rfind(','), cleaned.rfind('.')) {
+        (Some(comma), Some(dot)) if dot > comma => cleaned.replace(',', ""),
+        (Some(_), Some(_)) => cleaned.replace('.', "").replace(',', "."),
+        _ => cleaned.replace(',', "."),
+    };
+    normalized.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_mnb_csv_with_units() {
+        let csv = "Dátum/ISO;CHF;EUR;JPY\nEgység;1;1;100\n2010.01.04.;139,52;270,84;227,16\n2010.01.05.;139,90;;228,00\n";
+        let series = MnbRateImporter::new().parse_table(&read_csv(csv.as_bytes()).unwrap()).unwrap();
+
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[0].currency.as_deref(), Some("CHF"));
+        assert_eq!(series[0].kind, RateKind::Official);
+        assert_eq!(series[0].values, vec![(date("2010-01-04"), 139.52), (date("2010-01-05"), 139.90)]);
+        assert_eq!(series[1].values.len(), 1);
+        assert!((series[2].values[0].1 - 2.2716).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bank_sheet_and_base_rate() {
+        let rows = read_csv(b"Date,CHF buy,CHF sell\n2008-09-01,148.10,156.30\n").unwrap();
+        let series = MnbRateImporter::new().parse_table(&rows).unwrap();
+        assert_eq!(series[0].kind, RateKind::BankBuy);
+        assert_eq!(series[1].kind, RateKind::BankSell);
+
+        let rows = read_csv("Dátum;Jegybanki alapkamat (%)\n2008.10.22.;11,50\n2008.11.25.;11,00\n".as_bytes()).unwrap();
+        let series = MnbRateImporter::new().parse_table(&rows).unwrap();
+        assert_eq!(series[0].kind, RateKind::BaseRate);
+        assert_eq!(series[0].currency, None);
+        assert_eq!(series[0].values[1], (date("2008-11-25"), 11.0));
+
+        let rows = read_csv("Dátum;BUBOR O/N;BUBOR 1 hó;BUBOR 3 hó;BUBOR 6 hó\n2009.03.02.;9,50;9,80;10,02;10,10\n".as_bytes()).unwrap();
+        let series = MnbRateImporter::new().parse_table(&rows).unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].kind, RateKind::Bubor);
+        assert_eq!(series[0].values, vec![(date("2009-03-02"), 10.02)]);
+    }
+
+    #[test]
+    fn test_source_names_in_header_are_not_currencies() {
+        let rows = read_csv("Dátum;OTP CHF vételi;OTP CHF eladási;MNB EUR;HUF\n2008.09.01.;148,10;156,30;241,20;1\n".as_bytes()).unwrap();
+        let series = MnbRateImporter::new().parse_table(&rows).unwrap();
+
+        let found: Vec<(RateKind, Option<&str>)> = series.iter().map(|s| (s.kind, s.currency.as_deref())).collect();
+        assert_eq!(found, vec![
+            (RateKind::BankBuy, Some("CHF")),
+            (RateKind::BankSell, Some("CHF")),
+            (RateKind::Official, Some("EUR")),
+        ]);
+    }
+
+    #[test]
+    fn test_xlsx_with_serial_dates() {
+        let shared = r#"<sst><si><t>Dátum/ISO</t></si><si><t>CHF</t></si></sst>"#;
+        let sheet = r#"<worksheet><sheetData>
+            <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row>
+            <row r="2"><c r="A2"><v>40182</v></c><c r="B2"><v>139.52</v></c></row>
+        </sheetData></worksheet>"#;
+
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("xl/sharedStrings.xml", options).unwrap();
+        zip.write_all(shared.as_bytes()).unwrap();
+        zip.start_file("xl/worksheets/sheet1.xml", options).unwrap();
+        zip.write_all(sheet.as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let rows = read_xlsx(buffer.get_ref()).unwrap();
+        let series = MnbRateImporter::new().parse_table(&rows).unwrap();
+        assert_eq!(series[0].values, vec![(date("2010-01-04"), 139.52)]);
+    }
+}
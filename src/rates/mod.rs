@@ -0,0 +1,5 @@
+pub mod mnb_import;
+pub mod table;
+
+pub use mnb_import::*;
+pub use table::*;
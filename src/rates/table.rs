@@ -0,0 +1,63 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use crate::models::{RateKind, RateLookup, RateObservation};
+
+/// One rate series held in memory, for calculations that look up many dates.
+#[derive(Debug, Clone)]
+pub struct RateTable {
+    kind: RateKind,
+    values: BTreeMap<NaiveDate, f64>,
+}
+
+impl RateTable {
+    pub fn new(kind: RateKind, values: impl IntoIterator<Item = (NaiveDate, f64)>) -> Self {
+        Self { kind, values: values.into_iter().collect() }
+    }
+
+    pub fn from_observations(kind: RateKind, observations: &[RateObservation]) -> Self {
+        Self::new(kind, observations.iter().map(|o| (o.rate_date, o.value)))
+    }
+
+    pub fn kind(&self) -> RateKind {
+        self.kind
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The value that applies on `date` under the series' fallback rule.
+    pub fn rate_on(&self, date: NaiveDate) -> Option<RateLookup> {
+        let latest = self.values.range(..=date).next_back().map(|(d, v)| (*d, *v));
+        RateLookup::resolve(self.kind, date, latest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_weekend_and_holiday_fallback() {
+        // Friday 2010-12-24 is the last banking day before Christmas
+        let chf = RateTable::new(RateKind::Official, [(date("2010-12-23"), 206.2), (date("2010-12-24"), 207.0), (date("2010-12-28"), 208.5)]);
+
+        let sunday = chf.rate_on(date("2010-12-26")).unwrap();
+        assert_eq!(sunday.rate_date, date("2010-12-24"));
+        assert!(sunday.is_fallback());
+        assert!(!chf.rate_on(date("2010-12-28")).unwrap().is_fallback());
+        assert!(chf.rate_on(date("2010-12-01")).is_none());
+        assert!(chf.rate_on(date("2011-02-01")).is_none(), "a month without data is a gap, not a holiday");
+    }
+
+    #[test]
+    fn test_base_rate_stays_in_force() {
+        let base = RateTable::new(RateKind::BaseRate, [(date("2008-10-22"), 11.5), (date("2008-11-25"), 11.0)]);
+        assert_eq!(base.rate_on(date("2008-11-24")).unwrap().value, 11.5);
+        assert_eq!(base.rate_on(date("2009-06-01")).unwrap().value, 11.0);
+    }
+}
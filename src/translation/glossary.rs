@@ -0,0 +1,254 @@
+use futures_util::future::{self, BoxFuture, FutureExt};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+use super::translator::{Translation, TranslationError, Translator};
+
+/// Glossary shipped with the binary; `LEGAL_GLOSSARY_PATH` points the server at an edited copy.
+const DEFAULT_GLOSSARY: &str = include_str!("../../data/legal_glossary.json");
+
+/// Terms at least this long also match inflected forms ("árfolyamkockázatot")
+const MIN_STEM_CHARS: usize = 4;
+
+/// Case, plural and possessive endings a term may carry, by language. A word
+/// only matches a shorter term when the rest of it is one of these, so
+/// "adósság" (debt) is not "adós" and "vagyoni" is not "vagy".
+fn inflectional_endings(language: &str) -> &'static [&'static str] {
+    match language {
+        "hu" => &[
+            "t", "ot", "et", "öt", "at", "át", "ét",
+            "k", "ok", "ek", "ök", "ak", "okat", "eket", "akat", "öket",
+            "a", "e", "á", "é", "ja", "je", "ai", "ei", "ait", "eit", "ját", "jét",
+            "nak", "nek", "ának", "ének", "jának", "jének",
+            "ban", "ben", "ában", "ében", "ba", "be", "ába", "ébe",
+            "ból", "ből", "ából", "éből", "ról", "ről", "áról", "éről",
+            "ra", "re", "ára", "ére", "on", "en", "ön", "án", "én",
+            "tól", "től", "ától", "étől", "hoz", "hez", "höz", "ához", "éhez",
+            "nál", "nél", "ánál", "énél", "val", "vel", "ával", "ével",
+            "ért", "áért", "éért", "ig", "áig", "éig", "ként",
+        ],
+        "pl" => &[
+            "a", "u", "y", "i", "e", "o", "ą", "ę", "owi", "em", "ie", "om", "ami", "ach", "ów",
+            "ego", "emu", "ej", "ym", "ych", "ymi",
+        ],
+        "cs" => &[
+            "a", "u", "e", "ě", "y", "i", "í", "é", "em", "ou", "ům", "ech", "ami",
+            "ího", "ímu", "ím", "ých", "ými", "ého", "ému", "ém",
+        ],
+        "ro" => &["ul", "ului", "ui", "a", "ei", "le", "lor", "i", "ii", "e", "ele", "elor"],
+        _ => &[],
+    }
+}
+
+struct Language {
+    entries: Vec<GlossaryEntry>, // longest first
+    endings: &'static [&'static str],
+}
+
+struct GlossaryEntry {
+    source: Vec<char>, // lowercase
+    english: String,
+    words: usize,
+}
+
+/// Offline phrase-table translator. Known legal phrases and terms are replaced
+/// by their English equivalents, longest match first; words the glossary does
+/// not cover are kept in the original language, and `coverage` reports how
+/// much of the clause was rendered. The result is a working gloss for
+/// co-counsel, not a certified translation.
+pub struct GlossaryTranslator {
+    languages: HashMap<String, Language>,
+}
+
+impl GlossaryTranslator {
+    /// Translator over the glossary bundled at build time.
+    pub fn new() -> Self {
+        let glossary = serde_json::from_str(DEFAULT_GLOSSARY).expect("bundled glossary is valid JSON");
+        Self::from_glossary(glossary)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let glossary = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(Self::from_glossary(glossary))
+    }
+
+    /// `glossary` maps language codes to source phrase → English phrase tables.
+    pub fn from_glossary(glossary: HashMap<String, HashMap<String, String>>) -> Self {
+        let languages = glossary.into_iter()
+            .map(|(language, phrases)| {
+                let mut entries: Vec<GlossaryEntry> = phrases.into_iter()
+                    .map(|(source, english)| GlossaryEntry {
+                        words: source.split_whitespace().count(),
+                        source: source.to_lowercase().chars().collect(),
+                        english,
+                    })
+                    .collect();
+                entries.sort_by_key(|entry| Reverse(entry.source.len()));
+                let endings = inflectional_endings(&language);
+                (language, Language { entries, endings })
+            })
+            .collect();
+
+        Self { languages }
+    }
+
+    pub fn term_count(&self, language: &str) -> usize {
+        self.languages.get(language).map_or(0, |l| l.entries.len())
+    }
+
+    /// Glossary terms occurring in `text`, as (source, English) pairs.
+    pub fn terms_in(&self, text: &str, language: &str) -> Vec<(String, String)> {
+        let lower: Vec<char> = text.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ").chars().collect();
+        let Some(language) = self.languages.get(language) else {
+            return Vec::new();
+        };
+        language.entries.iter()
+            .filter(|e| e.source.iter().filter(|c| c.is_alphabetic()).count() >= MIN_STEM_CHARS)
+            .filter(|e| (0..lower.len()).any(|i| (i == 0 || !lower[i - 1].is_alphabetic()) && match_entry(e, language.endings, &lower, i).is_some()))
+            .map(|e| (e.source.iter().collect(), e.english.clone()))
+            .collect()
+    }
+}
+
+/// Renders `text` with a language's glossary entries; returns the text and
+/// the share of its words that were translated.
+fn render(language: &Language, text: &str) -> (String, f32) {
+    // Line breaks are kept (clause points, list items); only runs of spaces
+    // within a line collapse. Terms still match across a wrapped line.
+    let chars: Vec<char> = text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .chars()
+        .collect();
+    let lower: Vec<char> = chars.iter()
+        .map(|&c| if c == '\n' { ' ' } else { c.to_lowercase().next().unwrap_or(c) })
+        .collect();
+
+    let mut output = String::new();
+    let (mut total_words, mut translated_words) = (0, 0);
+    let mut i = 0;
+    while i < chars.len() {
+        let at_word_start = chars[i].is_alphabetic() && (i == 0 || !chars[i - 1].is_alphabetic());
+        if !at_word_start {
+            output.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        if let Some((entry, end)) = language.entries.iter().find_map(|e| match_entry(e, language.endings, &lower, i).map(|end| (e, end))) {
+            output.push_str(&match_case(&entry.english, chars[i]));
+            total_words += entry.words;
+            translated_words += entry.words;
+            i = end;
+        } else {
+            let end = (i..chars.len()).find(|&j| !chars[j].is_alphabetic()).unwrap_or(chars.len());
+            output.extend(&chars[i..end]);
+            total_words += 1;
+            i = end;
+        }
+    }
+
+    let coverage = if total_words == 0 { 0.0 } else { translated_words as f32 / total_words as f32 };
+    (output, coverage)
+}
+
+/// End of the match of `entry` at `at`, including any inflectional ending.
+fn match_entry(entry: &GlossaryEntry, endings: &[&str], lower: &[char], at: usize) -> Option<usize> {
+    let end = at + entry.source.len();
+    if end > lower.len() || lower[at..end] != entry.source[..] {
+        return None;
+    }
+
+    let word_end = (end..lower.len()).find(|&j| !lower[j].is_alphabetic()).unwrap_or(lower.len());
+    let suffix: String = lower[end..word_end].iter().collect();
+    let last_word_chars = entry.source.iter().rev().take_while(|c| c.is_alphabetic()).count();
+    if suffix.is_empty() || (last_word_chars >= MIN_STEM_CHARS && endings.contains(&suffix.as_str())) {
+        Some(word_end)
+    } else {
+        None
+    }
+}
+
+/// Capitalizes the English phrase when the source started a sentence or a name.
+fn match_case(english: &str, source_first: char) -> String {
+    let mut chars = english.chars();
+    match chars.next() {
+        Some(first) if source_first.is_uppercase() => first.to_uppercase().chain(chars).collect(),
+        _ => english.to_string(),
+    }
+}
+
+impl Translator for GlossaryTranslator {
+    fn method(&self) -> String {
+        "glossary".to_string()
+    }
+
+    fn supports(&self, language: &str) -> bool {
+        self.languages.contains_key(language)
+    }
+
+    fn translate<'a>(&'a self, text: &'a str, source_language: &'a str) -> BoxFuture<'a, Result<Translation, TranslationError>> {
+        let result = match self.languages.get(source_language) {
+            Some(language) => {
+                let (rendered, coverage) = render(language, text);
+                Ok(Translation::new(rendered, source_language, self.method(), coverage))
+            },
+            None => Err(TranslationError::UnsupportedLanguage(source_language.to_string())),
+        };
+        future::ready(result).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_glossary_renders_terms_and_inflections() {
+        let translator = GlossaryTranslator::new();
+        let translation = translator
+            .translate("Az Adós tudomásul veszi, hogy az árfolyamkockázatot az Adós viseli.", "hu")
+            .await
+            .unwrap();
+
+        assert_eq!(translation.text, "The Borrower acknowledges that the exchange rate risk the Borrower viseli.");
+        assert_eq!(translation.method, "glossary");
+        assert!(translation.coverage > 0.8 && translation.coverage < 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_line_breaks_are_kept() {
+        let translation = GlossaryTranslator::new()
+            .translate("1. Az Adós   tudomásul veszi\n2. A  felmondás", "hu")
+            .await
+            .unwrap();
+        assert_eq!(translation.text, "1. The Borrower acknowledges\n2. The termination");
+    }
+
+    #[tokio::test]
+    async fn test_short_terms_need_whole_words() {
+        let translation = GlossaryTranslator::new().translate("A díjmentes felmondás.", "hu").await.unwrap();
+        assert_eq!(translation.text, "The díjmentes termination.");
+    }
+
+    #[tokio::test]
+    async fn test_derived_words_are_not_inflections() {
+        let translator = GlossaryTranslator::new();
+        let cases = [
+            ("Az adósság összege a vagyoni biztosíték.", "The adósság összege the vagyoni biztosíték."),
+            ("hogyan", "hogyan"),
+            ("A kölcsönszerződésben az Adóst terheli.", "The loan agreement the Borrower terheli."),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(translator.translate(source, "hu").await.unwrap().text, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_language_is_an_error() {
+        let result = GlossaryTranslator::new().translate("Der Kreditnehmer trägt das Risiko.", "de").await;
+        assert!(matches!(result, Err(TranslationError::UnsupportedLanguage(_))));
+    }
+}
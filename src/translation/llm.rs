@@ -0,0 +1,43 @@
+use futures_util::future::{BoxFuture, FutureExt};
+use crate::ai::MistralClient;
+use super::glossary::GlossaryTranslator;
+use super::translator::{Translation, TranslationError, Translator};
+
+/// Translation through the Mistral API. The glossary terms found in a clause
+/// are sent along as fixed renderings, so LLM output and glossary output use
+/// the same terminology. Needs `MISTRAL_API_KEY`.
+pub struct LlmTranslator {
+    client: MistralClient,
+    glossary: GlossaryTranslator,
+}
+
+impl LlmTranslator {
+    pub fn new(glossary: GlossaryTranslator) -> Result<Self, TranslationError> {
+        let client = MistralClient::new().map_err(|e| TranslationError::Unavailable("llm".to_string(), e.to_string()))?;
+        Ok(Self { client, glossary })
+    }
+}
+
+impl Translator for LlmTranslator {
+    fn method(&self) -> String {
+        format!("llm:{}", self.client.small_model())
+    }
+
+    fn supports(&self, language: &str) -> bool {
+        language != "en"
+    }
+
+    fn translate<'a>(&'a self, text: &'a str, source_language: &'a str) -> BoxFuture<'a, Result<Translation, TranslationError>> {
+        async move {
+            if !self.supports(source_language) {
+                return Err(TranslationError::UnsupportedLanguage(source_language.to_string()));
+            }
+
+            let terms = self.glossary.terms_in(text, source_language);
+            let english = self.client.translate_clause(text, source_language, &terms).await
+                .map_err(|e| TranslationError::Failed(e.to_string()))?;
+            Ok(Translation::new(english, source_language, self.method(), 1.0))
+        }
+        .boxed()
+    }
+}
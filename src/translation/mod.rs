@@ -0,0 +1,7 @@
+pub mod translator;
+pub mod glossary;
+pub mod llm;
+
+pub use translator::*;
+pub use glossary::*;
+pub use llm::*;
\ No newline at end of file
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use crate::models::TranslationRecord;
+
+/// A pluggable backend rendering clause text into English. Backends report
+/// languages they cannot handle as errors instead of echoing the source text.
+pub trait Translator: Send + Sync {
+    /// Stored with every translation, e.g. "glossary" or "llm:mistral-small-latest".
+    fn method(&self) -> String;
+
+    fn supports(&self, language: &str) -> bool;
+
+    /// Translates `text` from `source_language` (ISO 639-1) into English.
+    fn translate<'a>(&'a self, text: &'a str, source_language: &'a str) -> BoxFuture<'a, Result<Translation, TranslationError>>;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TranslationError {
+    #[error("Translation from '{0}' is not supported by this translator")]
+    UnsupportedLanguage(String),
+    #[error("Translator '{0}' is not available: {1}")]
+    Unavailable(String, String),
+    #[error("Translation failed: {0}")]
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Translation {
+    pub text: String,
+    pub source_language: String,
+    pub method: String,
+    pub translated_at: DateTime<Utc>,
+    pub coverage: f32, // share of source words the method rendered in English; 1.0 for full translators
+}
+
+impl Translation {
+    pub fn new(text: String, source_language: &str, method: String, coverage: f32) -> Self {
+        Self {
+            text,
+            source_language: source_language.to_string(),
+            method,
+            translated_at: Utc::now(),
+            coverage,
+        }
+    }
+
+    /// Provenance stored next to the clause's `english_translation`.
+    pub fn record(&self) -> TranslationRecord {
+        TranslationRecord {
+            method: self.method.clone(),
+            translated_at: self.translated_at,
+            coverage: self.coverage,
+        }
+    }
+}